@@ -24,11 +24,40 @@ use crate::{
     MyType, SpekiPaths,
 };
 
+use crate::utils::cloze::ClozeConfig;
+use crate::utils::duplicates::DuplicatesConfig;
+use crate::utils::graphics::{Graphics, ImageConfig};
+use crate::utils::leech::LeechConfig;
+use crate::utils::limits::LimitsConfig;
+use crate::utils::llm::LlmConfig;
+use crate::utils::ordering::ReviewConfig;
+use crate::utils::tts::TtsConfig;
+use cpal::traits::{DeviceTrait, HostTrait};
 use serde_derive::Deserialize;
+use std::cell::RefCell;
 
 #[derive(Deserialize)]
 pub struct Config {
+    /// Older name for the api key of `[llm]`.
     pub gptkey: Option<String>,
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    #[serde(default)]
+    pub review: ReviewConfig,
+    #[serde(default)]
+    pub leech: LeechConfig,
+    #[serde(default)]
+    pub cloze: ClozeConfig,
+    #[serde(default)]
+    pub images: ImageConfig,
+    #[serde(default)]
+    pub tts: TtsConfig,
+    #[serde(default)]
+    pub audio: AudioConfig,
+    #[serde(default)]
+    pub llm: LlmConfig,
+    #[serde(default)]
+    pub duplicates: DuplicatesConfig,
 }
 
 use toml;
@@ -41,6 +70,34 @@ impl Config {
     }
 }
 
+/// Set under `[audio]` in the config file.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AudioConfig {
+    /// Starts speki without sound, it can still be turned on with Alt+m.
+    pub enabled: bool,
+    pub volume: f32,
+    /// Name of the output device, the default one is used when it's empty or not found.
+    pub device: String,
+    pub autoplay_front: bool,
+    pub autoplay_back: bool,
+    /// Replays the audio of the card in review together with Alt.
+    pub replay_key: char,
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            volume: 0.2,
+            device: String::new(),
+            autoplay_front: true,
+            autoplay_back: true,
+            replay_key: 'a',
+        }
+    }
+}
+
 pub struct Audio {
     pub source: rodio::OutputStream,
     pub handle: rodio::OutputStreamHandle,
@@ -48,13 +105,27 @@ pub struct Audio {
 }
 
 impl Audio {
-    pub fn new() -> Option<Self> {
-        let (source, handle) = rodio::OutputStream::try_default().unwrap();
-        let volume = 0.2;
+    /// None when there's no output device to play on, like on a headless machine.
+    pub fn new(config: &AudioConfig) -> Option<Self> {
+        let device = match config.device.as_str() {
+            "" => None,
+            name => cpal::default_host()
+                .output_devices()
+                .ok()
+                .and_then(|mut devices| {
+                    devices.find(|device| device.name().is_ok_and(|device| device == name))
+                }),
+        };
+        let (source, handle) = match device {
+            Some(device) => rodio::OutputStream::try_from_device(&device)
+                .or_else(|_| rodio::OutputStream::try_default())
+                .ok()?,
+            None => rodio::OutputStream::try_default().ok()?,
+        };
         Some(Audio {
             source,
             handle,
-            volume,
+            volume: config.volume.clamp(0., 1.),
         })
     }
 }
@@ -64,6 +135,7 @@ pub struct AppData {
     pub audio: Option<Audio>,
     pub paths: SpekiPaths,
     pub config: Config,
+    pub graphics: RefCell<Graphics>,
 }
 
 pub struct TabsState {
@@ -170,12 +242,18 @@ impl App {
             Connection::open(&paths.database).expect("Failed to connect to database."),
         ));
         let config = Config::new(&paths);
-        let audio = Audio::new();
+        let audio = if config.audio.enabled {
+            Audio::new(&config.audio)
+        } else {
+            None
+        };
+        let graphics = RefCell::new(Graphics::new(config.images.protocol));
         let appdata = AppData {
             conn,
             audio,
             config,
             paths,
+            graphics,
         };
         let tabs = TabsState::new(&appdata);
 
@@ -213,7 +291,7 @@ impl App {
                 if self.appdata.audio.is_some() {
                     self.appdata.audio = None;
                 } else {
-                    self.appdata.audio = Audio::new();
+                    self.appdata.audio = Audio::new(&self.appdata.config.audio);
                 }
             }
             key => self.tabs.keyhandler(&self.appdata, key),
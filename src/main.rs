@@ -83,6 +83,10 @@ use std::time::Duration;
 fn run_app(terminal: &mut Terminal<MyType>, mut app: App) -> io::Result<()> {
     loop {
         terminal.draw(|f| app.render(f))?;
+        app.appdata
+            .graphics
+            .borrow_mut()
+            .flush(terminal.backend_mut())?;
 
         if poll(Duration::from_millis(100))? {
             let event = event::read()?;
@@ -369,6 +369,7 @@ pub async fn download_deck(
         let _ = transmitter.try_send(ImportProgress {
             curr_index: new as usize,
             max: total_size as usize,
+            error: None,
         });
     }
 }
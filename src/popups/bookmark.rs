@@ -0,0 +1,69 @@
+use tui::layout::{Constraint, Rect};
+use tui::widgets::Borders;
+use tui::Frame;
+
+use crate::app::{AppData, PopUpState, Tab, TabData, Widget};
+use crate::utils::aliases::*;
+use crate::utils::area::{abs_centered, split_updown};
+use crate::utils::sql::insert::new_bookmark;
+use crate::widgets::infobox::InfoBox;
+use crate::widgets::textinput::Field;
+use crate::{MyKey, MyType};
+
+/// Asks for the name of a new bookmark at the given offset of a text.
+pub struct NameBookmark<'a> {
+    name: Field,
+    prompt: InfoBox<'a>,
+    id: IncID,
+    offset: usize,
+    tabdata: TabData,
+}
+
+impl<'a> NameBookmark<'a> {
+    pub fn new(id: IncID, offset: usize, name: String) -> Self {
+        let mut field = Field::new("Name".to_string());
+        field.replace_text(name);
+        Self {
+            name: field,
+            prompt: InfoBox::new("Name the bookmark, Enter to save".to_string())
+                .borders(Borders::NONE),
+            id,
+            offset,
+            tabdata: TabData::new("New bookmark".to_string()),
+        }
+    }
+}
+
+impl<'a> Tab for NameBookmark<'a> {
+    fn get_tabdata(&mut self) -> &mut TabData {
+        &mut self.tabdata
+    }
+
+    fn keyhandler(&mut self, appdata: &AppData, key: MyKey, cursor: &Pos) {
+        match key {
+            MyKey::Enter => {
+                let name = self.name.return_text();
+                let name = name.trim();
+                if !name.is_empty() {
+                    new_bookmark(&appdata.conn, self.id, name, self.offset).unwrap();
+                    self.tabdata.state = PopUpState::Exit;
+                }
+            }
+            key if self.name.is_selected(cursor) => self.name.keyhandler(appdata, key),
+            _ => {}
+        }
+    }
+
+    fn set_selection(&mut self, area: Rect) {
+        let area = abs_centered(area, 50, 6);
+        let chunks = split_updown([Constraint::Length(2), Constraint::Length(3)], area);
+        self.prompt.set_area(chunks[0]);
+        self.name.set_area(chunks[1]);
+        self.tabdata.view.areas.push(chunks[1]);
+    }
+
+    fn render(&mut self, f: &mut Frame<MyType>, appdata: &AppData, cursor: &Pos) {
+        self.prompt.render(f, appdata, cursor);
+        self.name.render(f, appdata, cursor);
+    }
+}
@@ -2,34 +2,74 @@ use std::path::PathBuf;
 
 use crate::{
     app::{AppData, PopUpState, Tab, TabData, Widget},
+    popups::menu::Menu,
     utils::{
-        aliases::TopicID,
+        aliases::{IncID, TopicID},
         area::{split_leftright_by_percent, split_updown_by_percent},
-        epub::load_book,
-        sql::insert::new_incread,
+        epub::{load_book, Book, Chapter},
+        incread::Provenance,
+        pdf::{first_page, load_pdf},
+        sql::insert::{new_incread, set_provenance},
+        sql::update::set_inc_page,
         statelist::StatefulList,
+        textimport::load_document,
     },
     widgets::{button::Button, checkboxlist::CheckItem, textinput::Field, topics::TopicList},
 };
 
 pub struct ChapterSelect<'a> {
-    chapters: StatefulList<CheckItem<String>>,
+    chapters: StatefulList<CheckItem<Chapter>>,
     text: Field,
     topic: TopicList,
     import_button: Button<'a>,
     desc: String,
+    provenance: Provenance,
     tabdata: TabData,
 }
 
 impl<'a> ChapterSelect<'a> {
-    pub fn new(appdata: &AppData, path: &PathBuf, _topic: TopicID) -> Self {
-        let (desc, chapters) = load_book(appdata, path);
+    pub fn new(appdata: &AppData, path: &PathBuf, topic: TopicID) -> Self {
+        let book = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("epub") => load_book(appdata, path),
+            Some("pdf") => load_pdf(path),
+            _ => load_document(path),
+        };
+        Self::from_book(appdata, book, topic)
+    }
+
+    /// The book goes in the given topic unless another one is picked. Chapters that couldn't be
+    /// loaded are listed in a warning.
+    pub fn from_book(appdata: &AppData, book: Book, topic: TopicID) -> Self {
+        let Book {
+            desc,
+            chapters,
+            skipped,
+            provenance,
+        } = book;
         let mut chapters = StatefulList::with_items("Chapters", CheckItem::new_true_vec(chapters));
-        chapters.state.select(Some(0));
-        let text = Field::new_with_text(chapters.items[0].item.clone(), 0, 0);
-        let topic = TopicList::new(&appdata.conn);
-        let import_button = Button::new("import book");
-        let tabdata = TabData::new("Select chapters");
+        let text = match chapters.items.first() {
+            Some(chapter) => {
+                chapters.state.select(Some(0));
+                Field::new_with_text(chapter.item.text.clone(), 0, 0)
+            }
+            None => Field::new_with_text(desc.clone(), 0, 0),
+        };
+        let topic = {
+            let mut list = TopicList::new(&appdata.conn);
+            if let Some(idx) = list.items.iter().position(|item| item.id == topic) {
+                list.state.select(Some(idx));
+            }
+            list
+        };
+        let import_button = Button::new("import");
+        let mut tabdata = TabData::new("Select chapters");
+        if !skipped.is_empty() {
+            let prompt = format!(
+                "Couldn't parse these chapters, they were skipped:\n\n{}",
+                skipped.join("\n")
+            );
+            tabdata.popup = Some(Box::new(Menu::new("Warning".to_string(), prompt, 4, 4, [])));
+        }
 
         Self {
             chapters,
@@ -37,16 +77,33 @@ impl<'a> ChapterSelect<'a> {
             topic,
             import_button,
             desc,
+            provenance,
             tabdata,
         }
     }
 
     fn import(&mut self, appdata: &AppData) {
         let topic = self.topic.get_selected_id().unwrap();
-        let parent = new_incread(&appdata.conn, 0, topic, self.desc.clone(), false);
-        let chapters = self.chapters.get_selected();
-        for chapter in &chapters {
-            new_incread(&appdata.conn, parent, topic, chapter.clone(), true);
+        if let Some(index) = self.chapters.state.selected() {
+            self.chapters.items[index].item.text = self.text.return_text();
+        }
+        let book = new_incread(&appdata.conn, 0, topic, self.desc.clone(), false);
+        set_provenance(&appdata.conn, book, &self.provenance).unwrap();
+        // Chapters are nested under the closest selected chapter above them in the table of contents.
+        let mut parents: Vec<(usize, IncID)> = vec![];
+        for chapter in self.chapters.get_selected() {
+            while parents
+                .last()
+                .is_some_and(|(depth, _)| *depth >= chapter.depth)
+            {
+                parents.pop();
+            }
+            let parent = parents.last().map_or(book, |(_, id)| *id);
+            let id = new_incread(&appdata.conn, parent, topic, chapter.text.clone(), true);
+            if let Some(page) = first_page(&chapter.text) {
+                set_inc_page(&appdata.conn, id, Some(page)).unwrap();
+            }
+            parents.push((chapter.depth, id));
         }
         self.tabdata.state = PopUpState::Exit;
     }
@@ -60,7 +117,7 @@ impl<'a> Tab for ChapterSelect<'a> {
         cursor: &crate::utils::aliases::Pos,
     ) {
         use crate::MyKey::*;
-        let index = self.chapters.state.selected().unwrap();
+        let index = self.chapters.state.selected();
         match key {
             key if self.chapters.is_selected(cursor) => self.chapters.keyhandler(appdata, key),
             key if self.text.is_selected(cursor) => self.text.keyhandler(appdata, key),
@@ -70,11 +127,12 @@ impl<'a> Tab for ChapterSelect<'a> {
             }
             _ => {}
         }
-        let new_index = self.chapters.state.selected().unwrap();
-        if new_index != index {
-            self.chapters.items[index].item = self.text.return_text();
-            self.text
-                .replace_text(self.chapters.items[new_index].item.clone());
+        if let (Some(index), Some(new_index)) = (index, self.chapters.state.selected()) {
+            if new_index != index {
+                self.chapters.items[index].item.text = self.text.return_text();
+                self.text
+                    .replace_text(self.chapters.items[new_index].item.text.clone());
+            }
         }
     }
     fn render(
@@ -135,4 +135,11 @@ impl<'a> Tab for Editor<'a> {
     fn refresh(&mut self, appdata: &AppData) {
         self.card.refresh(appdata);
     }
+
+    fn exit_popup(&mut self, appdata: &AppData) {
+        if let Some(mut popup) = self.tabdata.popup.take() {
+            self.card.popup_closed(appdata, &mut popup);
+        }
+        self.refresh(appdata);
+    }
 }
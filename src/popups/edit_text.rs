@@ -20,6 +20,13 @@ impl<'a> TextEditor<'a> {
             tabdata: TabData::new("Edit text".to_string()),
         }
     }
+
+    /// Opens the text with the cursor at the given character offset.
+    pub fn new_at(appdata: &AppData, id: IncID, offset: usize) -> Self {
+        let mut editor = Self::new(appdata, id);
+        editor.incview.text.source.jump_to_offset(offset);
+        editor
+    }
 }
 
 impl<'a> Tab for TextEditor<'a> {
@@ -34,19 +41,21 @@ impl<'a> Tab for TextEditor<'a> {
 
     fn set_selection(&mut self, area: tui::layout::Rect) {
         let leftright = split_leftright_by_percent([75, 25], area);
-        let rightcol = split_updown_by_percent([20, 25, 25, 30], leftright[1]);
+        let rightcol = split_updown_by_percent([25, 20, 20, 20, 15], leftright[1]);
 
         self.tabdata.view.areas.push(leftright[0]);
         self.tabdata.view.areas.push(rightcol[0]);
         self.tabdata.view.areas.push(rightcol[1]);
         self.tabdata.view.areas.push(rightcol[2]);
         self.tabdata.view.areas.push(rightcol[3]);
+        self.tabdata.view.areas.push(rightcol[4]);
 
         self.incview.text.source.set_area(leftright[0]);
         self.incview.info.set_area(rightcol[0]);
         self.incview.topics.set_area(rightcol[1]);
         self.incview.extracts.set_area(rightcol[2]);
         self.incview.clozes.set_area(rightcol[3]);
+        self.incview.bookmarks.set_area(rightcol[4]);
     }
     fn get_tabdata(&mut self) -> &mut TabData {
         &mut self.tabdata
@@ -56,6 +65,12 @@ impl<'a> Tab for TextEditor<'a> {
             .keyhandler(appdata, &mut self.tabdata, cursor, key);
     }
 
+    fn exit_popup(&mut self, appdata: &AppData) {
+        self.tabdata.popup = None;
+        self.incview.reload_bookmarks(appdata);
+        self.incview.reload_clozes(appdata);
+    }
+
     fn save_state(&mut self, appdata: &AppData) {
         self.incview.save_state(appdata);
     }
@@ -1,6 +1,8 @@
 use tui::widgets::Borders;
 
 use crate::app::AppData;
+use crate::app::PopUpState;
+use crate::app::PopupValue;
 use crate::app::Tab;
 use crate::app::TabData;
 use crate::app::Widget;
@@ -45,6 +47,9 @@ enum FileType {
 pub enum FilePickerPurpose {
     LoadCards,
     LoadBook(TopicID),
+    Occlusion(TopicID),
+    /// Hands the path back to the tab that opened the picker.
+    Media,
 }
 
 pub struct FilePicker<'a> {
@@ -168,6 +173,14 @@ impl<'a> FilePicker<'a> {
                 let popup = ChapterSelect::new(appdata, &path, id);
                 self.set_popup(Box::new(popup));
             }
+            FilePickerPurpose::Occlusion(id) => {
+                let popup = OcclusionEditor::new(path, id);
+                self.set_popup(Box::new(popup));
+            }
+            FilePickerPurpose::Media => {
+                self.tabdata.value = PopupValue::Path(path);
+                self.tabdata.state = PopUpState::Exit;
+            }
         }
     }
 }
@@ -222,3 +235,4 @@ use crate::MyType;
 
 use super::chapter_selection::ChapterSelect;
 use super::load_cards::LoadCards;
+use super::occlusion::OcclusionEditor;
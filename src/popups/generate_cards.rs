@@ -0,0 +1,188 @@
+use tui::layout::{Constraint, Rect};
+use tui::widgets::Borders;
+use tui::Frame;
+
+use crate::app::{AppData, PopUpState, Tab, TabData, Widget};
+use crate::utils::aliases::*;
+use crate::utils::area::{split_leftright_by_percent, split_updown};
+use crate::utils::llm::{parse_cards, CardProposal, Generation, OpenAiChat};
+use crate::utils::statelist::StatefulList;
+use crate::widgets::button::Button;
+use crate::widgets::checkboxlist::CheckItem;
+use crate::widgets::infobox::InfoBox;
+use crate::widgets::textinput::Field;
+use crate::{MyKey, MyType};
+
+/// Lets a language model propose cards about an extract, only the accepted ones are made.
+pub struct CardGenerator<'a> {
+    proposals: StatefulList<CheckItem<CardProposal>>,
+    question: Field,
+    answer: Field,
+    create: Button<'a>,
+    help: InfoBox<'a>,
+    generation: Option<Generation>,
+    source: IncID,
+    topic: TopicID,
+    tabdata: TabData,
+}
+
+impl<'a> CardGenerator<'a> {
+    pub fn new(appdata: &AppData, source: IncID, topic: TopicID, text: &str) -> Self {
+        let config = &appdata.config.llm;
+        let generator = OpenAiChat::new(config, appdata.config.gptkey.as_deref())
+            .max_tokens(config.cards_max_tokens);
+        let generation = Generation::start(Box::new(generator), config.cards_prompt(text));
+        let help = "Accept: Space, reject: Delete, edit the selected card on the right".to_string();
+
+        Self {
+            proposals: StatefulList::new("Generating".to_string()),
+            question: Field::new("Question".to_string()),
+            answer: Field::new("Answer".to_string()),
+            create: Button::new("Create accepted cards"),
+            help: InfoBox::new(help).borders(Borders::NONE),
+            generation: Some(generation),
+            source,
+            topic,
+            tabdata: TabData::new("Generate cards".to_string()),
+        }
+    }
+
+    fn poll_generation(&mut self) {
+        let generation = match &self.generation {
+            Some(generation) => generation,
+            None => return,
+        };
+        let result = match generation.poll() {
+            Some(result) => result,
+            None => {
+                self.proposals.title = format!("{} Generating", generation.spinner());
+                return;
+            }
+        };
+        self.generation = None;
+        match result.and_then(|response| parse_cards(&response)) {
+            Ok(cards) if cards.is_empty() => {
+                self.proposals.title = "No cards were proposed".to_string();
+            }
+            Ok(cards) => {
+                let area = self.proposals.get_area();
+                self.proposals = StatefulList::with_items(
+                    "Proposed cards".to_string(),
+                    CheckItem::new_false_vec(cards),
+                );
+                self.proposals.set_area(area);
+                self.load_selected();
+            }
+            Err(error) => {
+                self.proposals.title = format!("Couldn't generate cards: {}", error);
+            }
+        }
+    }
+
+    /// Shows the selected card in the fields, so that it can be edited.
+    fn load_selected(&mut self) {
+        let (question, answer) = match self.proposals.state.selected() {
+            Some(idx) => {
+                let card = &self.proposals.items[idx].item;
+                (card.question.clone(), card.answer.clone())
+            }
+            None => (String::new(), String::new()),
+        };
+        self.question.replace_text(question);
+        self.answer.replace_text(answer);
+    }
+
+    fn store_selected(&mut self) {
+        if let Some(idx) = self.proposals.state.selected() {
+            let card = &mut self.proposals.items[idx].item;
+            card.question = self.question.return_text();
+            card.answer = self.answer.return_text();
+        }
+    }
+
+    fn create_cards(&mut self, appdata: &AppData) {
+        let accepted = self.proposals.get_selected();
+        if accepted.is_empty() {
+            return;
+        }
+        for card in accepted {
+            card.save(&appdata.conn, self.topic, self.source);
+        }
+        self.tabdata.state = PopUpState::Exit;
+    }
+}
+
+impl<'a> Tab for CardGenerator<'a> {
+    fn get_tabdata(&mut self) -> &mut TabData {
+        &mut self.tabdata
+    }
+
+    fn get_manual(&self) -> String {
+        r#"
+
+The language model set under [llm] in the config proposes cards about the extract.
+Accepted cards are made with the extract as their source, the rest are thrown away.
+
+Accept or unaccept the selected card: Space or Enter (in the list)
+Reject the selected card: Delete (in the list)
+Edit the selected card: in the question and answer fields
+
+        "#
+        .to_string()
+    }
+
+    fn keyhandler(&mut self, appdata: &AppData, key: MyKey, cursor: &Pos) {
+        use MyKey::*;
+        if self.proposals.is_selected(cursor) {
+            match key {
+                Delete => {
+                    self.proposals.take_selected_item();
+                }
+                key => self.proposals.keyhandler(appdata, key),
+            }
+            self.load_selected();
+        } else if self.question.is_selected(cursor) {
+            self.question.keyhandler(appdata, key);
+            self.store_selected();
+        } else if self.answer.is_selected(cursor) {
+            self.answer.keyhandler(appdata, key);
+            self.store_selected();
+        } else if self.create.is_selected(cursor) && key == Enter {
+            self.create_cards(appdata);
+        }
+    }
+
+    fn set_selection(&mut self, area: Rect) {
+        let chunks = split_leftright_by_percent([50, 50], area);
+        let (left, right) = (chunks[0], chunks[1]);
+        let leftchunks = split_updown([Constraint::Min(5), Constraint::Length(1)], left);
+        let rightchunks = split_updown(
+            [
+                Constraint::Percentage(50),
+                Constraint::Min(5),
+                Constraint::Length(3),
+            ],
+            right,
+        );
+
+        self.proposals.set_area(leftchunks[0]);
+        self.help.set_area(leftchunks[1]);
+        self.question.set_area(rightchunks[0]);
+        self.answer.set_area(rightchunks[1]);
+        self.create.set_area(rightchunks[2]);
+
+        self.tabdata.view.areas.push(leftchunks[0]);
+        self.tabdata.view.areas.push(rightchunks[0]);
+        self.tabdata.view.areas.push(rightchunks[1]);
+        self.tabdata.view.areas.push(rightchunks[2]);
+    }
+
+    fn render(&mut self, f: &mut Frame<MyType>, appdata: &AppData, cursor: &Pos) {
+        self.poll_generation();
+        self.proposals.render(f, appdata, cursor);
+        self.help.render(f, appdata, cursor);
+        self.question.render(f, appdata, cursor);
+        self.answer.render(f, appdata, cursor);
+        self.create.render(f, appdata, cursor);
+    }
+}
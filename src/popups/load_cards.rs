@@ -5,6 +5,7 @@ use crate::popups::progress_popup::Progress;
 use crate::utils::aliases::Pos;
 use crate::utils::ankitemplate::{ImportProgress, Template};
 use crate::utils::area::{split_leftright_by_percent, split_updown_by_percent};
+use crate::utils::duplicates::{DuplicatesConfig, ImportDuplicates};
 use crate::utils::statelist::{StatefulList, TextItem};
 use crate::widgets::textinput::Field;
 use crate::widgets::topics::TopicList;
@@ -41,6 +42,9 @@ pub struct LoadCards<'a> {
     topics: TopicList,
     importbutton: Button<'a>,
     previewbutton: Button<'a>,
+    duplicatesbutton: Button<'a>,
+    /// What to do with the cards that are already there.
+    duplicates: ImportDuplicates,
     fields: StatefulList<TextItem>,
     viewpos: usize,
     tabdata: TabData,
@@ -62,6 +66,8 @@ impl<'a> LoadCards<'a> {
             topics: TopicList::new(&appdata.conn),
             importbutton,
             previewbutton,
+            duplicatesbutton: Button::new(String::new()),
+            duplicates: appdata.config.duplicates.import,
             fields,
             viewpos: 0,
             tabdata: TabData::new("Load cards".to_string()),
@@ -85,6 +91,8 @@ impl<'a> LoadCards<'a> {
             topics: TopicList::new(&appdata.conn),
             importbutton,
             previewbutton,
+            duplicatesbutton: Button::new(String::new()),
+            duplicates: appdata.config.duplicates.import,
             fields,
             viewpos: 0,
             tabdata: TabData::new("Load cards".to_string()),
@@ -108,6 +116,8 @@ impl<'a> LoadCards<'a> {
             topics: TopicList::new(&appdata.conn),
             importbutton,
             previewbutton,
+            duplicatesbutton: Button::new(String::new()),
+            duplicates: appdata.config.duplicates.import,
             fields,
             viewpos: 0,
             tabdata: TabData::new("Load cards".to_string()),
@@ -194,7 +204,9 @@ impl<'a> Tab for LoadCards<'a> {
         let rightcol = split_updown_by_percent([50, 50], right);
         let (thetopics, thefields) = (rightcol[0], rightcol[1]);
         let updown = split_updown_by_percent([20, 30, 30, 20], left);
-        let (preview, up, down, button) = (updown[0], updown[1], updown[2], updown[3]);
+        let (preview, up, down, bottom) = (updown[0], updown[1], updown[2], updown[3]);
+        let buttons = split_leftright_by_percent([50, 50], bottom);
+        let (button, duplicates) = (buttons[0], buttons[1]);
 
         let toprow = split_leftright_by_percent([50, 50], up);
         let bottomrow = split_leftright_by_percent([50, 50], down);
@@ -209,6 +221,7 @@ impl<'a> Tab for LoadCards<'a> {
         self.tabdata.view.areas.push(bottomleft);
         self.tabdata.view.areas.push(bottomright);
         self.tabdata.view.areas.push(button);
+        self.tabdata.view.areas.push(duplicates);
         self.tabdata.view.areas.push(thefields);
 
         self.front_template.set_area(topleft);
@@ -217,6 +230,7 @@ impl<'a> Tab for LoadCards<'a> {
         self.back_view.set_area(bottomright);
         self.previewbutton.set_area(preview);
         self.importbutton.set_area(button);
+        self.duplicatesbutton.set_area(duplicates);
         self.topics.set_area(thetopics);
         self.fields.set_area(thefields);
     }
@@ -254,6 +268,9 @@ impl<'a> Tab for LoadCards<'a> {
         self.front_view.render(f, appdata, cursor);
         self.back_view.render(f, appdata, cursor);
         self.importbutton.render(f, appdata, cursor);
+        self.duplicatesbutton
+            .change_text(format!("Cards that already exist: {}", self.duplicates));
+        self.duplicatesbutton.render(f, appdata, cursor);
     }
 
     fn keyhandler(&mut self, appdata: &AppData, key: MyKey, cursor: &Pos) {
@@ -292,13 +309,20 @@ impl<'a> Tab for LoadCards<'a> {
                 ) = std::sync::mpsc::sync_channel(5);
                 let connclone = Arc::clone(&appdata.conn);
                 let topic = self.topics.get_selected_id().unwrap();
+                let duplicates = DuplicatesConfig {
+                    import: self.duplicates,
+                    ..appdata.config.duplicates.clone()
+                };
                 std::thread::spawn(move || {
-                    tmpclone.import_cards(connclone, tx, topic);
+                    tmpclone.import_cards(connclone, tx, topic, duplicates);
                 });
                 //let max = self.template.cards.len() as u32;
                 let prog = Progress::new(rx, "Importing cards".to_string(), None);
                 self.set_popup(Box::new(prog));
             }
+            Enter | KeyPress(_) if self.duplicatesbutton.is_selected(cursor) => {
+                self.duplicates = self.duplicates.next();
+            }
             key if self.front_template.is_selected(cursor) => {
                 self.front_template.keyhandler(appdata, key);
                 self.update_template(self.viewpos);
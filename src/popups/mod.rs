@@ -1,14 +1,19 @@
 pub mod anki_users;
 pub mod ankimporter;
+pub mod bookmark;
 pub mod chapter_selection;
 pub mod edit_card;
 pub mod edit_text;
 pub mod filepicker;
 pub mod find_card;
+pub mod generate_cards;
 pub mod load_cards;
 pub mod menu;
 pub mod message_popup;
 pub mod newchild;
+pub mod occlusion;
 pub mod progress_popup;
 pub mod splash_message;
+pub mod split_card;
+pub mod urlimport;
 pub mod wikiselect;
@@ -27,6 +27,8 @@ pub struct AddChildWidget<'a> {
     pub prompt: Button<'a>,
     pub cardview: CardView<'a>,
     pub purpose: Purpose,
+    source_range: Option<(usize, usize)>,
+    page: Option<u32>,
     tabdata: TabData,
 }
 
@@ -43,10 +45,25 @@ impl<'a> AddChildWidget<'a> {
             prompt,
             cardview,
             purpose,
+            source_range: None,
+            page: None,
             tabdata: TabData::new("Add card".to_string()),
         }
     }
 
+    pub fn prefill(mut self, question: String, answer: String) -> Self {
+        self.cardview.question.replace_text(question);
+        self.cardview.answer.replace_text(answer);
+        self
+    }
+
+    /// Where in the source text the card comes from.
+    pub fn source_range(mut self, range: (usize, usize), page: Option<u32>) -> Self {
+        self.source_range = Some(range);
+        self.page = page;
+        self
+    }
+
     fn submit_card(&mut self, conn: &Arc<Mutex<Connection>>, isfinished: bool) {
         let topic = match &self.purpose {
             Purpose::Source(id) => get_topic_of_inc(conn, *id).unwrap(),
@@ -71,7 +88,10 @@ impl<'a> AddChildWidget<'a> {
             .question(question)
             .answer(answer)
             .topic(topic)
-            .source(source);
+            .source(source)
+            .source_range(self.source_range)
+            .page(self.page)
+            .media(self.cardview.media.clone());
 
         match &self.purpose {
             Purpose::Dependent(cid) => {
@@ -92,6 +112,12 @@ impl<'a> Tab for AddChildWidget<'a> {
         &mut self.tabdata
     }
 
+    fn exit_popup(&mut self, appdata: &AppData) {
+        if let Some(mut popup) = self.tabdata.popup.take() {
+            self.cardview.popup_closed(appdata, &mut popup);
+        }
+    }
+
     fn set_selection(&mut self, area: Rect) {
         let chunks = Layout::default()
             .direction(Vertical)
@@ -0,0 +1,219 @@
+use std::path::PathBuf;
+
+use tui::layout::{Constraint, Rect};
+use tui::widgets::Borders;
+use tui::Frame;
+
+use crate::app::{AppData, PopUpState, Tab, TabData, Widget};
+use crate::utils::aliases::*;
+use crate::utils::area::{split_leftright_by_percent, split_updown};
+use crate::utils::image::{Overlay, Region};
+use crate::utils::occlusion::{make_occlusion_cards, Mask, CURSOR_COLOR, MASK_COLOR, TARGET_COLOR};
+use crate::utils::statelist::StatefulList;
+use crate::widgets::button::Button;
+use crate::widgets::image::ImageView;
+use crate::widgets::infobox::InfoBox;
+use crate::widgets::textinput::Field;
+use crate::{MyKey, MyType};
+
+/// How far the cursor moves or grows with each key press, as a fraction of the image.
+const STEP: f32 = 1. / 40.;
+
+/// Draws masks over an image, every mask becomes a card asking what's hidden under it.
+pub struct OcclusionEditor<'a> {
+    image: ImageView,
+    cursor: Region,
+    masks: StatefulList<Mask>,
+    label: Field,
+    question: Field,
+    create: Button<'a>,
+    help: InfoBox<'a>,
+    path: PathBuf,
+    topic: TopicID,
+    tabdata: TabData,
+}
+
+impl<'a> OcclusionEditor<'a> {
+    pub fn new(path: PathBuf, topic: TopicID) -> Self {
+        let mut image = ImageView::new("Image");
+        image.load(Some(path.clone()));
+        let mut question = Field::new("Question".to_string());
+        question.replace_text("What is hidden?".to_string());
+        let help = "Move: hjkl, resize: HJKL, add mask: Enter, remove mask: Delete".to_string();
+
+        let mut editor = Self {
+            image,
+            cursor: Region {
+                x: 0.4,
+                y: 0.4,
+                w: 0.2,
+                h: 0.2,
+            },
+            masks: StatefulList::new("Masks".to_string()),
+            label: Field::new("Label".to_string()),
+            question,
+            create: Button::new("Create cards"),
+            help: InfoBox::new(help).borders(Borders::NONE),
+            path,
+            topic,
+            tabdata: TabData::new("Image occlusion".to_string()),
+        };
+        editor.update_overlays();
+        editor
+    }
+
+    fn update_overlays(&mut self) {
+        let selected = self.masks.state.selected();
+        let mut overlays: Vec<Overlay> = self
+            .masks
+            .items
+            .iter()
+            .enumerate()
+            .map(|(idx, mask)| {
+                if Some(idx) == selected {
+                    Overlay::Outline(mask.region, TARGET_COLOR)
+                } else {
+                    Overlay::Fill(mask.region, MASK_COLOR)
+                }
+            })
+            .collect();
+        overlays.push(Overlay::Outline(self.cursor, CURSOR_COLOR));
+        self.image.overlays = overlays;
+    }
+
+    fn move_cursor(&mut self, dx: f32, dy: f32, dw: f32, dh: f32) {
+        let cursor = self.cursor;
+        self.cursor = Region {
+            x: cursor.x + dx,
+            y: cursor.y + dy,
+            w: (cursor.w + dw).max(STEP),
+            h: (cursor.h + dh).max(STEP),
+        }
+        .clamped();
+    }
+
+    fn add_mask(&mut self) {
+        let label = self.label.return_text().trim().to_string();
+        let label = if label.is_empty() {
+            format!("Mask {}", self.masks.items.len() + 1)
+        } else {
+            label
+        };
+        self.masks.push(Mask {
+            region: self.cursor,
+            label,
+        });
+        self.label.replace_text(String::new());
+    }
+
+    fn create_cards(&mut self, appdata: &AppData) {
+        if self.masks.items.is_empty() {
+            return;
+        }
+        match make_occlusion_cards(
+            &appdata.conn,
+            &appdata.paths.media,
+            &self.path,
+            self.masks.items.clone(),
+            self.question.return_text(),
+            self.topic,
+        ) {
+            Ok(_) => self.tabdata.state = PopUpState::Exit,
+            Err(e) => self.help.change_text(format!(
+                "Couldn't copy the image to the media folder: {}",
+                e
+            )),
+        }
+    }
+}
+
+impl<'a> Tab for OcclusionEditor<'a> {
+    fn get_tabdata(&mut self) -> &mut TabData {
+        &mut self.tabdata
+    }
+
+    fn get_manual(&self) -> String {
+        r#"
+
+Draw masks over the image, each of them becomes a card with the label as the answer.
+
+Move the blue box: h/j/k/l or arrow keys
+Make it narrower/wider: H/L
+Make it shorter/taller: K/J
+Add the box as a mask: Enter
+Remove the selected mask: Delete (in the mask list)
+Open the image in the image viewer: Alt+o
+
+        "#
+        .to_string()
+    }
+
+    fn keyhandler(&mut self, appdata: &AppData, key: MyKey, cursor: &Pos) {
+        use MyKey::*;
+        if self.image.is_selected(cursor) {
+            match key {
+                Char('h') | Left => self.move_cursor(-STEP, 0., 0., 0.),
+                Char('l') | Right => self.move_cursor(STEP, 0., 0., 0.),
+                Char('k') | Up => self.move_cursor(0., -STEP, 0., 0.),
+                Char('j') | Down => self.move_cursor(0., STEP, 0., 0.),
+                Char('H') => self.move_cursor(0., 0., -STEP, 0.),
+                Char('L') => self.move_cursor(0., 0., STEP, 0.),
+                Char('K') => self.move_cursor(0., 0., 0., -STEP),
+                Char('J') => self.move_cursor(0., 0., 0., STEP),
+                Enter => self.add_mask(),
+                key => self.image.keyhandler(appdata, key),
+            }
+        } else if self.masks.is_selected(cursor) {
+            match key {
+                Delete => {
+                    self.masks.take_selected_item();
+                }
+                key => self.masks.keyhandler(appdata, key),
+            }
+        } else if self.label.is_selected(cursor) {
+            self.label.keyhandler(appdata, key);
+        } else if self.question.is_selected(cursor) {
+            self.question.keyhandler(appdata, key);
+        } else if self.create.is_selected(cursor) && key == Enter {
+            self.create_cards(appdata);
+        }
+        self.update_overlays();
+    }
+
+    fn set_selection(&mut self, area: Rect) {
+        let chunks = split_leftright_by_percent([70, 30], area);
+        let (left, right) = (chunks[0], chunks[1]);
+        let leftchunks = split_updown([Constraint::Min(5), Constraint::Length(1)], left);
+        let rightchunks = split_updown(
+            [
+                Constraint::Min(5),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+            ],
+            right,
+        );
+
+        self.image.set_area(leftchunks[0]);
+        self.help.set_area(leftchunks[1]);
+        self.masks.set_area(rightchunks[0]);
+        self.label.set_area(rightchunks[1]);
+        self.question.set_area(rightchunks[2]);
+        self.create.set_area(rightchunks[3]);
+
+        self.tabdata.view.areas.push(leftchunks[0]);
+        self.tabdata.view.areas.push(rightchunks[0]);
+        self.tabdata.view.areas.push(rightchunks[1]);
+        self.tabdata.view.areas.push(rightchunks[2]);
+        self.tabdata.view.areas.push(rightchunks[3]);
+    }
+
+    fn render(&mut self, f: &mut Frame<MyType>, appdata: &AppData, cursor: &Pos) {
+        self.image.render(f, appdata, cursor);
+        self.help.render(f, appdata, cursor);
+        self.masks.render(f, appdata, cursor);
+        self.label.render(f, appdata, cursor);
+        self.question.render(f, appdata, cursor);
+        self.create.render(f, appdata, cursor);
+    }
+}
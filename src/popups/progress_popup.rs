@@ -2,6 +2,7 @@ use tui::style::Color;
 
 use crate::{
     app::{PopUpState, PopupValue, Tab, TabData, Widget},
+    popups::menu::Menu,
     utils::{aliases::Pos, ankitemplate::ImportProgress, area::split_updown_by_percent},
     widgets::progress_bar::ProgressBar,
 };
@@ -13,6 +14,7 @@ pub struct Progress {
     tabdata: TabData,
     popupvalue: PopupValue,
     next_tab: Option<Box<dyn Tab>>,
+    errors: Vec<String>,
 }
 
 impl Progress {
@@ -27,6 +29,20 @@ impl Progress {
             tabdata: TabData::new(title),
             popupvalue: PopupValue::None,
             next_tab,
+            errors: vec![],
+        }
+    }
+
+    fn finish(&mut self) {
+        if !self.errors.is_empty() {
+            let prompt = format!("Some of it failed:\n\n{}", self.errors.join("\n"));
+            let menu = Menu::new("Warning".to_string(), prompt, 4, 4, []);
+            self.tabdata.state = PopUpState::Switch(Box::new(menu));
+        } else if let Some(tab) = std::mem::take(&mut self.next_tab) {
+            self.tabdata.state = PopUpState::Switch(tab);
+        } else {
+            self.popupvalue = PopupValue::Ok;
+            self.tabdata.state = PopUpState::Exit;
         }
     }
 }
@@ -43,7 +59,10 @@ impl Tab for Progress {
         appdata: &crate::app::AppData,
         _cursor: &Pos,
     ) {
-        if let Ok(prog) = self.rx.recv() {
+        if let Ok(mut prog) = self.rx.recv() {
+            if let Some(error) = prog.error.take() {
+                self.errors.push(error);
+            }
             let current = prog.curr_index as u32;
             self.bar.current = current;
             self.bar.max = prog.max as u32;
@@ -52,18 +71,10 @@ impl Tab for Progress {
             self.bar.render(f, appdata, &Pos::default());
 
             if prog.curr_index == prog.max - 1 {
-                if let Some(tab) = std::mem::take(&mut self.next_tab) {
-                    self.tabdata.state = PopUpState::Switch(tab);
-                } else {
-                    self.popupvalue = PopupValue::Ok;
-                    self.tabdata.state = PopUpState::Exit;
-                }
+                self.finish();
             }
-        } else if let Some(tab) = std::mem::take(&mut self.next_tab) {
-            self.tabdata.state = PopUpState::Switch(tab);
         } else {
-            self.popupvalue = PopupValue::Ok;
-            self.tabdata.state = PopUpState::Exit;
+            self.finish();
         }
     }
 
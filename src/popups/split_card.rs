@@ -0,0 +1,235 @@
+use tui::layout::{Constraint, Rect};
+use tui::widgets::Borders;
+use tui::Frame;
+
+use crate::app::{AppData, PopUpState, Tab, TabData, Widget};
+use crate::utils::aliases::*;
+use crate::utils::area::{split_leftright_by_percent, split_updown};
+use crate::utils::split::{split_card, SplitPart, SplitReview};
+use crate::utils::sql::fetch::cards::{fetch_card, get_history};
+use crate::utils::statelist::StatefulList;
+use crate::widgets::button::Button;
+use crate::widgets::infobox::InfoBox;
+use crate::widgets::textinput::Field;
+use crate::{MyKey, MyType};
+
+/// Splits an overloaded card into several, each getting the reviews it's given of the original.
+pub struct CardSplitter<'a> {
+    id: CardID,
+    parts: StatefulList<SplitPart>,
+    question: Field,
+    answer: Field,
+    help: InfoBox<'a>,
+    reviews: StatefulList<SplitReview>,
+    summary: bool,
+    summarybutton: Button<'a>,
+    split: Button<'a>,
+    tabdata: TabData,
+}
+
+impl<'a> CardSplitter<'a> {
+    pub fn new(appdata: &AppData, id: CardID) -> Self {
+        let card = fetch_card(&appdata.conn, id);
+        let parts = vec![
+            SplitPart {
+                question: card.question.clone(),
+                answer: card.answer.clone(),
+                ..Default::default()
+            },
+            SplitPart::default(),
+        ];
+        let reviews = get_history(&appdata.conn, id)
+            .into_iter()
+            .map(|review| SplitReview::new(review, parts.len()))
+            .collect();
+        let help = "Add: a, remove: Delete, edit the selected card below".to_string();
+
+        let mut myself = Self {
+            id,
+            parts: StatefulList::with_items("New cards".to_string(), parts),
+            question: Field::new("Question".to_string()),
+            answer: Field::new("Answer".to_string()),
+            help: InfoBox::new(help).borders(Borders::NONE),
+            reviews: StatefulList::with_items("Reviews (1-9: toggle card, a: all)", reviews),
+            summary: false,
+            summarybutton: Button::new(""),
+            split: Button::new("Split card"),
+            tabdata: TabData::new("Split card".to_string()),
+        };
+        myself.update_summarybutton();
+        myself.load_selected();
+        myself
+    }
+
+    fn update_summarybutton(&mut self) {
+        let checkbox = if self.summary { "🗹" } else { "⮽" };
+        self.summarybutton.change_text(format!(
+            "{} Keep the original as a summary depending on the new cards",
+            checkbox
+        ));
+    }
+
+    fn load_selected(&mut self) {
+        let (question, answer) = match self.parts.state.selected() {
+            Some(idx) => {
+                let part = &self.parts.items[idx];
+                (part.question.clone(), part.answer.clone())
+            }
+            None => (String::new(), String::new()),
+        };
+        self.question.replace_text(question);
+        self.answer.replace_text(answer);
+    }
+
+    fn store_selected(&mut self) {
+        if let Some(idx) = self.parts.state.selected() {
+            let part = &mut self.parts.items[idx];
+            part.question = self.question.return_text();
+            part.answer = self.answer.return_text();
+        }
+    }
+
+    fn add_part(&mut self) {
+        self.parts.push(SplitPart::default());
+        for review in self.reviews.items.iter_mut() {
+            review.parts.push(true);
+        }
+        self.parts.state.select(Some(self.parts.items.len() - 1));
+    }
+
+    fn remove_part(&mut self) {
+        if let Some(idx) = self.parts.state.selected() {
+            self.parts.take_selected_item();
+            for review in self.reviews.items.iter_mut() {
+                review.parts.remove(idx);
+            }
+        }
+    }
+
+    fn split_card(&mut self, appdata: &AppData) {
+        let parts: Vec<SplitPart> = self
+            .parts
+            .items
+            .iter()
+            .enumerate()
+            .map(|(idx, part)| SplitPart {
+                reviews: self
+                    .reviews
+                    .items
+                    .iter()
+                    .filter(|review| review.parts[idx])
+                    .map(|review| review.review.date)
+                    .collect(),
+                ..part.clone()
+            })
+            .filter(|part| !part.question.trim().is_empty())
+            .collect();
+        if parts.is_empty() {
+            return;
+        }
+        match split_card(&appdata.conn, self.id, &parts, self.summary) {
+            Ok(_) => self.tabdata.state = PopUpState::Exit,
+            Err(e) => self
+                .help
+                .change_text(format!("Couldn't split the card: {}", e)),
+        }
+    }
+}
+
+impl<'a> Tab for CardSplitter<'a> {
+    fn get_tabdata(&mut self) -> &mut TabData {
+        &mut self.tabdata
+    }
+
+    fn get_manual(&self) -> String {
+        r#"
+
+Splits the card into the new cards on the left, the ones without a question are left out.
+They get the topic, source and dependencies of the original card. Every new card gets a copy
+of the reviews that are given to it on the right, so far all of them are.
+
+Without a summary the original card is deleted and the cards that depended on it depend on the
+new cards instead. With a summary it's kept with the new cards as its dependencies.
+
+Add a new card: a or Insert (in the list)
+Remove the selected card: Delete (in the list)
+Edit the selected card: in the question and answer fields
+Give a review to a card or take it away: 1-9 (in the reviews)
+Give a review to all cards or take it away from all: a (in the reviews)
+
+        "#
+        .to_string()
+    }
+
+    fn keyhandler(&mut self, appdata: &AppData, key: MyKey, cursor: &Pos) {
+        use MyKey::*;
+        if self.parts.is_selected(cursor) {
+            match key {
+                Char('a') | Insert => self.add_part(),
+                Delete => self.remove_part(),
+                key => self.parts.keyhandler(appdata, key),
+            }
+            self.load_selected();
+        } else if self.question.is_selected(cursor) {
+            self.question.keyhandler(appdata, key);
+            self.store_selected();
+        } else if self.answer.is_selected(cursor) {
+            self.answer.keyhandler(appdata, key);
+            self.store_selected();
+        } else if self.reviews.is_selected(cursor) {
+            self.reviews.keyhandler(appdata, key);
+        } else if self.summarybutton.is_selected(cursor) && key == Enter {
+            self.summary ^= true;
+            self.update_summarybutton();
+        } else if self.split.is_selected(cursor) && key == Enter {
+            self.split_card(appdata);
+        }
+    }
+
+    fn set_selection(&mut self, area: Rect) {
+        let chunks = split_leftright_by_percent([50, 50], area);
+        let (left, right) = (chunks[0], chunks[1]);
+        let leftchunks = split_updown(
+            [
+                Constraint::Percentage(30),
+                Constraint::Length(1),
+                Constraint::Percentage(35),
+                Constraint::Min(5),
+            ],
+            left,
+        );
+        let rightchunks = split_updown(
+            [
+                Constraint::Min(5),
+                Constraint::Length(3),
+                Constraint::Length(3),
+            ],
+            right,
+        );
+
+        self.parts.set_area(leftchunks[0]);
+        self.help.set_area(leftchunks[1]);
+        self.question.set_area(leftchunks[2]);
+        self.answer.set_area(leftchunks[3]);
+        self.reviews.set_area(rightchunks[0]);
+        self.summarybutton.set_area(rightchunks[1]);
+        self.split.set_area(rightchunks[2]);
+
+        self.tabdata.view.areas.push(leftchunks[0]);
+        self.tabdata.view.areas.push(leftchunks[2]);
+        self.tabdata.view.areas.push(leftchunks[3]);
+        self.tabdata.view.areas.push(rightchunks[0]);
+        self.tabdata.view.areas.push(rightchunks[1]);
+        self.tabdata.view.areas.push(rightchunks[2]);
+    }
+
+    fn render(&mut self, f: &mut Frame<MyType>, appdata: &AppData, cursor: &Pos) {
+        self.parts.render(f, appdata, cursor);
+        self.help.render(f, appdata, cursor);
+        self.question.render(f, appdata, cursor);
+        self.answer.render(f, appdata, cursor);
+        self.reviews.render(f, appdata, cursor);
+        self.summarybutton.render(f, appdata, cursor);
+        self.split.render(f, appdata, cursor);
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+use tui::layout::{Constraint, Rect};
+use tui::widgets::Borders;
+use tui::Frame;
+
+use crate::app::{AppData, PopUpState, Tab, TabData, Widget};
+use crate::popups::chapter_selection::ChapterSelect;
+use crate::utils::aliases::*;
+use crate::utils::area::{abs_centered, split_updown};
+use crate::utils::webpage::{fetch_webpage, WebPage};
+use crate::utils::wiki::ReqwestClient;
+use crate::widgets::infobox::InfoBox;
+use crate::widgets::textinput::Field;
+use crate::{MyKey, MyType};
+
+pub struct UrlImport<'a> {
+    pub url: Field,
+    prompt: InfoBox<'a>,
+    /// The page being downloaded on another thread, so that a slow server doesn't freeze the ui.
+    loading: Option<Receiver<Result<WebPage, String>>>,
+    topic: TopicID,
+    tabdata: TabData,
+}
+
+impl<'a> UrlImport<'a> {
+    pub fn new(topic: TopicID) -> Self {
+        UrlImport {
+            url: Field::new("Url".to_string()),
+            prompt: InfoBox::new("Enter the address of a web page".to_string())
+                .borders(Borders::NONE),
+            loading: None,
+            topic,
+            tabdata: TabData::new("Import from url".to_string()),
+        }
+    }
+
+    fn open(&mut self) {
+        let url = self.url.return_text();
+        if url.trim().is_empty() || self.loading.is_some() {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let address = url.clone();
+        std::thread::spawn(move || {
+            let page = fetch_webpage(&ReqwestClient, &address).map_err(|e| e.to_string());
+            // Nobody is listening anymore if the popup was closed in the meantime.
+            let _ = sender.send(page);
+        });
+        self.loading = Some(receiver);
+        let prompt = format!("Loading {}...", url.trim());
+        self.prompt = InfoBox::new(prompt).borders(Borders::NONE);
+    }
+
+    fn poll_loading(&mut self, appdata: &AppData) {
+        let result = match &self.loading {
+            Some(receiver) => match receiver.try_recv() {
+                Ok(result) => result,
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => Err("the download stopped".to_string()),
+            },
+            None => return,
+        };
+        self.loading = None;
+        let url = self.url.return_text();
+        match result {
+            Ok(page) if page.text.is_empty() => {
+                let prompt = format!("Couldn't find any text on {}", page.url);
+                self.prompt = InfoBox::new(prompt).borders(Borders::NONE);
+            }
+            Ok(page) => {
+                let select = ChapterSelect::from_book(appdata, page.into_book(), self.topic);
+                self.tabdata.state = PopUpState::Switch(Box::new(select));
+            }
+            Err(e) => {
+                let prompt = format!("Couldn't load \"{}\": {}", url.trim(), e);
+                self.prompt = InfoBox::new(prompt).borders(Borders::NONE);
+            }
+        }
+    }
+}
+
+impl<'a> Tab for UrlImport<'a> {
+    fn get_tabdata(&mut self) -> &mut TabData {
+        &mut self.tabdata
+    }
+
+    fn keyhandler(&mut self, appdata: &AppData, key: MyKey, cursor: &Pos) {
+        match key {
+            MyKey::Enter if self.url.is_selected(cursor) => self.open(),
+            key if self.url.is_selected(cursor) => self.url.keyhandler(appdata, key),
+            _ => {}
+        }
+    }
+
+    fn set_selection(&mut self, area: Rect) {
+        let area = abs_centered(area, 70, 7);
+        let chunks = split_updown([Constraint::Length(3), Constraint::Length(3)], area);
+        self.prompt.set_area(chunks[0]);
+        self.url.set_area(chunks[1]);
+        self.tabdata.view.areas.push(chunks[1]);
+    }
+
+    fn render(&mut self, f: &mut Frame<MyType>, appdata: &AppData, cursor: &Pos) {
+        self.poll_loading(appdata);
+        self.prompt.render(f, appdata, cursor);
+        self.url.render(f, appdata, cursor);
+    }
+}
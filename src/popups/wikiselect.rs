@@ -1,66 +1,208 @@
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
 use tui::layout::{Constraint, Rect};
 use tui::widgets::Borders;
 use tui::Frame;
 
 use crate::app::{AppData, PopUpState, Tab, TabData, Widget};
+use crate::popups::chapter_selection::ChapterSelect;
 use crate::utils::aliases::*;
-use crate::utils::area::{abs_centered, split_updown};
-use crate::utils::sql::insert::new_incread;
+use crate::utils::area::{abs_centered, split_leftright, split_updown};
+use crate::utils::epub::Book;
+use crate::utils::incread::Provenance;
+use crate::utils::statelist::{StatefulList, TextItem};
+use crate::utils::wiki::{wiki_sections, Wiki, WikiPage, WikiResult};
 use crate::widgets::infobox::InfoBox;
 use crate::widgets::textinput::Field;
 use crate::{MyKey, MyType};
 
+/// What the worker thread got back from wikipedia.
+enum Loaded {
+    Results(Vec<String>),
+    Page(WikiPage),
+}
+
+/// A search or page request running on another thread, so that a slow connection doesn't
+/// freeze the ui.
+struct Loading {
+    /// The search query or the title of the page.
+    subject: String,
+    language: String,
+    receiver: Receiver<Result<Loaded, String>>,
+}
+
 pub struct WikiSelect<'a> {
     pub searchbar: Field,
+    language: Field,
+    results: StatefulList<TextItem>,
     prompt: InfoBox<'a>,
     topic: TopicID,
+    loading: Option<Loading>,
     tabdata: TabData,
 }
 
 impl<'a> WikiSelect<'a> {
     pub fn new(id: TopicID) -> Self {
+        let mut language = Field::new("Language".to_string());
+        language.replace_text("en".to_string());
         WikiSelect {
-            searchbar: Field::default(),
+            searchbar: Field::new("Search".to_string()),
+            language,
+            results: StatefulList::with_items("Results", vec![]),
             prompt: InfoBox::new("Search for a wikipedia page".to_string()).borders(Borders::NONE),
             topic: id,
+            loading: None,
             tabdata: TabData::new("Wikipedia selection".to_string()),
         }
     }
+
+    fn set_prompt(&mut self, prompt: String) {
+        self.prompt = InfoBox::new(prompt).borders(Borders::NONE);
+    }
+
+    fn set_results(&mut self, title: &str, results: Vec<String>) {
+        let area = self.results.get_area();
+        let items = results.into_iter().map(TextItem::new).collect();
+        self.results = StatefulList::with_items(title, items);
+        self.results.set_area(area);
+        self.tabdata.view.move_to_area(area);
+    }
+
+    /// Runs the request on another thread, the answer is picked up in `poll_loading`.
+    fn load<F>(&mut self, subject: String, request: F)
+    where
+        F: FnOnce(&Wiki, &str) -> WikiResult<Loaded> + Send + 'static,
+    {
+        if self.loading.is_some() {
+            return;
+        }
+        let language = self.language.return_text();
+        let (sender, receiver) = mpsc::channel();
+        let (thread_language, thread_subject) = (language.clone(), subject.clone());
+        std::thread::spawn(move || {
+            let wiki = Wiki::new(&thread_language);
+            let loaded = request(&wiki, &thread_subject).map_err(|e| e.to_string());
+            // Nobody is listening anymore if the popup was closed in the meantime.
+            let _ = sender.send(loaded);
+        });
+        self.set_prompt(format!("Loading \"{}\"...", subject.trim()));
+        self.loading = Some(Loading {
+            subject,
+            language,
+            receiver,
+        });
+    }
+
+    fn search(&mut self) {
+        let query = self.searchbar.return_text();
+        self.load(query, |wiki, query| wiki.search(query).map(Loaded::Results));
+    }
+
+    fn open(&mut self, title: String) {
+        self.load(title, |wiki, title| wiki.page(title).map(Loaded::Page));
+    }
+
+    fn poll_loading(&mut self, appdata: &AppData) {
+        let result = match &self.loading {
+            Some(loading) => match loading.receiver.try_recv() {
+                Ok(result) => result,
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => Err("the request stopped".to_string()),
+            },
+            None => return,
+        };
+        let Loading {
+            subject, language, ..
+        } = self.loading.take().unwrap();
+        match result {
+            Ok(Loaded::Results(results)) if results.is_empty() => {
+                self.set_prompt(format!("No results for \"{}\"", subject.trim()))
+            }
+            Ok(Loaded::Results(results)) => {
+                self.set_prompt("Pick a page with Enter".to_string());
+                self.set_results("Results", results);
+            }
+            Ok(Loaded::Page(page)) => self.show_page(appdata, &language, &subject, page),
+            Err(e) => self.set_prompt(format!("Couldn't load \"{}\": {}", subject.trim(), e)),
+        }
+    }
+
+    fn show_page(&mut self, appdata: &AppData, language: &str, title: &str, page: WikiPage) {
+        match page {
+            WikiPage::Article { title, text } => {
+                let url = Wiki::new(language).article_url(&title);
+                let book = Book {
+                    desc: format!("{}\n\n{}", title, url),
+                    chapters: wiki_sections(&title, &text),
+                    skipped: vec![],
+                    provenance: Provenance {
+                        origin: url,
+                        title,
+                        author: "Wikipedia contributors".to_string(),
+                    },
+                };
+                let select = ChapterSelect::from_book(appdata, book, self.topic);
+                self.tabdata.state = PopUpState::Switch(Box::new(select));
+            }
+            WikiPage::Disambiguation { title, options } => {
+                self.set_prompt(format!(
+                    "\"{}\" may refer to several pages, pick one",
+                    title
+                ));
+                self.set_results("Disambiguation", options);
+            }
+            WikiPage::Missing => self.set_prompt(format!("\"{}\" doesn't exist", title)),
+        }
+    }
 }
+
 impl<'a> Tab for WikiSelect<'a> {
     fn get_tabdata(&mut self) -> &mut TabData {
         &mut self.tabdata
     }
 
-    fn navigate(&mut self, _dir: crate::NavDir) {}
-
-    fn keyhandler(&mut self, appdata: &AppData, key: MyKey, _cursor: &Pos) {
+    fn keyhandler(&mut self, appdata: &AppData, key: MyKey, cursor: &Pos) {
         match key {
-            MyKey::Enter => {
-                let text = self.searchbar.return_text();
-                let wiki = wikipedia::Wikipedia::<wikipedia::http::default::Client>::default();
-                let page = wiki.page_from_title(text);
-                if let Ok(content) = page.get_content() {
-                    new_incread(&appdata.conn, 0, self.topic, content, true);
-                    self.tabdata.state = PopUpState::Exit;
-                } else {
-                    self.prompt = InfoBox::new("Invalid search result".to_string());
+            MyKey::Enter if self.searchbar.is_selected(cursor) => self.search(),
+            MyKey::Enter if self.results.is_selected(cursor) => {
+                if let Some(idx) = self.results.state.selected() {
+                    let title = self.results.items[idx].to_string();
+                    self.open(title);
                 }
             }
-            key => self.searchbar.keyhandler(appdata, key),
+            key if self.searchbar.is_selected(cursor) => self.searchbar.keyhandler(appdata, key),
+            key if self.language.is_selected(cursor) => self.language.keyhandler(appdata, key),
+            key if self.results.is_selected(cursor) => self.results.keyhandler(appdata, key),
+            _ => {}
         }
     }
+
     fn set_selection(&mut self, area: Rect) {
-        let area = abs_centered(area, 60, 5);
-        let chunks = split_updown([Constraint::Min(1), Constraint::Length(3)], area);
+        let area = abs_centered(area, 70, 25);
+        let chunks = split_updown(
+            [
+                Constraint::Length(2),
+                Constraint::Length(3),
+                Constraint::Min(3),
+            ],
+            area,
+        );
+        let searchrow = split_leftright([Constraint::Min(10), Constraint::Length(12)], chunks[1]);
 
         self.prompt.set_area(chunks[0]);
-        self.searchbar.set_area(chunks[1]);
-        self.tabdata.view.areas.push(chunks[1]);
+        self.searchbar.set_area(searchrow[0]);
+        self.language.set_area(searchrow[1]);
+        self.results.set_area(chunks[2]);
+        self.tabdata.view.areas.push(searchrow[0]);
+        self.tabdata.view.areas.push(searchrow[1]);
+        self.tabdata.view.areas.push(chunks[2]);
     }
 
     fn render(&mut self, f: &mut Frame<MyType>, appdata: &AppData, cursor: &Pos) {
+        self.poll_loading(appdata);
         self.prompt.render(f, appdata, cursor);
         self.searchbar.render(f, appdata, cursor);
+        self.language.render(f, appdata, cursor);
+        self.results.render(f, appdata, cursor);
     }
 }
@@ -2,9 +2,12 @@ use crate::app::AppData;
 use crate::app::Tab;
 use crate::app::TabData;
 use crate::app::Widget;
-use crate::utils::aliases::Pos;
+use crate::popups::filepicker::{FilePicker, FilePickerPurpose};
+use crate::utils::aliases::{CardID, Pos};
 use crate::utils::area::{split_leftright_by_percent, split_updown_by_percent};
 use crate::utils::card::CardView;
+use crate::utils::duplicates::DuplicateIndex;
+use crate::utils::media::IMAGE_EXTENSIONS;
 use crate::widgets::button::Button;
 use crate::{MyKey, MyType};
 use rusqlite::Connection;
@@ -15,6 +18,10 @@ use tui::Frame;
 pub struct NewCard<'a> {
     pub prompt: Button<'a>,
     cardview: CardView<'a>,
+    /// The duplicate that was warned about, submitting again adds the card anyway.
+    warned: Option<CardID>,
+    /// Made on the first submit and kept up to date with the cards added since.
+    duplicates: Option<DuplicateIndex>,
     tabdata: TabData,
 }
 
@@ -27,6 +34,8 @@ impl<'a> NewCard<'a> {
         NewCard {
             prompt: Button::new("Add new card".to_string()),
             cardview,
+            warned: None,
+            duplicates: None,
             tabdata: TabData::new("Add card".to_string()),
         }
     }
@@ -36,8 +45,28 @@ impl<'a> NewCard<'a> {
     }
 
     pub fn submit_card(&mut self, appdata: &AppData, iscompleted: bool) {
+        let question = self.cardview.question.return_text();
+        let answer = self.cardview.answer.return_text();
+        let threshold = appdata.config.duplicates.threshold;
+        let index = self
+            .duplicates
+            .get_or_insert_with(|| DuplicateIndex::new(&appdata.conn, threshold));
+        index.update(&appdata.conn);
+        if let Some(duplicate) = index.find(&question, &answer) {
+            if self.warned != Some(duplicate.id) {
+                self.warned = Some(duplicate.id);
+                self.prompt.change_text(format!(
+                    "Looks like a duplicate of \"{}\" ({:.0}% alike), submit again to add it anyway",
+                    duplicate.question.lines().next().unwrap_or_default(),
+                    duplicate.similarity * 100.
+                ));
+                return;
+            }
+        }
         self.cardview.submit_card(appdata, iscompleted);
         self.cardview = CardView::new(&appdata.conn);
+        self.warned = None;
+        self.prompt.change_text("Add new card".to_string());
     }
 }
 
@@ -46,6 +75,12 @@ impl<'a> Tab for NewCard<'a> {
         &mut self.tabdata
     }
 
+    fn exit_popup(&mut self, appdata: &AppData) {
+        if let Some(mut popup) = self.tabdata.popup.take() {
+            self.cardview.popup_closed(appdata, &mut popup);
+        }
+    }
+
     fn get_manual(&self) -> String {
         r#"
 
@@ -55,6 +90,14 @@ Upper textbox is question, lower is answer.
 
 add card as finished: Alt+f
 Add card as unfinished: Alt+u    
+(cards that look like duplicates are only added when submitted twice)
+Make image occlusion cards from an image: Alt+O
+Attach audio or an image to the selected side: Alt+e
+Remove the media of the selected side: Alt+E
+Play the audio of the selected side: Alt+l
+Read out the selected side with text-to-speech: Alt+v
+Record yourself: Alt+w, keep the recording as the answer audio: Alt+S
+Suggest dependencies with a language model: Alt+D (accept: y, reject: n)
 
         "#
         .to_string()
@@ -63,24 +106,36 @@ Add card as unfinished: Alt+u
     fn set_selection(&mut self, area: Rect) {
         let chunks = split_leftright_by_percent([75, 15], area);
         let left = chunks[0];
-        let right = chunks[1];
+        let right = split_updown_by_percent([50, 50], chunks[1]);
         let chunks = split_updown_by_percent([10, 37, 37], left);
 
         self.tabdata.view.areas.push(chunks[1]);
-        self.tabdata.view.areas.push(right);
+        self.tabdata.view.areas.push(right[0]);
+        self.tabdata.view.areas.push(right[1]);
         self.tabdata.view.areas.push(chunks[0]);
         self.tabdata.view.areas.push(chunks[2]);
 
         self.prompt.set_area(chunks[0]);
         self.cardview.question.set_area(chunks[1]);
         self.cardview.answer.set_area(chunks[2]);
-        self.cardview.topics.set_area(right);
+        self.cardview.topics.set_area(right[0]);
+        self.cardview.dependencies.set_area(right[1]);
     }
 
     fn keyhandler(&mut self, appdata: &AppData, key: MyKey, cursor: &Pos) {
         match key {
             MyKey::Alt('f') => self.submit_card(appdata, true),
             MyKey::Alt('u') => self.submit_card(appdata, false),
+            MyKey::Alt('O') => {
+                if let Some(topic) = self.cardview.topics.get_selected_id() {
+                    let picker = FilePicker::new(
+                        FilePickerPurpose::Occlusion(topic),
+                        "Choose an image to draw masks over".to_string(),
+                        IMAGE_EXTENSIONS.map(String::from),
+                    );
+                    self.tabdata.popup = Some(Box::new(picker));
+                }
+            }
             key if self.cardview.is_selected(cursor) => {
                 self.cardview
                     .keyhandler(appdata, &mut self.tabdata, cursor, key)
@@ -6,11 +6,14 @@ use tui::layout::{Constraint, Direction, Layout, Rect};
 use crate::app::{AppData, TabData, Widget};
 use crate::popups::edit_card::Editor;
 use crate::popups::find_card::{CardPurpose, FindCardWidget};
+use crate::popups::menu::Menu;
 use crate::popups::newchild::{AddChildWidget, Purpose};
+use crate::popups::split_card::CardSplitter;
 use crate::utils::aliases::*;
 use crate::utils::area::{split_leftright, split_updown_by_percent};
 use crate::utils::card::CardItem;
 use crate::utils::card::CardType;
+use crate::utils::duplicates::merge_cards;
 use crate::utils::sql::fetch::{cards::get_highest_pos, cards::is_pending, CardQuery};
 use crate::utils::sql::update::{set_suspended, update_position};
 use crate::utils::statelist::KeyHandler;
@@ -133,6 +136,8 @@ pub struct Browse {
     selected: StatefulList<CardItem>,
     selected_ids: HashSet<CardID>,
     filteractions: StatefulList<ActionItem>,
+    /// The cards a merge was asked for, choosing it again with the same cards merges them.
+    merge_warned: Option<Vec<CardID>>,
     tabdata: TabData,
 }
 
@@ -153,6 +158,7 @@ impl Browse {
                 FilterItem::Numitem(NumItem::new("Min stability".to_string(), None)),
                 FilterItem::Numitem(NumItem::new("Max strength".to_string(), Some(100))),
                 FilterItem::Numitem(NumItem::new("Min strength".to_string(), Some(100))),
+                FilterItem::Optitem(OptItem::new("Leech".to_string())),
             ],
         );
         let selected_ids = HashSet::new();
@@ -169,6 +175,7 @@ impl Browse {
             selected,
             selected_ids,
             filteractions,
+            merge_warned: None,
             tabdata: TabData::new("Browse".to_string()),
         };
 
@@ -242,6 +249,14 @@ impl Browse {
             }
         }
 
+        if let FilterItem::Optitem(val) = &self.filters.items[10] {
+            match val.filter {
+                FilterSetting::TruePass => query = query.leech(true),
+                FilterSetting::FalsePass => query = query.leech(false),
+                _ => {}
+            }
+        }
+
         let items = query
             .fetch_carditems(conn)
             .into_iter()
@@ -281,13 +296,67 @@ impl Browse {
         )
     }
 
+    /// Merges the selected cards into the first one, which keeps its text. The other cards are
+    /// deleted, so it only happens when it's chosen a second time with the same cards selected.
+    fn merge_selected(&mut self, conn: &Arc<Mutex<Connection>>) {
+        let ids: Vec<CardID> = self.selected.items.iter().map(|card| card.id).collect();
+        if ids.len() < 2 {
+            return;
+        }
+        if self.merge_warned.as_ref() != Some(&ids) {
+            self.merge_item().text = format!(
+                "Merge {} cards into \"{}\"? Choose again to confirm",
+                ids.len() - 1,
+                self.selected.items[0]
+                    .question
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+            );
+            self.merge_warned = Some(ids);
+            return;
+        }
+        self.cancel_merge();
+        let (keep, remove) = ids.split_first().unwrap();
+        for id in remove {
+            if let Err(e) = merge_cards(conn, *keep, *id) {
+                let prompt = format!("Couldn't merge the cards: {}", e);
+                self.set_popup(Box::new(Menu::new("Warning".to_string(), prompt, 4, 4, [])));
+                break;
+            }
+            self.selected_ids.remove(id);
+        }
+        let selected_ids = &self.selected_ids;
+        self.selected
+            .items
+            .retain(|card| selected_ids.contains(&card.id));
+        self.selected.state.select(Some(0));
+    }
+
+    fn cancel_merge(&mut self) {
+        self.merge_warned = None;
+        self.merge_item().text = Action::Merge.to_string();
+    }
+
+    fn merge_item(&mut self) -> &mut ActionItem {
+        self.filteractions
+            .items
+            .iter_mut()
+            .find(|item| item.action == Action::Merge)
+            .unwrap()
+    }
+
     fn do_action(&mut self, appdata: &AppData) {
         if let Some(idx) = self.filteractions.state.selected() {
-            match idx {
-                0 => self.clear_selected(&appdata.conn),
-                1 => self.apply_suspended(appdata, true),
-                2 => self.apply_suspended(appdata, false),
-                3 => {
+            let action = self.filteractions.items[idx].action;
+            if action != Action::Merge {
+                self.cancel_merge();
+            }
+            match action {
+                Action::ClearSelected => self.clear_selected(&appdata.conn),
+                Action::Suspend => self.apply_suspended(appdata, true),
+                Action::Unsuspend => self.apply_suspended(appdata, false),
+                Action::NewDependency => {
                     let dependencies = self
                         .selected_ids
                         .clone()
@@ -299,7 +368,7 @@ impl Browse {
                     let addchild = AddChildWidget::new(appdata, Purpose::Dependent(dependencies));
                     self.set_popup(Box::new(addchild));
                 }
-                4 => {
+                Action::OldDependency => {
                     let dependencies = self
                         .selected_ids
                         .clone()
@@ -312,7 +381,7 @@ impl Browse {
                     let cardfinder = FindCardWidget::new(&appdata.conn, purpose);
                     self.set_popup(Box::new(cardfinder));
                 }
-                5 => {
+                Action::NewDependent => {
                     let dependents = self
                         .selected_ids
                         .clone()
@@ -324,7 +393,7 @@ impl Browse {
                     let addchild = AddChildWidget::new(appdata, Purpose::Dependency(dependents));
                     self.set_popup(Box::new(addchild));
                 }
-                6 => {
+                Action::OldDependent => {
                     let dependents = self
                         .selected_ids
                         .clone()
@@ -337,8 +406,16 @@ impl Browse {
                     let cardfinder = FindCardWidget::new(&appdata.conn, purpose);
                     self.set_popup(Box::new(cardfinder));
                 }
-                7 => self.save_pending_queue(&appdata.conn),
-                8 => {
+                Action::Merge => self.merge_selected(&appdata.conn),
+                Action::Split => {
+                    let id = match self.selected.state.selected() {
+                        Some(idx) => self.selected.items[idx].id,
+                        None => return,
+                    };
+                    self.set_popup(Box::new(CardSplitter::new(appdata, id)));
+                }
+                Action::SavePending => self.save_pending_queue(&appdata.conn),
+                Action::View => {
                     let ids: Vec<CardID> =
                         self.selected.items.clone().iter().map(|x| x.id).collect();
 
@@ -348,7 +425,6 @@ impl Browse {
 
                     self.tabdata.popup = Some(Box::new(Editor::new(appdata, ids)));
                 }
-                _ => return,
             }
             self.apply_filter(&appdata.conn);
         }
@@ -360,6 +436,25 @@ impl Tab for Browse {
         &mut self.tabdata
     }
 
+    /// Drops the selected cards that were deleted in a popup, like a card that was split.
+    fn refresh(&mut self, appdata: &AppData) {
+        let existing: HashSet<CardID> = CardQuery::default()
+            .fetch_card_ids(&appdata.conn)
+            .into_iter()
+            .collect();
+        self.selected
+            .items
+            .retain(|card| existing.contains(&card.id));
+        self.selected_ids.retain(|id| existing.contains(id));
+        if self.selected.items.is_empty() {
+            self.selected.state.select(None);
+        } else if let Some(idx) = self.selected.state.selected() {
+            let idx = idx.min(self.selected.items.len() - 1);
+            self.selected.state.select(Some(idx));
+        }
+        self.apply_filter(&appdata.conn);
+    }
+
     fn set_selection(&mut self, area: Rect) {
         let chunks = split_leftright([Constraint::Length(20), Constraint::Percentage(50)], area);
         let filters = Layout::default()
@@ -446,13 +541,69 @@ impl Tab for Browse {
 
 use crate::MyKey;
 
+#[derive(Clone, Copy, PartialEq)]
+enum Action {
+    ClearSelected,
+    Suspend,
+    Unsuspend,
+    NewDependency,
+    OldDependency,
+    NewDependent,
+    OldDependent,
+    Merge,
+    Split,
+    SavePending,
+    View,
+}
+
+impl Action {
+    /// In the order they're listed in.
+    const ALL: [Action; 11] = [
+        Action::ClearSelected,
+        Action::Suspend,
+        Action::Unsuspend,
+        Action::NewDependency,
+        Action::OldDependency,
+        Action::NewDependent,
+        Action::OldDependent,
+        Action::Merge,
+        Action::Split,
+        Action::SavePending,
+        Action::View,
+    ];
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Action::ClearSelected => "Clear selected",
+            Action::Suspend => "Suspend",
+            Action::Unsuspend => "Unsuspend",
+            Action::NewDependency => "Add new dependency",
+            Action::OldDependency => "Add old dependency",
+            Action::NewDependent => "Add new dependent",
+            Action::OldDependent => "Add old dependent",
+            Action::Merge => "Merge into first",
+            Action::Split => "Split card",
+            Action::SavePending => "Save to pending",
+            Action::View => "View cards",
+        };
+        write!(f, "{}", text)
+    }
+}
+
 struct ActionItem {
+    action: Action,
+    /// Usually the name of the action, but merging asks for confirmation here.
     text: String,
 }
 
 impl ActionItem {
-    pub fn new(text: String) -> Self {
-        Self { text }
+    pub fn new(action: Action) -> Self {
+        Self {
+            action,
+            text: action.to_string(),
+        }
     }
 }
 
@@ -466,18 +617,7 @@ impl Display for ActionItem {
 
 impl Default for StatefulList<ActionItem> {
     fn default() -> Self {
-        let actions = vec![
-            "Clear selected".to_string(),
-            "Suspend".to_string(),
-            "Unsuspend".to_string(),
-            "Add new dependency".to_string(),
-            "Add old dependency".to_string(),
-            "Add new dependent".to_string(),
-            "Add old dependent".to_string(),
-            "Save to pending".to_string(),
-            "View cards".to_string(),
-        ];
-        let items = actions.into_iter().map(ActionItem::new).collect();
+        let items = Action::ALL.into_iter().map(ActionItem::new).collect();
         StatefulList::with_items("Actions".to_string(), items)
     }
 }
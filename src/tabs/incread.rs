@@ -6,14 +6,15 @@ use crate::popups::filepicker::FilePicker;
 use crate::popups::filepicker::FilePickerPurpose;
 use crate::popups::menu::Menu;
 use crate::popups::menu::TraitButton;
+use crate::popups::urlimport::UrlImport;
 use crate::popups::wikiselect::WikiSelect;
-use crate::utils::area::split_leftright_by_percent;
+use crate::utils::area::{split_leftright_by_percent, split_updown_by_percent};
 use crate::MyKey;
 use crate::MyType;
 
 use crate::utils::aliases::*;
 use crate::utils::area::take_upper_area;
-use crate::utils::sql::fetch::load_inc_items;
+use crate::utils::sql::fetch::{load_inc_items, load_inc_queue};
 use crate::utils::sql::insert::new_incread;
 use crate::utils::statelist::StatefulList;
 use crate::widgets::button::Button;
@@ -21,11 +22,12 @@ use crate::widgets::topics::TopicList;
 use tui::layout::Rect;
 use tui::Frame;
 
-use crate::utils::incread::IncListItem;
+use crate::utils::incread::{IncListItem, IncQueueItem};
 use std::sync::{Arc, Mutex};
 
 pub struct MainInc<'a> {
     pub inclist: StatefulList<IncListItem>,
+    pub queue: StatefulList<IncQueueItem>,
     pub topics: TopicList,
     import_button: Button<'a>,
     tabdata: TabData,
@@ -37,11 +39,13 @@ impl<'a> MainInc<'a> {
     pub fn new(conn: &Arc<Mutex<Connection>>) -> Self {
         let items = load_inc_items(conn, 1).unwrap();
         let inclist = StatefulList::with_items("Sources".to_string(), items);
+        let queue = StatefulList::with_items("Review queue".to_string(), load_inc_queue(conn));
         let topics = TopicList::new(conn);
         let import_button = Button::new("Import texts".to_string());
 
         MainInc {
             inclist,
+            queue,
             topics,
             tabdata: TabData::new("Incremental reading".to_string()),
             import_button,
@@ -51,6 +55,7 @@ impl<'a> MainInc<'a> {
     pub fn reload_inc_list(&mut self, conn: &Arc<Mutex<Connection>>) {
         let items = load_inc_items(conn, self.topics.get_selected_id().unwrap()).unwrap();
         self.inclist = StatefulList::with_items("Sources".to_string(), items);
+        self.queue = StatefulList::with_items("Review queue".to_string(), load_inc_queue(conn));
     }
 }
 
@@ -62,14 +67,17 @@ impl<'a> crate::app::Tab for MainInc<'a> {
     fn set_selection(&mut self, area: Rect) {
         let mut leftright = split_leftright_by_percent([25, 75], area);
         let button = take_upper_area(&mut leftright[0], 4);
+        let rightcol = split_updown_by_percent([60, 40], leftright[1]);
 
-        self.tabdata.view.areas.push(leftright[1]);
+        self.tabdata.view.areas.push(rightcol[0]);
+        self.tabdata.view.areas.push(rightcol[1]);
         self.tabdata.view.areas.push(button);
         self.tabdata.view.areas.push(leftright[0]);
 
         self.import_button.set_area(button);
         self.topics.set_area(leftright[0]);
-        self.inclist.set_area(leftright[1]);
+        self.inclist.set_area(rightcol[0]);
+        self.queue.set_area(rightcol[1]);
     }
 
     fn get_manual(&self) -> String {
@@ -77,6 +85,7 @@ impl<'a> crate::app::Tab for MainInc<'a> {
 
 Sources are the top level texts with the topic that is currently selected.
 Extracts are the extracts taken from the currently focused text.
+The review queue shows all active texts in the order they'll come up in review.
 You can paste text into the textwidget.
 
 Add wikipedia page: Alt+w
@@ -87,6 +96,11 @@ normal mode -> visual mode: v
 visual mode -> normal mode: Ctrl+c
 make extract (visual mode): Alt+x 
 make cloze (visual mode): Alt+z
+mark another cloze to make along with it (visual mode): Alt+Z
+question/answer card from selection (visual mode): Alt+n
+generate cards from the text or the selection with a language model: Alt+g
+bookmark the cursor position: Alt+b
+mark the section under the cursor as read: Alt+r
 
         "#
         .to_string()
@@ -111,18 +125,23 @@ make cloze (visual mode): Alt+z
                 let b = move |_appdata: &AppData| -> Box<dyn crate::app::Tab> {
                     Box::new(FilePicker::new(
                         FilePickerPurpose::LoadBook(topic),
-                        "Choose an epub file".to_string(),
-                        ["epub".to_string()],
+                        "Choose a file to import (epub, pdf, txt, md or html)".to_string(),
+                        ["epub", "pdf", "txt", "md", "html", "htm"].map(String::from),
                     ))
                 };
                 let c = move |_appdata: &AppData| -> Box<dyn crate::app::Tab> {
                     Box::new(WikiSelect::new(topic))
                 };
 
+                let d = move |_appdata: &AppData| -> Box<dyn crate::app::Tab> {
+                    Box::new(UrlImport::new(topic))
+                };
+
                 let buttons = [
                     TraitButton::new(Box::new(a), "New blank", true),
                     TraitButton::new(Box::new(b), "Load from file", false),
                     TraitButton::new(Box::new(c), "Download from wikipedia", false),
+                    TraitButton::new(Box::new(d), "Import from url", false),
                 ];
 
                 let popup = Menu::new(
@@ -142,6 +161,14 @@ make cloze (visual mode): Alt+z
                 }
             }
             key if self.inclist.is_selected(cursor) => self.inclist.keyhandler(appdata, key),
+            Enter if self.queue.is_selected(cursor) => {
+                if let Some(idx) = self.queue.state.selected() {
+                    let id = self.queue.items[idx].id;
+                    let txt = TextEditor::new(appdata, id);
+                    self.set_popup(Box::new(txt));
+                }
+            }
+            key if self.queue.is_selected(cursor) => self.queue.keyhandler(appdata, key),
             key if self.topics.is_selected(cursor) => {
                 self.topics.keyhandler(appdata, key);
                 self.reload_inc_list(&appdata.conn);
@@ -155,5 +182,6 @@ make cloze (visual mode): Alt+z
         self.import_button.render(f, appdata, cursor);
         self.topics.render(f, appdata, cursor);
         self.inclist.render(f, appdata, cursor);
+        self.queue.render(f, appdata, cursor);
     }
 }
@@ -7,7 +7,6 @@ use crate::utils::sql::update::{set_suspended, update_inc_active};
 use crate::widgets::button::Button;
 use crate::widgets::mode_status::ModeStatus;
 use crate::widgets::progress_bar::ProgressBar;
-use crate::widgets::textinput::Field;
 use crate::{
     app::Tab,
     utils::{
@@ -15,12 +14,13 @@ use crate::{
         misc::modecolor,
         sql::{
             fetch::cards::get_cardtype,
-            update::{double_inc_skip_duration, double_skip_duration},
+            update::{double_skip_duration, reschedule_inc},
         },
     },
     MyType,
 };
 use rand::prelude::*;
+use rand::rngs::StdRng;
 use rusqlite::Connection;
 use std::sync::{Arc, Mutex};
 use tui::{
@@ -44,32 +44,48 @@ pub struct ForReview {
 }
 
 impl ForReview {
-    pub fn new(conn: &Arc<Mutex<Connection>>) -> Self {
+    pub fn new(appdata: &AppData, rng: &mut StdRng) -> Self {
+        let conn = &appdata.conn;
+        let limits = &appdata.config.limits;
+        let order = appdata.config.review.order;
         crate::utils::interval::calc_strength(conn);
 
-        let mut review_cards = CardQuery::default()
+        let review_cards = CardQuery::default()
             .cardtype(vec![CardType::Finished])
             .max_strength(0.9)
             .suspended(false)
             .resolved(true)
-            .fetch_card_ids(conn);
+            .fetch_review_items(conn);
 
-        let mut unfinished_cards = CardQuery::default()
+        let unfinished_cards = CardQuery::default()
             .unfinished_due()
             .suspended(false)
             .resolved(true)
-            .fetch_card_ids(conn);
+            .fetch_review_items(conn);
 
-        let pending_cards = CardQuery::default()
+        let mut pending_cards = CardQuery::default()
             .cardtype(vec![CardType::Pending])
             .order_by("ORDER BY position DESC".to_string())
             .suspended(false)
             .resolved(true)
-            .fetch_card_ids(conn);
+            .fetch_review_items(conn);
 
         let active_increads = load_active_inc(conn);
-        unfinished_cards.shuffle(&mut thread_rng());
-        review_cards.shuffle(&mut thread_rng());
+        let unfinished_cards = order_items(unfinished_cards, order, rng)
+            .into_iter()
+            .map(|item| item.id)
+            .collect();
+        let review_cards: Vec<(CardID, TopicID)> = order_items(review_cards, order, rng)
+            .iter()
+            .map(ReviewItem::topic_pair)
+            .collect();
+        // new cards keep their position in the queue, apart from priority
+        pending_cards.sort_by_key(|item| std::cmp::Reverse(item.priority));
+        let pending_cards = pending_cards.iter().map(ReviewItem::topic_pair).collect();
+
+        let review_cards = limit_queue(conn, limits, review_cards, LimitKind::Reviews);
+        let pending_cards = limit_queue(conn, limits, pending_cards, LimitKind::NewCards);
+        let active_increads = limit_queue(conn, limits, active_increads, LimitKind::IncRead);
 
         ForReview {
             review_cards,
@@ -110,15 +126,27 @@ pub struct MainReview<'a> {
     pub for_review: ForReview,
     pub start_qty: StartQty,
     pub automode: bool,
+    pub session: Session,
+    summary: Button<'a>,
+    rng: StdRng,
     tabdata: TabData,
 }
 
-use crate::utils::sql::fetch::{load_active_inc, CardQuery};
+use crate::utils::leech::{check_leech, leech_menu};
+use crate::utils::limits::{limit_queue, DailyCount, LimitKind};
+use crate::utils::misc::get_current_day;
+use crate::utils::ordering::{order_items, ReviewItem};
+use crate::utils::sql::fetch::cards::get_topic_of_card;
+use crate::utils::sql::fetch::{get_topic_of_inc, load_active_inc, CardQuery};
+use crate::utils::sql::insert::bump_daily_count;
+
+use super::session::Session;
 
 impl<'a> MainReview<'a> {
     pub fn new(appdata: &AppData) -> Self {
         let mode = ReviewMode::Done;
-        let for_review = ForReview::new(&appdata.conn);
+        let mut rng = appdata.config.review.rng();
+        let for_review = ForReview::new(appdata, &mut rng);
         let start_qty = StartQty::new(&for_review);
         let progress_bar = ProgressBar::new(0);
         let status = ModeStatus::default();
@@ -130,6 +158,9 @@ impl<'a> MainReview<'a> {
             for_review,
             start_qty,
             automode: true,
+            session: Session::new(&appdata.conn),
+            summary: Button::new(String::new()),
+            rng,
             tabdata: TabData::new("Review".to_string()),
         };
         myself.random_mode(appdata);
@@ -154,12 +185,22 @@ impl<'a> MainReview<'a> {
         }
     }
 
-    pub fn draw_done(&mut self, f: &mut Frame<crate::MyType>, appdata: &AppData, area: Rect) {
-        let mut field = Field::default();
-        let mut button = Button::new("Nothing left to review now!\n\nYou could import anki cards from the import page, or add new cards manually.\n\nIf you've imported cards, press Alt+r here to refresh".to_string());
-        field.set_area(area);
-        let cursor = &self.get_cursor();
-        button.render(f, appdata, cursor)
+    pub fn draw_done(&mut self, f: &mut Frame<crate::MyType>, appdata: &AppData, _area: Rect) {
+        let cursor = *self.get_cursor();
+        self.summary.render(f, appdata, &cursor)
+    }
+
+    fn finish_session(&mut self, appdata: &AppData) {
+        self.session.finish(&appdata.conn);
+        let text = if self.session.is_empty() {
+            format!("Nothing left to review now!\n\nYou could import anki cards from the import page, or add new cards manually.\n\nIf you've imported cards, press Alt+r here to refresh\n\nDaily streak: {}", self.session.streak)
+        } else {
+            format!(
+                "{}\n\nPress Alt+r to check for more cards",
+                self.session.summary()
+            )
+        };
+        self.summary.change_text(text);
     }
 
     #[allow(clippy::single_match)]
@@ -170,24 +211,33 @@ impl<'a> MainReview<'a> {
         }
     }
     // randomly choose a mode between active, unfinished and inc read, if theyre all done,
-    // start with pending cards, if theyre all done, declare nothing left to review
+    // start with pending cards, if theyre all done, declare nothing left to review.
+    // if new cards are interleaved, they're part of the random choice instead.
     pub fn random_mode(&mut self, appdata: &AppData) {
+        self.session.count_mode(&self.mode);
         let act: u32 = self.for_review.review_cards.len() as u32;
         let unf: u32 = self.for_review.unfinished_cards.len() as u32 + act;
         let inc: u32 = self.for_review.active_increads.len() as u32 + unf;
 
         let pending_qty = self.for_review.pending_cards.len() as u32;
+        if appdata.config.limits.interleave
+            && pending_qty > 0
+            && self.rng.gen_range(0..inc + pending_qty) >= inc
+        {
+            self.new_pending_mode(appdata);
+            return;
+        }
         if inc == 0 {
             if pending_qty > 0 {
                 self.new_pending_mode(appdata);
             } else {
                 self.mode = ReviewMode::Done;
+                self.finish_session(appdata);
             }
             return;
         }
 
-        let mut rng = rand::thread_rng();
-        let rand = rng.gen_range(0..inc);
+        let rand = self.rng.gen_range(0..inc);
 
         if rand < act {
             self.new_review_mode(appdata);
@@ -225,18 +275,42 @@ impl<'a> MainReview<'a> {
         self.mode = ReviewMode::Review(cardreview);
     }
 
-    pub fn inc_next(&mut self, appdata: &AppData, id: IncID) {
+    pub fn inc_next(&mut self, appdata: &AppData, id: IncID, priority: u32, progress: f32) {
+        self.count_inc(appdata, id);
         self.random_mode(appdata);
-        double_inc_skip_duration(&appdata.conn, id).unwrap();
+        reschedule_inc(&appdata.conn, id, priority, progress).unwrap();
     }
     pub fn inc_done(&mut self, appdata: &AppData, id: IncID) {
         let active = false;
+        self.count_inc(appdata, id);
         update_inc_active(&appdata.conn, id, active).unwrap();
         self.random_mode(appdata);
     }
 
+    fn count_inc(&self, appdata: &AppData, id: IncID) {
+        let topic = get_topic_of_inc(&appdata.conn, id).unwrap();
+        let count = DailyCount {
+            incread: 1,
+            ..Default::default()
+        };
+        bump_daily_count(&appdata.conn, get_current_day(), topic, count).unwrap();
+    }
+
     pub fn new_review(&mut self, appdata: &AppData, id: CardID, recallgrade: RecallGrade) {
+        if let ReviewMode::Review(_) = self.mode {
+            let topic = get_topic_of_card(&appdata.conn, id);
+            let count = DailyCount {
+                reviews: 1,
+                ..Default::default()
+            };
+            bump_daily_count(&appdata.conn, get_current_day(), topic, count).unwrap();
+        }
+        let lapsed = matches!(recallgrade, RecallGrade::None | RecallGrade::Failed);
+        self.session.count_grade(&recallgrade);
         Card::new_review(&appdata.conn, id, recallgrade);
+        if lapsed && check_leech(appdata, id) {
+            self.set_popup(Box::new(leech_menu(appdata, id)));
+        }
         self.random_mode(appdata);
     }
 
@@ -343,19 +417,24 @@ impl<'a> Tab for MainReview<'a> {
             ReviewMode::IncRead(rev) => {
                 let mainvec = split_leftright_by_percent([75, 15], area);
                 let (editing, rightside) = (mainvec[0], mainvec[1]);
-                let rightvec = split_updown_by_percent([33, 33, 33], rightside);
+                let rightvec = split_updown_by_percent([25, 25, 25, 25], rightside);
 
                 self.tabdata.view.areas.push(editing);
                 self.tabdata.view.areas.push(rightvec[0]);
                 self.tabdata.view.areas.push(rightvec[1]);
                 self.tabdata.view.areas.push(rightvec[2]);
+                self.tabdata.view.areas.push(rightvec[3]);
 
                 rev.source.text.source.set_area(editing);
                 rev.source.topics.set_area(rightvec[0]);
                 rev.source.extracts.set_area(rightvec[1]);
                 rev.source.clozes.set_area(rightvec[2]);
+                rev.source.bookmarks.set_area(rightvec[3]);
+            }
+            ReviewMode::Done => {
+                self.tabdata.view.areas.push(area);
+                self.summary.set_area(area);
             }
-            ReviewMode::Done => {}
         }
     }
 
@@ -386,8 +465,22 @@ impl<'a> Tab for MainReview<'a> {
     }
 
     fn exit_popup(&mut self, appdata: &AppData) {
-        self.tabdata.popup = None;
+        if let Some(mut popup) = self.tabdata.popup.take() {
+            match &mut self.mode {
+                ReviewMode::Review(review) | ReviewMode::Pending(review) => {
+                    review.cardview.popup_closed(appdata, &mut popup)
+                }
+                ReviewMode::Unfinished(unfinished) => {
+                    unfinished.cardview.popup_closed(appdata, &mut popup)
+                }
+                _ => {}
+            }
+        }
         self.update_dependencies(&appdata.conn);
+        if let ReviewMode::IncRead(inc) = &mut self.mode {
+            inc.source.reload_bookmarks(appdata);
+            inc.source.reload_clozes(appdata);
+        }
     }
 
     fn keyhandler(&mut self, appdata: &AppData, key: MyKey, cursor: &Pos) {
@@ -414,6 +507,7 @@ impl<'a> Tab for MainReview<'a> {
                     unf.cardview.save_state(&appdata.conn);
                     self.random_mode(appdata);
                 }
+                Alt(c) if c == appdata.config.audio.replay_key => unf.replay_audio(appdata),
                 key if unf.cardview.is_selected(cursor) => {
                     unf.cardview
                         .keyhandler(appdata, &mut self.tabdata, cursor, key);
@@ -430,6 +524,7 @@ impl<'a> Tab for MainReview<'a> {
                     rev.cardview.save_state(&appdata.conn);
                     self.random_mode(appdata);
                 }
+                Alt(c) if c == appdata.config.audio.replay_key => rev.replay_audio(appdata),
 
                 Char(num)
                     if rev.cardview.cardrater.is_selected(cursor)
@@ -479,14 +574,16 @@ impl<'a> Tab for MainReview<'a> {
             },
             ReviewMode::IncRead(inc) => match key {
                 Alt('d') => {
-                    //inc.source.update_text(&appdata.conn);
+                    inc.source.save_state(appdata);
                     let id = inc.source.text.id;
                     self.inc_done(appdata, id);
                 }
                 Alt('s') => {
-                    //inc.source.update_text(&appdata.conn);
+                    inc.source.save_state(appdata);
                     let id = inc.source.text.id;
-                    self.inc_next(appdata, id);
+                    let priority = inc.source.text.priority;
+                    let progress = inc.progress_made();
+                    self.inc_next(appdata, id, priority, progress);
                 }
                 key => {
                     inc.source
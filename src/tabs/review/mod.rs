@@ -1,2 +1,3 @@
 pub mod logic;
 pub mod reviewmodes;
+pub mod session;
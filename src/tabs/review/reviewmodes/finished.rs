@@ -12,6 +12,8 @@ use crate::{
 
 pub struct CardReview<'a> {
     pub cardview: CardView<'a>,
+    /// For the manual, it's configurable.
+    replay_key: char,
 }
 
 impl<'a> CardReview<'a> {
@@ -19,7 +21,15 @@ impl<'a> CardReview<'a> {
         Card::play_frontaudio(appdata, id);
         let mut cardview = CardView::new_with_id(appdata, id);
         cardview.revealed = false;
-        Self { cardview }
+        Self {
+            cardview,
+            replay_key: appdata.config.audio.replay_key,
+        }
+    }
+
+    pub fn replay_audio(&self, appdata: &AppData) {
+        let id = self.cardview.get_id();
+        Card::replay_audio(appdata, id, self.cardview.revealed);
     }
 
     pub fn render(&mut self, f: &mut Frame<MyType>, appdata: &AppData, cursor: &Pos) {
@@ -33,15 +43,24 @@ impl<'a> CardReview<'a> {
     }
 
     pub fn get_manual(&self) -> String {
-        r#"
+        format!(
+            r#"
         Skip card: Alt+s
         Add old card as dependent: Alt+t
         add new card as dependent: Alt+T
         add old card as dependency: Alt+y
         add new card as dependency: Alt+Y
+        suggest dependencies with a language model: Alt+D (accept: y, reject: n)
         suspend card: Alt+i
+        raise/lower priority: Alt+(+/-)
+        open image in the image viewer: Alt+o
+        read out question/answer: Alt+v
+        replay audio: Alt+{} (replay_key under [audio] in the config)
+        record yourself: Alt+w, replay it and the answer audio: Alt+W
+        keep the recording as the answer audio: Alt+S
         rate card: 1,2,3,4
-                "#
-        .to_string()
+                "#,
+            self.replay_key
+        )
     }
 }
@@ -6,11 +6,21 @@ use crate::{app::AppData, utils::aliases::CardID, MyType};
 
 pub struct IncMode<'a> {
     pub source: IncView<'a>,
+    start_progress: f32,
 }
 impl<'a> IncMode<'a> {
     pub fn new(appdata: &AppData, id: CardID) -> Self {
         let source = IncView::new(appdata, id);
-        Self { source }
+        let start_progress = source.text.progress();
+        Self {
+            source,
+            start_progress,
+        }
+    }
+
+    /// How much further into the text the reader got this time.
+    pub fn progress_made(&self) -> f32 {
+        (self.source.text.progress() - self.start_progress).max(0.)
     }
     pub fn render(&mut self, f: &mut Frame<MyType>, appdata: &AppData, cursor: &Pos) {
         self.source.render(f, appdata, cursor);
@@ -23,7 +33,13 @@ impl<'a> IncMode<'a> {
         skip text: Alt+s
         make extract (visual mode): Alt+x 
         make cloze (visual mode): Alt+z
+        mark another cloze to make along with it (visual mode): Alt+Z
+        question/answer card from selection (visual mode): Alt+n
+        generate cards from the text or the selection with a language model: Alt+g
+        bookmark the cursor position: Alt+b
+        mark the section under the cursor as read: Alt+r
         add child card(in text widget): Alt+a
+        raise/lower priority: Alt+(+/-)
 
                 "#
         .to_string()
@@ -1,4 +1,3 @@
 pub mod finished;
 pub mod incread;
 pub mod unfinished;
-
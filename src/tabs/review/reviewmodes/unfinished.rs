@@ -2,27 +2,37 @@ use crate::MyType;
 use tui::Frame;
 
 use crate::app::AppData;
-use crate::utils::card::CardView;
+use crate::utils::card::{Card, CardView};
 
 use crate::utils::aliases::{CardID, Pos};
 
 pub struct UnfCard<'a> {
     pub cardview: CardView<'a>,
+    /// For the manual, it's configurable.
+    replay_key: char,
 }
 
 impl<'a> UnfCard<'a> {
     pub fn new(appdata: &AppData, id: CardID) -> Self {
         let cardview = CardView::new_with_id(appdata, id);
 
-        Self { cardview }
+        Self {
+            cardview,
+            replay_key: appdata.config.audio.replay_key,
+        }
     }
 
     pub fn render(&mut self, f: &mut Frame<MyType>, appdata: &AppData, cursor: &Pos) {
         self.cardview.render(f, appdata, cursor);
     }
 
+    pub fn replay_audio(&self, appdata: &AppData) {
+        Card::replay_audio(appdata, self.cardview.get_id(), true);
+    }
+
     pub fn get_manual(&self) -> String {
-        r#"
+        format!(
+            r#"
             
         Skip card: Alt+s
         complete card: Alt+f
@@ -30,9 +40,13 @@ impl<'a> UnfCard<'a> {
         add new card as dependent: Alt+T
         add old card as dependency: Alt+y
         add new card as dependency: Alt+Y
+        suggest dependencies with a language model: Alt+D (accept: y, reject: n)
         suspend card: Alt+i
+        raise/lower priority: Alt+(+/-)
+        replay audio: Alt+{} (replay_key under [audio] in the config)
 
-                "#
-        .to_string()
+                "#,
+            self.replay_key
+        )
     }
 }
@@ -0,0 +1,137 @@
+use crate::utils::aliases::*;
+use crate::utils::card::RecallGrade;
+use crate::utils::misc::{get_current_day, get_current_unix};
+use crate::utils::session::SessionStats;
+use crate::utils::sql::fetch::cards::is_resolved;
+use crate::utils::sql::fetch::{fetch_item, CardQuery};
+use crate::utils::sql::fetch::{get_highest_card_id, get_highest_inc_id, get_review_days};
+use crate::utils::sql::insert::save_session;
+
+use super::logic::ReviewMode;
+
+/// Keeps track of what happened during a review session, to show a summary once it's done.
+#[derive(Default, Debug, Clone)]
+pub struct Session {
+    pub stats: SessionStats,
+    pub streak: u32,
+    unresolved_at_start: Vec<CardID>,
+    highest_card: CardID,
+    highest_inc: IncID,
+    saved: bool,
+}
+
+impl Session {
+    pub fn new(conn: Conn) -> Self {
+        Self {
+            stats: SessionStats {
+                start: get_current_unix().as_secs(),
+                ..Default::default()
+            },
+            unresolved_at_start: CardQuery::default().resolved(false).fetch_card_ids(conn),
+            highest_card: get_highest_card_id(conn),
+            highest_inc: get_highest_inc_id(conn),
+            ..Default::default()
+        }
+    }
+
+    /// Counts the item that was just shown in the given mode.
+    pub fn count_mode(&mut self, mode: &ReviewMode) {
+        match mode {
+            ReviewMode::Review(_) => self.stats.reviews += 1,
+            ReviewMode::Pending(_) => self.stats.pending += 1,
+            ReviewMode::Unfinished(_) => self.stats.unfinished += 1,
+            ReviewMode::IncRead(_) => self.stats.incread += 1,
+            ReviewMode::Done => {}
+        }
+    }
+
+    pub fn count_grade(&mut self, grade: &RecallGrade) {
+        self.stats.grades[grade.clone() as usize] += 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let stats = &self.stats;
+        stats.reviews + stats.pending + stats.unfinished + stats.incread == 0
+    }
+
+    /// Fills in the totals and saves the session, only the first time it's called.
+    pub fn finish(&mut self, conn: Conn) {
+        if self.saved {
+            return;
+        }
+        self.stats.end = get_current_unix().as_secs();
+        self.stats.resolved = self
+            .unresolved_at_start
+            .iter()
+            .filter(|id| is_resolved(conn, **id))
+            .count() as u32;
+        self.stats.extracts = fetch_item(
+            conn,
+            format!(
+                "SELECT COUNT(*) FROM incread WHERE id > {} AND parent != 0",
+                self.highest_inc
+            ),
+            |row| row.get(0),
+        )
+        .unwrap();
+        self.stats.clozes = fetch_item(
+            conn,
+            format!(
+                "SELECT COUNT(*) FROM cards WHERE id > {} AND source != 0",
+                self.highest_card
+            ),
+            |row| row.get(0),
+        )
+        .unwrap();
+        self.streak = calc_streak(&get_review_days(conn), get_current_day());
+        if !self.is_empty() {
+            save_session(conn, &self.stats).unwrap();
+        }
+        self.saved = true;
+    }
+
+    pub fn summary(&self) -> String {
+        let stats = &self.stats;
+        let minutes = (stats.end - stats.start) / 60;
+        let seconds = (stats.end - stats.start) % 60;
+        format!(
+            "Session done!\n\nReviews: {}\nNew cards: {}\nUnfinished cards: {}\nIncremental reading: {}\n\nGrades (1/2/3/4): {} / {} / {} / {}\nTime spent: {}m {}s\nCards resolved: {}\nExtracts made: {}\nClozes made: {}\n\nDaily streak: {} {}",
+            stats.reviews,
+            stats.pending,
+            stats.unfinished,
+            stats.incread,
+            stats.grades[0],
+            stats.grades[1],
+            stats.grades[2],
+            stats.grades[3],
+            minutes,
+            seconds,
+            stats.resolved,
+            stats.extracts,
+            stats.clozes,
+            self.streak,
+            if self.streak == 1 { "day" } else { "days" },
+        )
+    }
+}
+
+/// Amount of consecutive days with reviews, ending today or yesterday.
+/// `days` has to be sorted in ascending order.
+pub fn calc_streak(days: &[u32], today: u32) -> u32 {
+    let mut expected = match days.last() {
+        Some(&day) if day == today || day + 1 == today => day,
+        _ => return 0,
+    };
+    let mut streak = 0;
+    for &day in days.iter().rev() {
+        if day != expected {
+            break;
+        }
+        streak += 1;
+        if expected == 0 {
+            break;
+        }
+        expected -= 1;
+    }
+    streak
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+use crate::app::{Audio, AudioConfig};
+#[cfg(test)]
+use crate::utils::misc::{play_audio, play_audio_queue};
+
+#[test]
+fn audio_config_test() {
+    let config: AudioConfig = toml::from_str(
+        r#"
+volume = 0.5
+autoplay_back = false
+replay_key = "r"
+"#,
+    )
+    .unwrap();
+    assert!(config.enabled);
+    assert_eq!(config.volume, 0.5);
+    assert!(config.device.is_empty());
+    assert!(config.autoplay_front);
+    assert!(!config.autoplay_back);
+    assert_eq!(config.replay_key, 'r');
+    assert!(toml::from_str::<AudioConfig>("replay_key = \"ab\"").is_err());
+}
+
+#[test]
+fn no_audio_test() {
+    // Whether or not this machine can play sound, none of this may panic.
+    let config = AudioConfig {
+        device: "no such device".to_string(),
+        volume: 3.,
+        ..Default::default()
+    };
+    if let Some(audio) = Audio::new(&config) {
+        assert_eq!(audio.volume, 1.);
+    }
+    play_audio(&None, "missing.wav".into());
+    play_audio_queue(&None, vec!["missing.wav".into()]);
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+use super::test_db;
+#[cfg(test)]
+use crate::utils::cloze::{make_cloze, surrounding_text, ClozeContext};
+#[cfg(test)]
+use crate::utils::incread::IncRead;
+#[cfg(test)]
+use crate::utils::sql::fetch::cards::fetch_card;
+#[cfg(test)]
+use crate::utils::sql::insert::new_incread;
+#[cfg(test)]
+use crate::widgets::textinput::CursorPos;
+
+#[cfg(test)]
+const TEXT: &str = "The cat sat. The cat ran away! Dogs bark.\nNew line here.\n\nSecond paragraph.";
+
+#[test]
+fn cloze_range_test() {
+    // the second "cat", not every occurrence of it
+    let (question, answer) = make_cloze(TEXT, (17, 20), ClozeContext::Full);
+    assert_eq!(answer, "cat");
+    assert!(question.starts_with("The cat sat. The [...] ran away!"));
+
+    let (question, _) = make_cloze(TEXT, (17, 20), ClozeContext::Sentence);
+    assert_eq!(question, "The [...] ran away!");
+
+    let (question, answer) = make_cloze(TEXT, (46, 50), ClozeContext::Sentence);
+    assert_eq!(answer, "line");
+    assert_eq!(question, "New [...] here.");
+
+    let (question, _) = make_cloze(TEXT, (17, 20), ClozeContext::Paragraph);
+    assert_eq!(
+        question,
+        "The cat sat. The [...] ran away! Dogs bark.\nNew line here."
+    );
+
+    let (question, _) = make_cloze(TEXT, (58, 64), ClozeContext::Paragraph);
+    assert_eq!(question, "[...] paragraph.");
+
+    assert_eq!(
+        surrounding_text(TEXT, (17, 20), ClozeContext::Sentence),
+        "The cat ran away!"
+    );
+}
+
+#[test]
+fn multi_cloze_test() {
+    let conn = test_db("cloze");
+    let id = new_incread(&conn, 0, 1, TEXT.to_string(), true);
+
+    let mut text = IncRead::new(&conn, id);
+    text.source.cursor = CursorPos { row: 0, column: 4 };
+    text.source.set_visual_mode();
+    text.source.cursor = CursorPos { row: 0, column: 6 };
+    text.mark_cloze();
+    text.source.clear_selection();
+    text.source.cursor = CursorPos { row: 0, column: 36 };
+    text.source.set_visual_mode();
+    text.source.cursor = CursorPos { row: 0, column: 39 };
+    text.cloze(&conn, ClozeContext::Sentence);
+
+    assert!(text.source.marks.is_empty());
+    assert_eq!(text.clozes.len(), 2);
+    let mut cards: Vec<(String, String)> = text
+        .clozes
+        .iter()
+        .map(|item| {
+            let card = fetch_card(&conn, item.id);
+            (card.question, card.answer)
+        })
+        .collect();
+    cards.sort();
+    assert_eq!(
+        cards,
+        vec![
+            ("Dogs [...].".to_string(), "bark".to_string()),
+            ("The [...] sat.".to_string(), "cat".to_string()),
+        ]
+    );
+}
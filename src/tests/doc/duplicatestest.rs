@@ -0,0 +1,183 @@
+#[cfg(test)]
+use super::{save, test_db};
+#[cfg(test)]
+use crate::utils::ankitemplate::Template;
+#[cfg(test)]
+use crate::utils::card::{RecallGrade, Review};
+#[cfg(test)]
+use crate::utils::duplicates::{
+    merge_cards, normalize, similarity, DuplicateIndex, DuplicatesConfig, ImportDuplicates,
+};
+#[cfg(test)]
+use crate::utils::misc::get_current_unix;
+#[cfg(test)]
+use crate::utils::sql::fetch::cards::{fetch_card, get_history, get_stability, get_strength};
+#[cfg(test)]
+use crate::utils::sql::fetch::CardQuery;
+#[cfg(test)]
+use crate::utils::sql::insert::{new_incread, revlog_new, update_both};
+#[cfg(test)]
+use crate::utils::sql::update::update_card_source;
+#[cfg(test)]
+use std::time::Duration;
+
+#[test]
+fn similarity_test() {
+    assert_eq!(
+        normalize("  What's the  capital\nof FRANCE?"),
+        "what s the capital of france"
+    );
+    assert_eq!(
+        similarity("Capital of France?", "Paris", "capital of france", "paris."),
+        1.
+    );
+
+    let typo = similarity(
+        "What is the capital of France?",
+        "Paris",
+        "What is the capitol of France",
+        "Paris",
+    );
+    assert!(typo > 0.85, "{}", typo);
+    let different = similarity(
+        "What is the capital of France?",
+        "Paris",
+        "What is the capital of Spain?",
+        "Madrid",
+    );
+    assert!(different < 0.85, "{}", different);
+}
+
+#[test]
+fn duplicate_index_test() {
+    let conn = test_db("duplicates");
+    let paris = save(&conn, "What is the capital of France?", "Paris");
+
+    let mut index = DuplicateIndex::new(&conn, 0.85);
+    let exact = index
+        .find("what is the CAPITAL of france", "paris")
+        .unwrap();
+    assert_eq!((exact.id, exact.similarity), (paris, 1.));
+    assert_eq!(exact.question, "What is the capital of France?");
+
+    let fuzzy = index
+        .find("What is the capitol of France?", "Paris")
+        .unwrap();
+    assert_eq!(fuzzy.id, paris);
+    assert!(fuzzy.similarity < 1.);
+
+    assert!(index.find("?!", "").is_none());
+
+    // Cards saved after the index was made are only found once it's updated.
+    let madrid = save(&conn, "What is the capital of Spain?", "Madrid");
+    assert!(index
+        .find("What is the capital of Spain?", "Madrid")
+        .is_none());
+    index.update(&conn);
+    let spain = index
+        .find("What is the capital of Spain?", "Madrid")
+        .unwrap();
+    assert_eq!(spain.id, madrid);
+
+    // Above 1 only exact matches count.
+    let strict = DuplicateIndex::new(&conn, 1.1);
+    assert!(strict
+        .find("What is the capitol of France?", "Paris")
+        .is_none());
+    assert!(strict
+        .find("What is the capital of France", "Paris")
+        .is_some());
+}
+
+#[test]
+fn merge_test() {
+    let conn = test_db("merge");
+    let keep = save(&conn, "What is the capital of France?", "Paris");
+    let remove = save(&conn, "Capital of France?", "Paris");
+    let dependency = save(&conn, "What is a capital?", "The seat of government");
+    let dependent = save(&conn, "Where is the Louvre?", "Paris");
+    let source = new_incread(
+        &conn,
+        0,
+        1,
+        "Paris is the capital of France.".to_string(),
+        true,
+    );
+
+    update_card_source(&conn, remove, source);
+    update_both(&conn, remove, dependency).unwrap();
+    update_both(&conn, keep, dependency).unwrap();
+    update_both(&conn, dependent, remove).unwrap();
+    revlog_new(&conn, remove, &Review::from(&RecallGrade::Easy)).unwrap();
+    let earlier = Review {
+        grade: RecallGrade::Decent,
+        date: get_current_unix() - Duration::from_secs(10 * 86400),
+        answertime: 1.,
+    };
+    revlog_new(&conn, remove, &earlier).unwrap();
+
+    merge_cards(&conn, keep, remove).unwrap();
+
+    let card = fetch_card(&conn, keep);
+    assert_eq!(card.question, "What is the capital of France?");
+    assert_eq!(card.dependencies, vec![dependency]);
+    assert_eq!(card.dependents, vec![dependent]);
+    assert_eq!(card.source, source);
+    // Both cards got a review when they were saved, and the merged one got two more.
+    assert_eq!(get_history(&conn, keep).len(), 4);
+    // Two days from the first review, doubled by the reviews ten days later.
+    let stability = get_stability(&conn, keep).as_secs_f32() / 86400.;
+    assert!((stability - 20.).abs() < 0.01, "{}", stability);
+    assert!(get_strength(&conn, keep).is_finite());
+    assert!(!CardQuery::default().fetch_card_ids(&conn).contains(&remove));
+}
+
+#[test]
+fn merge_rollback_test() {
+    let conn = test_db("merge_rollback");
+    let keep = save(&conn, "What is the capital of France?", "Paris");
+    let remove = save(&conn, "Capital of France?", "Paris");
+    // Deleting the merged card fails at its last step.
+    conn.lock()
+        .unwrap()
+        .execute("DROP TABLE leeches", [])
+        .unwrap();
+
+    assert!(merge_cards(&conn, keep, remove).is_err());
+    assert_eq!(get_history(&conn, keep).len(), 1);
+    assert_eq!(get_history(&conn, remove).len(), 1);
+    assert!(CardQuery::default().fetch_card_ids(&conn).contains(&remove));
+}
+
+#[test]
+fn import_duplicates_test() {
+    let dir = std::env::temp_dir().join("speki_import_duplicates_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let tsv = dir.join("deck.tsv");
+    std::fs::write(
+        &tsv,
+        "Front\tBack\nWhat is the capital of France?\tParis\nwhat is the capital of france\tparis\nWhat is the capital of Spain?\tMadrid\n",
+    )
+    .unwrap();
+
+    let import = |policy: ImportDuplicates| {
+        let conn = test_db("import_duplicates");
+        let existing = save(&conn, "What is the capital of Spain?", "Madrid");
+        let before = CardQuery::default().fetch_card_ids(&conn).len();
+        let (tx, _rx) = std::sync::mpsc::sync_channel(5);
+        let config = DuplicatesConfig {
+            import: policy,
+            ..Default::default()
+        };
+        Template::new_csv(tsv.clone()).import_cards(conn.clone(), tx, 1, config);
+        let added = CardQuery::default().fetch_card_ids(&conn).len() - before;
+        (added, get_history(&conn, existing).len())
+    };
+
+    assert_eq!(import(ImportDuplicates::Keep), (3, 1));
+    // Duplicates within the deck itself are caught too.
+    assert_eq!(import(ImportDuplicates::Skip), (1, 1));
+    // The imported cards haven't been reviewed, so there's no history to move over.
+    assert_eq!(import(ImportDuplicates::Merge), (1, 1));
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+use crate::utils::epub::get_body_contents;
+#[cfg(test)]
+use crate::utils::incread::inc_title;
+#[cfg(test)]
+use crate::widgets::textinput::markup::markup_spans;
+#[cfg(test)]
+use tui::style::Modifier;
+
+#[test]
+fn epub_markup_test() {
+    let xhtml = r#"<html xmlns="http://www.w3.org/1999/xhtml"><head><title>x</title></head>
+<body>
+  <h1>Chapter   One</h1>
+  <p>Some <em>emphasized</em> and <strong>strong</strong>
+     text.</p>
+  <ul><li>first</li><li><p>second</p></li></ul>
+  <ol><li>one</li><li>two</li></ol>
+</body></html>"#;
+    let markup = get_body_contents(xhtml).unwrap();
+    assert_eq!(
+        markup,
+        "# Chapter One\n\nSome *emphasized* and **strong** text.\n\n- first\n- second\n\n1. one\n2. two"
+    );
+    assert_eq!(inc_title(&markup), "Chapter One");
+}
+
+#[test]
+fn epub_unparsable_test() {
+    assert!(get_body_contents("<html><body><p>unclosed</body></html>").is_none());
+    assert!(get_body_contents(r#"<html xmlns="http://www.w3.org/1999/xhtml"></html>"#).is_none());
+}
+
+#[test]
+fn markup_style_test() {
+    let heading = markup_spans("## Title");
+    assert!(heading.0[0].style.add_modifier.contains(Modifier::BOLD));
+
+    let spans = markup_spans("- a *b* c");
+    let text: Vec<&str> = spans.0.iter().map(|span| span.content.as_ref()).collect();
+    assert_eq!(text, vec!["- ", "a ", "*b*", " c"]);
+    assert!(spans.0[2].style.add_modifier.contains(Modifier::ITALIC));
+    assert_eq!(markup_spans("2 * 3 * 4").0.len(), 1);
+}
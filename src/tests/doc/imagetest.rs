@@ -0,0 +1,75 @@
+#[cfg(test)]
+use crate::utils::graphics::{half_blocks, iterm_escape, ImageProtocol};
+#[cfg(test)]
+use crate::utils::image::Image;
+#[cfg(test)]
+use tui::layout::Rect;
+#[cfg(test)]
+use tui::style::Color;
+
+/// A single red pixel.
+#[cfg(test)]
+const RED_GIF: &[u8] = b"GIF89a\x01\x00\x01\x00\x80\x00\x00\xff\x00\x00\x00\x00\x00,\x00\x00\x00\x00\x01\x00\x01\x00\x00\x02\x02D\x01\x00;";
+
+#[test]
+fn decode_test() {
+    let gif = Image::decode(RED_GIF).unwrap();
+    assert_eq!((gif.width, gif.height), (1, 1));
+    assert_eq!(gif.pixel(0, 0), [255, 0, 0, 255]);
+
+    let png = Image::new(3, 2, [1, 2, 3, 255]);
+    assert_eq!(Image::decode(&png.to_png()).unwrap(), png);
+
+    assert!(Image::decode(b"\xff\xd8 broken jpeg").is_err());
+    assert!(Image::decode(b"<svg></svg>").is_err());
+}
+
+#[test]
+fn half_blocks_test() {
+    let mut image = Image::new(2, 3, [0, 0, 255, 255]);
+    image.pixels[0] = [255, 0, 0, 255];
+    image.pixels[3] = [0, 0, 0, 0];
+
+    let lines = half_blocks(&image, Rect::new(0, 0, 10, 10));
+    assert_eq!(lines.len(), 2);
+    let colors: Vec<Vec<(Option<Color>, Option<Color>)>> = lines
+        .iter()
+        .map(|line| {
+            line.0
+                .iter()
+                .map(|span| (span.style.fg, span.style.bg))
+                .collect()
+        })
+        .collect();
+    let blue = Some(Color::Rgb(0, 0, 255));
+    assert_eq!(
+        colors,
+        vec![
+            vec![
+                (Some(Color::Rgb(255, 0, 0)), blue),
+                (blue, Some(Color::Reset))
+            ],
+            // The last row has no pixels below it.
+            vec![(blue, Some(Color::Reset)), (blue, Some(Color::Reset))],
+        ]
+    );
+    assert!(lines[0].0.iter().all(|span| span.content == "▀"));
+
+    // Images that are too big are scaled down to the area.
+    let big = Image::new(100, 100, [0, 0, 0, 255]);
+    let lines = half_blocks(&big, Rect::new(0, 0, 20, 5));
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0].0.len(), 10);
+}
+
+#[test]
+fn iterm_test() {
+    let image = Image::new(20, 20, [0, 255, 0, 255]);
+    let escape = iterm_escape(&image, 4, 2);
+    assert!(escape.starts_with("\x1b]1337;File=inline=1;size="));
+    assert!(escape.contains(";width=4;height=2;preserveAspectRatio=1:iVBORw0KGgo"));
+    assert!(escape.ends_with('\x07'));
+
+    assert_eq!(ImageProtocol::Iterm.resolve(), ImageProtocol::Iterm);
+    assert_eq!(ImageProtocol::Blocks.resolve(), ImageProtocol::Blocks);
+}
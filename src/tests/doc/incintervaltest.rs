@@ -0,0 +1,11 @@
+#[cfg(test)]
+use crate::utils::interval::{inc_interval, MAX_INC_PRIORITY};
+
+#[test]
+fn inc_interval_test() {
+    assert_eq!(inc_interval(4, 0, 0., 1.), 8);
+    assert_eq!(inc_interval(4, MAX_INC_PRIORITY, 0., 1.), 4);
+    assert_eq!(inc_interval(4, 0, 1., 1.), 4);
+    assert!(inc_interval(10, 8, 0., 1.) < inc_interval(10, 2, 0., 1.));
+    assert_eq!(inc_interval(1, MAX_INC_PRIORITY, 1., 0.75), 1);
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+use super::{get_appdata, save, test_db};
+#[cfg(test)]
+use crate::app::AppData;
+#[cfg(test)]
+use crate::utils::aliases::*;
+#[cfg(test)]
+use crate::utils::card::{RecallGrade, Review};
+#[cfg(test)]
+use crate::utils::leech::{check_leech, LeechAction, LeechConfig};
+#[cfg(test)]
+use crate::utils::sql::fetch::cards::{fetch_card, is_leech};
+#[cfg(test)]
+use crate::utils::sql::fetch::CardQuery;
+#[cfg(test)]
+use crate::utils::sql::insert::revlog_new;
+#[cfg(test)]
+use std::time::Duration;
+
+#[cfg(test)]
+fn leech_appdata(name: &str, threshold: u32, action: LeechAction) -> AppData {
+    let mut appdata = AppData {
+        conn: test_db(name),
+        ..get_appdata()
+    };
+    appdata.config.leech = LeechConfig { threshold, action };
+    appdata
+}
+
+#[cfg(test)]
+fn review(appdata: &AppData, id: CardID, date: u64, grade: RecallGrade) {
+    let review = Review {
+        grade,
+        date: Duration::from_secs(date),
+        answertime: 1.,
+    };
+    revlog_new(&appdata.conn, id, &review).unwrap();
+}
+
+#[test]
+fn leech_threshold_test() {
+    let appdata = leech_appdata("leech_tag", 3, LeechAction::Tag);
+    let card = save(&appdata.conn, "What is the capital of Burkina Faso?", "a");
+    let other = save(&appdata.conn, "What is the capital of France?", "a");
+
+    review(&appdata, card, 100, RecallGrade::Failed);
+    review(&appdata, card, 200, RecallGrade::None);
+    review(&appdata, card, 300, RecallGrade::Easy);
+    assert!(!check_leech(&appdata, card));
+    assert!(!is_leech(&appdata.conn, card));
+
+    review(&appdata, card, 400, RecallGrade::Failed);
+    assert!(check_leech(&appdata, card));
+    assert!(is_leech(&appdata.conn, card));
+    assert!(!fetch_card(&appdata.conn, card).suspended);
+    // It only becomes a leech once.
+    review(&appdata, card, 500, RecallGrade::Failed);
+    assert!(!check_leech(&appdata, card));
+
+    assert_eq!(
+        CardQuery::default()
+            .leech(true)
+            .fetch_card_ids(&appdata.conn),
+        vec![card]
+    );
+    let others = CardQuery::default()
+        .leech(false)
+        .fetch_card_ids(&appdata.conn);
+    assert!(others.contains(&other));
+    assert!(!others.contains(&card));
+}
+
+#[test]
+fn leech_suspend_test() {
+    let appdata = leech_appdata("leech_suspend", 2, LeechAction::Suspend);
+    let card = save(&appdata.conn, "What is the capital of Burkina Faso?", "a");
+    review(&appdata, card, 100, RecallGrade::Failed);
+    review(&appdata, card, 200, RecallGrade::Failed);
+    assert!(check_leech(&appdata, card));
+    assert!(fetch_card(&appdata.conn, card).suspended);
+
+    let appdata = leech_appdata("leech_off", 0, LeechAction::Suspend);
+    let card = save(&appdata.conn, "What is the capital of Burkina Faso?", "a");
+    for date in 1..10 {
+        review(&appdata, card, date, RecallGrade::Failed);
+    }
+    assert!(!check_leech(&appdata, card));
+    assert!(!is_leech(&appdata.conn, card));
+    assert!(!fetch_card(&appdata.conn, card).suspended);
+}
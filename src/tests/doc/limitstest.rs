@@ -0,0 +1,77 @@
+#[cfg(test)]
+use crate::utils::limits::{DailyCount, LimitKind, LimitsConfig, Remaining, TopicLimit};
+#[cfg(test)]
+use crate::utils::sql::{fetch::get_daily_counts, init_db, insert::bump_daily_count};
+#[cfg(test)]
+use rusqlite::Connection;
+#[cfg(test)]
+use std::collections::HashMap;
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn daily_limit_test() {
+    let mut limits = LimitsConfig {
+        new_cards: Some(5),
+        ..Default::default()
+    };
+    limits.topics.insert(
+        "spanish".to_string(),
+        TopicLimit {
+            new_cards: Some(2),
+            ..Default::default()
+        },
+    );
+    let topic_ids = HashMap::from([("spanish".to_string(), 2)]);
+    let counts = HashMap::from([(
+        2,
+        DailyCount {
+            new_cards: 1,
+            reviews: 10,
+            incread: 0,
+        },
+    )]);
+
+    let remaining = Remaining::new(&limits, &topic_ids, &counts, LimitKind::NewCards);
+    let cards = vec![(1, 2), (2, 2), (3, 1), (4, 1), (5, 1), (6, 1)];
+    assert_eq!(remaining.apply(cards), vec![1, 3, 4, 5]);
+
+    let remaining = Remaining::new(&limits, &topic_ids, &counts, LimitKind::Reviews);
+    assert_eq!(remaining.apply(vec![(1, 2), (2, 1)]), vec![1, 2]);
+}
+
+#[test]
+fn daily_counts_migration_test() {
+    let path = std::env::temp_dir().join("speki_daily_counts_migration_test.db");
+    let _ = std::fs::remove_file(&path);
+    let conn = Connection::open(&path).unwrap();
+    conn.execute(
+        "create table daily_counts (
+            day       integer not null,
+            topic     integer not null,
+            new_cards integer not null,
+            reviews   integer not null
+        )",
+        [],
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO daily_counts (day, topic, new_cards, reviews) VALUES (7, 1, 2, 3)",
+        [],
+    )
+    .unwrap();
+    drop(conn);
+
+    init_db(&path).unwrap();
+    let conn = Arc::new(Mutex::new(Connection::open(&path).unwrap()));
+    let count = DailyCount {
+        new_cards: 0,
+        reviews: 0,
+        incread: 4,
+    };
+    bump_daily_count(&conn, 7, 1, count).unwrap();
+    let counts = get_daily_counts(&conn, 7);
+    assert_eq!(counts[&1].new_cards, 2);
+    assert_eq!(counts[&1].reviews, 3);
+    assert_eq!(counts[&1].incread, 4);
+}
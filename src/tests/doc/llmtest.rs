@@ -0,0 +1,244 @@
+#[cfg(test)]
+use super::{mock_server, save, test_db};
+#[cfg(test)]
+use crate::utils::aliases::CardID;
+#[cfg(test)]
+use crate::utils::card::CardItem;
+#[cfg(test)]
+use crate::utils::llm::{
+    parse_cards, parse_suggestions, similar_cards, AnswerGenerator, CardProposal, Generation,
+    LlmConfig, LlmResult, OpenAiChat, Suggestion,
+};
+#[cfg(test)]
+use crate::utils::misc::SpekiPaths;
+#[cfg(test)]
+use crate::utils::sql::fetch::cards::fetch_card;
+#[cfg(test)]
+use crate::utils::sql::insert::new_incread;
+#[cfg(test)]
+use std::sync::mpsc::Receiver;
+#[cfg(test)]
+use std::time::Duration;
+
+/// Answers every request with the same response, after a delay in milliseconds.
+#[cfg(test)]
+fn chat_server(status: &'static str, body: &'static str, delay: u64) -> (String, Receiver<String>) {
+    let (server, requests) = mock_server("application/json", move |_| {
+        std::thread::sleep(Duration::from_millis(delay));
+        (status, body)
+    });
+    (format!("{}/v1", server), requests)
+}
+
+#[cfg(test)]
+fn config(endpoint: &str) -> LlmConfig {
+    LlmConfig {
+        endpoint: endpoint.to_string(),
+        model: "llama3".to_string(),
+        timeout: 2,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+const ANSWER: &str =
+    r#"{"choices":[{"index":0,"message":{"role":"assistant","content":"  Paris\n"}}]}"#;
+
+#[test]
+fn chat_test() {
+    let (endpoint, requests) = chat_server("200 OK", ANSWER, 0);
+    let config = config(&format!("{}/", endpoint));
+    let prompt = config.answer_prompt(" What is the capital of France? ");
+    assert!(prompt.ends_with("\n\nWhat is the capital of France?"));
+
+    let generator = OpenAiChat::new(&config, Some("sk-legacy"));
+    assert_eq!(generator.generate(&prompt).unwrap(), "Paris");
+
+    let request = requests.recv().unwrap();
+    assert!(request.starts_with("POST /v1/chat/completions HTTP/1.1\r\n"));
+    assert!(request
+        .to_lowercase()
+        .contains("authorization: bearer sk-legacy\r\n"));
+    let body = request.split("\r\n\r\n").nth(1).unwrap();
+    let json: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(json["model"], "llama3");
+    assert_eq!(json["messages"][0]["role"], "user");
+    assert_eq!(json["messages"][0]["content"], prompt.as_str());
+    assert_eq!(json["max_tokens"], 200);
+}
+
+#[test]
+fn local_server_test() {
+    // Local servers don't need a key, so none is sent.
+    let (endpoint, requests) = chat_server("200 OK", ANSWER, 0);
+    let mut config = config(&endpoint);
+    config.api_key = Some("".to_string());
+    assert_eq!(
+        OpenAiChat::new(&config, None).generate("q").unwrap(),
+        "Paris"
+    );
+    let request = requests.recv().unwrap().to_lowercase();
+    assert!(!request.contains("authorization"));
+}
+
+#[test]
+fn failure_test() {
+    let (endpoint, _requests) = chat_server("401 Unauthorized", r#"{"error":"bad key"}"#, 0);
+    assert!(OpenAiChat::new(&config(&endpoint), None)
+        .generate("q")
+        .is_err());
+
+    let (endpoint, _requests) = chat_server("200 OK", r#"{"choices":[]}"#, 0);
+    assert!(OpenAiChat::new(&config(&endpoint), None)
+        .generate("q")
+        .is_err());
+
+    let (endpoint, _requests) = chat_server("200 OK", ANSWER, 3000);
+    let mut config = config(&endpoint);
+    config.timeout = 1;
+    assert!(OpenAiChat::new(&config, None).generate("q").is_err());
+}
+
+#[cfg(test)]
+struct Echo;
+
+#[cfg(test)]
+impl AnswerGenerator for Echo {
+    fn generate(&self, prompt: &str) -> LlmResult<String> {
+        std::thread::sleep(Duration::from_millis(50));
+        Ok(prompt.to_uppercase())
+    }
+}
+
+#[test]
+fn generation_test() {
+    let generation = Generation::start(Box::new(Echo), "paris".to_string());
+    // The answer isn't waited for, the ui keeps going with a spinner.
+    assert!(generation.poll().is_none());
+    assert!(generation.spinner() != ' ');
+
+    let mut result = None;
+    for _ in 0..100 {
+        result = generation.poll();
+        if result.is_some() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(result.unwrap().unwrap(), "PARIS");
+}
+
+#[test]
+fn parse_cards_test() {
+    let response = "Here you go:\n```json\n[\n  {\"question\": \"Who wrote Hamlet?\", \"answer\": \"Shakespeare\"},\n  {\"question\": \"Hamlet is set in [...]\", \"answer\": \"Denmark\", \"type\": \"cloze\"},\n  {\"question\": \"\", \"answer\": \"empty\"},\n  \"not a card\"\n]\n```";
+    let cards = parse_cards(response).unwrap();
+    assert_eq!(
+        cards,
+        vec![
+            CardProposal {
+                question: "Who wrote Hamlet?".to_string(),
+                answer: "Shakespeare".to_string(),
+            },
+            CardProposal {
+                question: "Hamlet is set in [...]".to_string(),
+                answer: "Denmark".to_string(),
+            },
+        ]
+    );
+    assert!(parse_cards("I can't make cards from this").is_err());
+    assert!(parse_cards("] backwards [").is_err());
+
+    let config = LlmConfig {
+        cards_prompt: "{count} cards: {text}".to_string(),
+        card_count: 3,
+        ..Default::default()
+    };
+    assert_eq!(config.cards_prompt(" some text\n"), "3 cards: some text");
+}
+
+#[test]
+fn default_config_test() {
+    // The examples in the default config file are the defaults, uncommented.
+    let defaults = LlmConfig::default();
+    let example: String = SpekiPaths::DEFAULTCONFIG
+        .split("#[llm]\n")
+        .nth(1)
+        .unwrap()
+        .lines()
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.strip_prefix('#'))
+        .filter(|line| !line.starts_with(' '))
+        .collect::<Vec<&str>>()
+        .join("\n");
+    let example: LlmConfig = toml::from_str(&example).unwrap();
+    assert_eq!(example.cards_prompt, defaults.cards_prompt);
+    assert_eq!(example.dependencies_prompt, defaults.dependencies_prompt);
+    assert_eq!(example.max_tokens, defaults.max_tokens);
+    assert_eq!(example.cards_max_tokens, defaults.cards_max_tokens);
+}
+
+#[test]
+fn save_proposal_test() {
+    let conn = test_db("llm");
+
+    let extract = new_incread(&conn, 0, 1, "Hamlet is set in Denmark.".to_string(), true);
+    let proposal = CardProposal {
+        question: "Hamlet is set in [...] ".to_string(),
+        answer: "Denmark\n".to_string(),
+    };
+    let card = fetch_card(&conn, proposal.save(&conn, 1, extract));
+    assert_eq!(card.question, "Hamlet is set in [...]");
+    assert_eq!(card.answer, "Denmark");
+    assert_eq!(card.source, extract);
+    assert_eq!(card.topic, 1);
+}
+
+#[test]
+fn suggest_dependencies_test() {
+    let conn = test_db("suggest");
+
+    let proton = save(&conn, "What is a proton?", "a");
+    let nucleus = save(&conn, "What is in the nucleus of an atom?", "a");
+    let bread = save(&conn, "How is bread baked?", "a");
+    let card = save(&conn, "What is the charge of a proton in the nucleus?", "a");
+
+    let text = "What is the charge of a proton in the nucleus?";
+    let candidates = similar_cards(&conn, text, &[card], 10);
+    let ids: Vec<CardID> = candidates.iter().map(|card| card.id).collect();
+    // The database starts out with some cards of its own that share words like "what".
+    assert_eq!(ids[..2], [nucleus, proton]);
+    assert!(!ids.contains(&bread));
+    assert!(!ids.contains(&card));
+    assert_eq!(similar_cards(&conn, text, &[card], 1).len(), 1);
+    let candidates = candidates[..2].to_vec();
+
+    let config = LlmConfig {
+        dependencies_prompt: "{question}|{answer}|{cards}".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        config.dependencies_prompt(" q ", "a", &candidates),
+        format!(
+            "q|a|{}: What is in the nucleus of an atom?\n{}: What is a proton?",
+            nucleus, proton
+        )
+    );
+
+    // Made up ids and duplicates are left out.
+    let response = format!(
+        "```json\n{{\"prerequisites\": [{}, \"{}\", {}, 999], \"missing\": [\"electric charge\", \" \"]}}\n```",
+        proton, proton, nucleus
+    );
+    let suggestions = parse_suggestions(&response, &candidates).unwrap();
+    assert_eq!(
+        suggestions,
+        vec![
+            Suggestion::Existing(CardItem::from_id(&conn, proton)),
+            Suggestion::Existing(CardItem::from_id(&conn, nucleus)),
+            Suggestion::Missing("electric charge".to_string()),
+        ]
+    );
+    assert_eq!(suggestions[2].to_string(), "new: electric charge");
+    assert!(parse_suggestions("no idea", &candidates).is_err());
+    assert_eq!(parse_suggestions("{}", &candidates).unwrap(), vec![]);
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+use crate::utils::ankitemplate::html_to_markup;
+#[cfg(test)]
+use crate::widgets::textinput::markup::{code, markup_rows, markup_spans};
+#[cfg(test)]
+use tui::style::Modifier;
+
+#[test]
+fn inline_code_test() {
+    let spans = markup_spans("call `a*b*c` with **care**");
+    let text: Vec<&str> = spans.0.iter().map(|span| span.content.as_ref()).collect();
+    assert_eq!(text, vec!["call ", "`a*b*c`", " with ", "**care**"]);
+    assert_eq!(spans.0[1].style, code());
+    assert!(spans.0[3].style.add_modifier.contains(Modifier::BOLD));
+}
+
+#[test]
+fn code_block_test() {
+    let rows = [
+        "```rust",
+        "# not a heading",
+        "  let x = *y*;",
+        "```",
+        "*after*",
+    ];
+    let spans = markup_rows(&rows);
+    assert_eq!(spans.len(), 5);
+    assert_eq!(spans[1].0.len(), 1);
+    assert_eq!(spans[1].0[0].style, code());
+    // Indentation is kept, the cursor positions have to line up with the text.
+    assert_eq!(spans[2].0[0].content, "  let x = *y*;");
+    assert!(spans[3].0[0].style.add_modifier.contains(Modifier::DIM));
+    assert!(spans[4].0[0].style.add_modifier.contains(Modifier::ITALIC));
+}
+
+#[test]
+fn anki_html_test() {
+    assert_eq!(
+        html_to_markup("What does <b>ls -a </b>do?<br>See <i>man&nbsp;ls</i>"),
+        "What does **ls -a** do?\nSee *man ls*"
+    );
+    assert_eq!(
+        html_to_markup("Use <code>Vec&lt;T&gt;</code><div>for <B>lists</B></div><!-- note -->"),
+        "Use `Vec<T>`\nfor **lists**"
+    );
+    assert_eq!(
+        html_to_markup("<ul><li>one</li><li>two<ol><li>a</li><li>b</li></ol></li></ul>done"),
+        "- one\n- two\n  1. a\n  2. b\n\ndone"
+    );
+    assert_eq!(
+        html_to_markup(
+            "Rust:<pre><code>fn main() {\n    println!(&quot;hi&quot;);\n}</code></pre>Done"
+        ),
+        "Rust:\n```\nfn main() {\n    println!(\"hi\");\n}\n```\nDone"
+    );
+    // Tags that aren't closed or carry no text don't leave markers behind.
+    assert_eq!(
+        html_to_markup("<span style=\"color:red\">red</span> <b></b><i>open"),
+        "red open"
+    );
+    assert_eq!(html_to_markup("<b> padded </b>text"), "**padded** text");
+    assert_eq!(html_to_markup("<i>é <b>ü</b></i>ñ"), "*é **ü***ñ");
+    // Misnested tags lose the markers of the inner one.
+    assert_eq!(html_to_markup("<b>a<i>b</b>c</i>"), "**ab**c");
+    assert_eq!(
+        html_to_markup("<b>          x          <i>  </b></i>"),
+        "**x**"
+    );
+    assert_eq!(
+        html_to_markup("<i>ø<b> é  <i>ü</b>  æ</i>"),
+        "*ø **é  ü**  æ*"
+    );
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+use super::test_db;
+#[cfg(test)]
+use crate::utils::card::{Card, CardTypeData, FinishedInfo};
+#[cfg(test)]
+use crate::utils::media::{import_media, CardMedia, MediaKind, Side};
+#[cfg(test)]
+use crate::utils::sql::fetch::cards::fetch_card;
+#[cfg(test)]
+use crate::utils::sql::update::set_card_media;
+#[cfg(test)]
+use std::path::{Path, PathBuf};
+
+#[test]
+fn import_media_test() {
+    let dir = std::env::temp_dir().join("speki_media_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let media = dir.join("media");
+
+    let first = dir.join("heart.PNG");
+    let copy = dir.join("copy.png");
+    let other = dir.join("lung.png");
+    std::fs::write(&first, b"heart").unwrap();
+    std::fs::write(&copy, b"heart").unwrap();
+    std::fs::write(&other, b"lung").unwrap();
+
+    let imported = import_media(&media, &first).unwrap();
+    assert_eq!(imported.parent().unwrap(), media);
+    assert_eq!(imported.extension().unwrap(), "png");
+    assert_eq!(std::fs::read(&imported).unwrap(), b"heart");
+
+    // Files with the same contents end up as the same file.
+    assert_eq!(import_media(&media, &copy).unwrap(), imported);
+    assert_ne!(import_media(&media, &other).unwrap(), imported);
+    assert_eq!(std::fs::read_dir(&media).unwrap().count(), 2);
+
+    assert!(import_media(&media, &dir.join("missing.png")).is_err());
+}
+
+#[test]
+fn media_kind_test() {
+    assert_eq!(
+        MediaKind::from_path(Path::new("a/b.MP3")),
+        Some(MediaKind::Audio)
+    );
+    assert_eq!(
+        MediaKind::from_path(Path::new("b.jpeg")),
+        Some(MediaKind::Image)
+    );
+    assert_eq!(MediaKind::from_path(Path::new("b.txt")), None);
+    assert_eq!(MediaKind::from_path(Path::new("noextension")), None);
+}
+
+#[test]
+fn card_media_test() {
+    let conn = test_db("cardmedia");
+
+    let mut media = CardMedia::default();
+    media.set(
+        Side::Front,
+        MediaKind::Audio,
+        Some(PathBuf::from("/m/q.mp3")),
+    );
+    media.set(
+        Side::Back,
+        MediaKind::Image,
+        Some(PathBuf::from("/m/a.png")),
+    );
+    assert_eq!(
+        media.get(Side::Front, MediaKind::Audio),
+        Some(&PathBuf::from("/m/q.mp3"))
+    );
+    assert_eq!(media.get(Side::Back, MediaKind::Audio), None);
+
+    let id = Card::new(CardTypeData::Finished(FinishedInfo::default()))
+        .question("q".to_string())
+        .answer("a".to_string())
+        .topic(1)
+        .media(media.clone())
+        .save_card(&conn);
+    assert_eq!(CardMedia::from_card(&fetch_card(&conn, id)), media);
+
+    media.clear(Side::Front);
+    media.set(
+        Side::Front,
+        MediaKind::Image,
+        Some(PathBuf::from("/m/q.gif")),
+    );
+    set_card_media(&conn, id, &media);
+    let card = fetch_card(&conn, id);
+    assert_eq!(card.frontaudio, None);
+    assert_eq!(card.frontimage, Some(PathBuf::from("/m/q.gif")));
+    assert_eq!(card.backimage, Some(PathBuf::from("/m/a.png")));
+}
@@ -1,14 +1,49 @@
 use std::default;
 #[cfg(test)]
+use std::io::{BufRead, BufReader, Read, Write};
+#[cfg(test)]
+use std::net::TcpListener;
+#[cfg(test)]
+use std::sync::mpsc::{self, Receiver};
+#[cfg(test)]
 use std::sync::{Arc, Mutex};
+pub mod audiotest;
+pub mod clozetest;
 pub mod databasetest;
+pub mod duplicatestest;
+pub mod epubtest;
+pub mod imagetest;
+pub mod incintervaltest;
+pub mod leechtest;
+pub mod limitstest;
+pub mod llmtest;
+pub mod markuptest;
+pub mod mediatest;
+pub mod occlusiontest;
+pub mod orderingtest;
 pub mod pdftest;
+pub mod provenancetest;
+pub mod readprogresstest;
+pub mod recordingtest;
+pub mod sessiontest;
+pub mod splittest;
+pub mod textimporttest;
+pub mod ttstest;
+pub mod webpagetest;
+pub mod wikitest;
 use rusqlite::Connection;
+use std::cell::RefCell;
 use tui::layout::Rect;
 
 use crate::{
     app::{AppData, Audio, Config, Widget},
-    utils::misc::SpekiPaths,
+    utils::{
+        aliases::CardID,
+        card::{Card, CardTypeData, FinishedInfo},
+        graphics::Graphics,
+        misc::SpekiPaths,
+        sql::init_db,
+    },
     widgets::textinput::Field,
     MyKey,
 };
@@ -19,15 +54,83 @@ pub fn get_appdata() -> AppData {
         Connection::open(&paths.database).expect("Failed to connect to database."),
     ));
     let config = Config::new(&paths);
-    let audio = Audio::new();
+    let audio = Audio::new(&config.audio);
+    let graphics = RefCell::new(Graphics::new(config.images.protocol));
     AppData {
         conn,
         audio,
         config,
         paths,
+        graphics,
     }
 }
 
+/// A new database in the temp folder, so that tests don't touch the real one or each other's.
+#[cfg(test)]
+pub fn test_db(name: &str) -> Arc<Mutex<Connection>> {
+    let path = std::env::temp_dir().join(format!("speki_{}_test.db", name));
+    let _ = std::fs::remove_file(&path);
+    init_db(&path).unwrap();
+    Arc::new(Mutex::new(Connection::open(&path).unwrap()))
+}
+
+/// Saves a finished card in the first topic.
+#[cfg(test)]
+pub fn save(conn: &Arc<Mutex<Connection>>, question: &str, answer: &str) -> CardID {
+    Card::new(CardTypeData::Finished(FinishedInfo::default()))
+        .question(question.to_string())
+        .answer(answer.to_string())
+        .topic(1)
+        .save_card(conn)
+}
+
+/// A stand-in http server on a free local port, which answers every request with the status and
+/// body that `respond` picks for it. Returns the address of the server, and the requests it got.
+#[cfg(test)]
+pub fn mock_server<F>(content_type: &'static str, respond: F) -> (String, Receiver<String>)
+where
+    F: Fn(&str) -> (&'static str, &'static str) + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = stream.unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut request = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let mut content = vec![0; length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+
+            let (status, body) = respond(&request);
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
+            );
+            // The client may have given up waiting already.
+            let _ = (&stream).write_all(response.as_bytes());
+            let _ = sender.send(request);
+        }
+    });
+    (format!("http://127.0.0.1:{}", port), receiver)
+}
+
 #[test]
 fn is_last_visrow() {
     let input_text = "0123456789012".to_string();
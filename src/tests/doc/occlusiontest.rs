@@ -0,0 +1,141 @@
+#[cfg(test)]
+use super::test_db;
+#[cfg(test)]
+use crate::utils::card::CardTypeData;
+#[cfg(test)]
+use crate::utils::graphics::{kitty_escape, sixel_escape};
+#[cfg(test)]
+use crate::utils::image::{Image, Overlay, Region};
+#[cfg(test)]
+use crate::utils::occlusion::{make_occlusion_cards, Mask, MASK_COLOR, TARGET_COLOR};
+#[cfg(test)]
+use crate::utils::sql::delete::remove_card;
+#[cfg(test)]
+use crate::utils::sql::fetch::cards::fetch_card;
+#[cfg(test)]
+use crate::utils::sql::fetch::get_occlusion;
+
+#[cfg(test)]
+fn region(x: f32, y: f32, w: f32, h: f32) -> Region {
+    Region { x, y, w, h }
+}
+
+#[test]
+fn png_test() {
+    let mut image = Image::new(7, 5, [10, 20, 30, 255]);
+    image.draw(&Overlay::Fill(region(0., 0., 0.5, 0.5), [200, 100, 0]));
+    let decoded = Image::from_png(&image.to_png()).unwrap();
+    assert_eq!(decoded, image);
+    assert!(Image::from_png(b"not a png").is_err());
+}
+
+#[test]
+fn overlay_test() {
+    let background = [255, 255, 255, 255];
+    let mut image = Image::new(100, 100, background);
+    image.draw(&Overlay::Fill(region(0.1, 0.1, 0.2, 0.2), TARGET_COLOR));
+    image.draw(&Overlay::Outline(region(0.5, 0.5, 0.4, 0.4), MASK_COLOR));
+
+    assert_eq!(image.pixel(15, 15), [231, 76, 60, 255]);
+    assert_eq!(image.pixel(35, 35), background);
+    assert_eq!(image.pixel(50, 70), [255, 214, 102, 255]);
+    assert_eq!(image.pixel(70, 70), background);
+
+    // Regions reaching outside the image are pulled back in.
+    let clamped = region(0.9, -0.2, 0.3, 1.5).clamped();
+    assert_eq!(clamped, region(0.7, 0., 0.3, 1.));
+}
+
+#[test]
+fn escape_test() {
+    let image = Image::new(20, 40, [255, 0, 0, 255]);
+    let kitty = kitty_escape(&image, 2, 2);
+    assert!(kitty.starts_with("\x1b_Ga=T,f=100,q=2,C=1,c=2,r=2,m=0;"));
+    assert!(kitty.ends_with("\x1b\\"));
+
+    let sixel = sixel_escape(&image);
+    assert!(sixel.starts_with("\x1bPq\"1;1;20;40"));
+    // Pure red is the last level of the first channel in the color cube.
+    assert!(sixel.contains("#180!20~"));
+    assert!(sixel.ends_with("\x1b\\"));
+}
+
+#[test]
+fn occlusion_cards_test() {
+    let conn = test_db("occlusion");
+
+    let masks = vec![
+        Mask {
+            region: region(0.1, 0.1, 0.2, 0.2),
+            label: "Heart".to_string(),
+        },
+        Mask {
+            region: region(0.5, 0.5, 0.2, 0.2),
+            label: "Lung".to_string(),
+        },
+    ];
+    let dir = std::env::temp_dir().join("speki_occlusion_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let original = dir.join("anatomy.png");
+    std::fs::write(&original, Image::new(4, 4, [0, 0, 0, 255]).to_png()).unwrap();
+    let media = dir.join("media");
+    let ids = make_occlusion_cards(
+        &conn,
+        &media,
+        &original,
+        masks.clone(),
+        "What is hidden?".to_string(),
+        1,
+    )
+    .unwrap();
+    assert_eq!(ids.len(), 2);
+
+    // The cards point to a copy in the media folder, not to the original.
+    let image = fetch_card(&conn, ids[0]).frontimage.unwrap();
+    assert_eq!(image.parent().unwrap(), media);
+    assert_eq!(
+        std::fs::read(&image).unwrap(),
+        std::fs::read(&original).unwrap()
+    );
+    assert!(make_occlusion_cards(
+        &conn,
+        &media,
+        &dir.join("missing.png"),
+        masks.clone(),
+        String::new(),
+        1
+    )
+    .is_err());
+
+    for (target, &id) in ids.iter().enumerate() {
+        let card = fetch_card(&conn, id);
+        assert_eq!(card.question, "What is hidden?");
+        assert_eq!(card.answer, masks[target].label);
+        assert_eq!(card.frontimage, Some(image.clone()));
+        assert!(matches!(card.cardtype, CardTypeData::Finished(_)));
+
+        let occlusion = get_occlusion(&conn, id).unwrap();
+        assert_eq!(occlusion.target, target);
+        assert_eq!(occlusion.masks, masks);
+
+        let hidden = occlusion.overlays(false);
+        assert_eq!(
+            hidden[target],
+            Overlay::Fill(masks[target].region, TARGET_COLOR)
+        );
+        assert_eq!(
+            hidden[1 - target],
+            Overlay::Fill(masks[1 - target].region, MASK_COLOR)
+        );
+        let revealed = occlusion.overlays(true);
+        assert_eq!(
+            revealed[target],
+            Overlay::Outline(masks[target].region, TARGET_COLOR)
+        );
+    }
+    assert!(get_occlusion(&conn, 999).is_none());
+    // Card ids get reused, so a deleted card mustn't leave its occlusion behind.
+    remove_card(&conn, ids[1]).unwrap();
+    assert!(get_occlusion(&conn, ids[1]).is_none());
+}
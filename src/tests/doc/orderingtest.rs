@@ -0,0 +1,58 @@
+#[cfg(test)]
+use crate::utils::ordering::{order_items, ReviewConfig, ReviewItem, ReviewOrder};
+
+#[cfg(test)]
+fn item(id: u32, topic: u32, priority: u32, strength: f32, overdue: f32) -> ReviewItem {
+    ReviewItem {
+        id,
+        topic,
+        priority,
+        strength,
+        overdue,
+    }
+}
+
+#[cfg(test)]
+fn ordered_ids(order: ReviewOrder) -> Vec<u32> {
+    let items = vec![
+        item(1, 1, 0, 0.5, 3.),
+        item(2, 1, 0, 0.2, 1.),
+        item(3, 2, 0, 0.8, 9.),
+        item(4, 2, 1, 0.7, 0.),
+        item(5, 3, 0, 0.6, 2.),
+    ];
+    let config = ReviewConfig {
+        order,
+        seed: Some(7),
+    };
+    order_items(items, order, &mut config.rng())
+        .into_iter()
+        .map(|item| item.id)
+        .collect()
+}
+
+#[test]
+fn review_order_test() {
+    assert_eq!(ordered_ids(ReviewOrder::Overdue), vec![4, 3, 1, 5, 2]);
+    assert_eq!(ordered_ids(ReviewOrder::Strength), vec![4, 2, 1, 5, 3]);
+    assert_eq!(
+        ordered_ids(ReviewOrder::Random),
+        ordered_ids(ReviewOrder::Random)
+    );
+
+    let topic = ordered_ids(ReviewOrder::Topic);
+    assert_eq!(topic[0], 4);
+    assert_eq!(&topic[3..], &[3, 5]);
+
+    let interleaved = ordered_ids(ReviewOrder::Interleaved);
+    assert_eq!(interleaved[0], 4);
+    let topics: Vec<u32> = interleaved[1..]
+        .iter()
+        .map(|id| match id {
+            1 | 2 => 1,
+            3 => 2,
+            _ => 3,
+        })
+        .collect();
+    assert!(topics.windows(2).all(|pair| pair[0] != pair[1]));
+}
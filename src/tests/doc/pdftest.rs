@@ -1 +1,76 @@
+#[cfg(test)]
+use crate::utils::pdf::{first_page, page_at_row, pdf_sections};
+#[cfg(test)]
+use lopdf::{
+    content::{Content, Operation},
+    dictionary, Document, Object, Stream,
+};
 
+#[cfg(test)]
+fn make_pdf(pages: &[&str]) -> Document {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Courier",
+    });
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => font_id },
+    });
+    let mut kids = vec![];
+    for text in pages {
+        let content = Content {
+            operations: vec![
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec!["F1".into(), 12.into()]),
+                Operation::new("Td", vec![100.into(), 600.into()]),
+                Operation::new("Tj", vec![Object::string_literal(*text)]),
+                Operation::new("ET", vec![]),
+            ],
+        };
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        kids.push(page_id.into());
+    }
+    let pages = dictionary! {
+        "Type" => "Pages",
+        "Kids" => kids,
+        "Count" => pages.len() as u32,
+        "Resources" => resources_id,
+        "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+    };
+    doc.objects.insert(pages_id, Object::Dictionary(pages));
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc
+}
+
+#[test]
+fn pdf_pages_test() {
+    let doc = make_pdf(&["first page", "second page"]);
+    let sections = pdf_sections(&doc);
+    assert_eq!(sections.len(), 2);
+    assert!(sections[0].starts_with("[page 1]\n"));
+    assert!(sections[0].contains("first page"));
+    assert!(sections[1].contains("second page"));
+    assert_eq!(first_page(&sections[1]), Some(2));
+}
+
+#[test]
+fn page_at_row_test() {
+    let rows: Vec<String> = "Chapter\n\n[page 3]\nfoo\n[page 4]\nbar"
+        .lines()
+        .map(String::from)
+        .collect();
+    assert_eq!(page_at_row(&rows, 0), None);
+    assert_eq!(page_at_row(&rows, 3), Some(3));
+    assert_eq!(page_at_row(&rows, 5), Some(4));
+}
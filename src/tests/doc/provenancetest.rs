@@ -0,0 +1,75 @@
+#[cfg(test)]
+use super::test_db;
+#[cfg(test)]
+use crate::utils::cloze::ClozeContext;
+#[cfg(test)]
+use crate::utils::incread::{IncRead, Provenance};
+#[cfg(test)]
+use crate::utils::sql::fetch::cards::fetch_card;
+#[cfg(test)]
+use crate::utils::sql::fetch::{get_provenance, load_extracted_ranges};
+#[cfg(test)]
+use crate::utils::sql::insert::{new_incread, set_provenance};
+#[cfg(test)]
+use crate::widgets::textinput::markup::{extracted, highlight, markup_spans};
+#[cfg(test)]
+use crate::widgets::textinput::{CursorPos, Field};
+
+#[test]
+fn offsets_test() {
+    let mut field = Field::new_with_text("ab\ncdé\nf".to_string(), 1, 1);
+    assert_eq!(field.offset_of(&field.cursor), 4);
+    assert_eq!(field.pos_at_offset(7), CursorPos { row: 2, column: 0 });
+    assert_eq!(field.pos_at_offset(100), CursorPos { row: 2, column: 1 });
+
+    field.set_visual_mode();
+    field.cursor = CursorPos { row: 1, column: 2 };
+    assert_eq!(field.selection_range(), Some((4, 6)));
+    assert_eq!(field.return_selection().unwrap(), "dé");
+
+    let spans = highlight(markup_spans("abcdef"), &[(1, 3)], extracted());
+    let text: Vec<&str> = spans.0.iter().map(|span| span.content.as_ref()).collect();
+    assert_eq!(text, vec!["a", "bc", "def"]);
+}
+
+#[test]
+fn provenance_test() {
+    let conn = test_db("provenance");
+
+    let provenance = Provenance {
+        origin: "/books/moby.epub".to_string(),
+        title: "Moby Dick".to_string(),
+        author: "Herman Melville".to_string(),
+    };
+    let book = new_incread(&conn, 0, 1, "Moby Dick".to_string(), false);
+    set_provenance(&conn, book, &provenance).unwrap();
+    let chapter = new_incread(
+        &conn,
+        book,
+        1,
+        "Call me Ishmael.\nSome years ago".to_string(),
+        true,
+    );
+    assert_eq!(get_provenance(&conn, chapter), Some(provenance));
+
+    let mut text = IncRead::new(&conn, chapter);
+    text.source.cursor = CursorPos { row: 0, column: 8 };
+    text.source.set_visual_mode();
+    text.source.cursor = CursorPos { row: 0, column: 14 };
+    text.extract(&conn);
+    text.source.cursor = CursorPos { row: 1, column: 5 };
+    text.source.set_visual_mode();
+    text.source.cursor = CursorPos { row: 1, column: 9 };
+    text.cloze(&conn, ClozeContext::Sentence);
+
+    assert_eq!(text.extracts[0].text, "Ishmael");
+    assert_eq!(
+        load_extracted_ranges(&conn, chapter),
+        vec![(8, 15), (22, 27)]
+    );
+    assert_eq!(
+        fetch_card(&conn, text.clozes[0].id).source_range,
+        Some((22, 27))
+    );
+    assert_eq!(IncRead::new(&conn, chapter).source.highlights.len(), 2);
+}
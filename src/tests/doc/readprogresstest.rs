@@ -0,0 +1,76 @@
+#[cfg(test)]
+use super::test_db;
+#[cfg(test)]
+use crate::utils::incread::{progress_bar, section_at, IncRead};
+#[cfg(test)]
+use crate::utils::sql::delete::delete_bookmark;
+#[cfg(test)]
+use crate::utils::sql::fetch::{load_bookmarks, load_inc_items};
+#[cfg(test)]
+use crate::utils::sql::insert::{new_bookmark, new_incread};
+#[cfg(test)]
+use crate::widgets::textinput::CursorPos;
+
+#[cfg(test)]
+fn rows(text: &str) -> Vec<String> {
+    text.split('\n').map(String::from).collect()
+}
+
+#[test]
+fn section_test() {
+    let text = rows("# One\nabc\n# Two\ndefg");
+    assert_eq!(section_at(&text, 7), (0, 9));
+    assert_eq!(section_at(&text, 10), (10, 20));
+    assert_eq!(section_at(&text, 17), (10, 20));
+    assert_eq!(section_at(&rows("ab\ncd"), 1), (0, 5));
+
+    assert_eq!(progress_bar(0.4, 10), "[####------]  40%");
+    assert_eq!(progress_bar(1.5, 4), "[####] 100%");
+}
+
+#[test]
+fn read_progress_test() {
+    let conn = test_db("read_progress");
+
+    let id = new_incread(&conn, 0, 1, "# One\nabc\n# Two\ndefg".to_string(), true);
+    let mut text = IncRead::new(&conn, id);
+    assert!(text.last_read.is_none());
+
+    text.source.cursor = CursorPos { row: 1, column: 0 };
+    assert_eq!(text.progress(), 0.3);
+
+    text.source.cursor = CursorPos { row: 2, column: 0 };
+    text.toggle_read(&conn);
+    assert_eq!(text.source.dimmed, vec![(10, 20)]);
+    text.source.cursor = CursorPos { row: 1, column: 2 };
+    assert_eq!(text.progress(), 0.9);
+    text.update_text(&conn);
+
+    let mut text = IncRead::new(&conn, id);
+    assert_eq!(text.source.cursor, CursorPos { row: 1, column: 2 });
+    assert_eq!(text.source.dimmed, vec![(10, 20)]);
+    assert!(text.last_read.is_some());
+    let source = load_inc_items(&conn, 1)
+        .unwrap()
+        .into_iter()
+        .find(|item| item.id == id)
+        .unwrap();
+    assert_eq!(source.progress, Some(0.9));
+
+    text.source.cursor = CursorPos { row: 3, column: 1 };
+    text.toggle_read(&conn);
+    assert!(IncRead::new(&conn, id).source.dimmed.is_empty());
+
+    new_bookmark(&conn, id, "later", 16).unwrap();
+    new_bookmark(&conn, id, "start", 0).unwrap();
+    let bookmarks = load_bookmarks(&conn, id);
+    let names: Vec<&str> = bookmarks.iter().map(|mark| mark.name.as_str()).collect();
+    assert_eq!(names, vec!["start", "later"]);
+    text.source.jump_to_offset(bookmarks[1].offset);
+    assert_eq!(text.source.cursor, CursorPos { row: 3, column: 0 });
+
+    delete_bookmark(&conn, bookmarks[0].id).unwrap();
+    text.reload_bookmarks(&conn);
+    assert_eq!(text.bookmarks.len(), 1);
+    assert_eq!(text.bookmarks[0].name, "later");
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+use crate::utils::recording::{FileInput, Recorder};
+
+#[test]
+fn recorder_test() {
+    let dir = std::env::temp_dir().join("speki_recording_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 8000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let spoken = dir.join("spoken.wav");
+    let samples: Vec<i16> = (0..800).map(|i| ((i % 50) * 600 - 15000) as i16).collect();
+    let mut writer = hound::WavWriter::create(&spoken, spec).unwrap();
+    for sample in &samples {
+        writer.write_sample(*sample).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let mut recorder = Recorder::new(Box::new(FileInput::new(spoken).unwrap()));
+    assert!(!recorder.is_recording());
+    recorder.start().unwrap();
+    assert!(recorder.is_recording());
+
+    let recorded = dir.join("temp").join("recording.wav");
+    recorder.stop(&recorded).unwrap();
+    assert!(!recorder.is_recording());
+
+    let mut reader = hound::WavReader::open(&recorded).unwrap();
+    assert_eq!(reader.spec(), spec);
+    let result: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+    assert_eq!(result, samples);
+
+    // Every recording starts out empty.
+    recorder.start().unwrap();
+    recorder.start().unwrap();
+    recorder.stop(&recorded).unwrap();
+    assert_eq!(hound::WavReader::open(&recorded).unwrap().len(), 800);
+
+    // A cancelled recording leaves nothing behind for the next one.
+    recorder.start().unwrap();
+    recorder.cancel();
+    assert!(!recorder.is_recording());
+    recorder.stop(&recorded).unwrap();
+    assert_eq!(hound::WavReader::open(&recorded).unwrap().len(), 0);
+
+    assert!(FileInput::new(dir.join("missing.wav")).is_err());
+}
@@ -0,0 +1,10 @@
+#[cfg(test)]
+use crate::tabs::review::session::calc_streak;
+
+#[test]
+fn streak_test() {
+    assert_eq!(calc_streak(&[], 100), 0);
+    assert_eq!(calc_streak(&[90, 98, 99, 100], 100), 3);
+    assert_eq!(calc_streak(&[97, 98, 99], 100), 3);
+    assert_eq!(calc_streak(&[97, 98], 100), 0);
+}
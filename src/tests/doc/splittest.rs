@@ -0,0 +1,163 @@
+#[cfg(test)]
+use super::{get_appdata, save, test_db};
+#[cfg(test)]
+use crate::utils::aliases::*;
+#[cfg(test)]
+use crate::utils::card::{RecallGrade, Review};
+#[cfg(test)]
+use crate::utils::split::{split_card, SplitPart, SplitReview};
+#[cfg(test)]
+use crate::utils::sql::fetch::cards::{fetch_card, get_history};
+#[cfg(test)]
+use crate::utils::sql::fetch::CardQuery;
+#[cfg(test)]
+use crate::utils::sql::in_transaction;
+#[cfg(test)]
+use crate::utils::sql::insert::{revlog_new, update_both};
+#[cfg(test)]
+use crate::utils::statelist::KeyHandler;
+#[cfg(test)]
+use crate::MyKey;
+#[cfg(test)]
+use rusqlite::Connection;
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
+#[cfg(test)]
+use std::time::Duration;
+
+/// A card with a dependency, a dependent and two reviews from the past.
+#[cfg(test)]
+fn overloaded(conn: &Arc<Mutex<Connection>>) -> (CardID, CardID, CardID, Vec<Review>) {
+    let card = save(conn, "What are the capitals of France and Spain?", "a");
+    let dependency = save(conn, "What is a capital?", "a");
+    let dependent = save(conn, "Where are the Louvre and the Prado?", "a");
+    update_both(conn, card, dependency).unwrap();
+    update_both(conn, dependent, card).unwrap();
+
+    let reviews: Vec<Review> = [(100, RecallGrade::Failed), (200, RecallGrade::Easy)]
+        .into_iter()
+        .map(|(date, grade)| Review {
+            grade,
+            date: Duration::from_secs(date),
+            answertime: 1.,
+        })
+        .collect();
+    for review in &reviews {
+        revlog_new(conn, card, review).unwrap();
+    }
+    (card, dependency, dependent, reviews)
+}
+
+#[cfg(test)]
+fn parts(reviews: &[Review]) -> Vec<SplitPart> {
+    vec![
+        SplitPart {
+            question: "What is the capital of France?".to_string(),
+            answer: "Paris".to_string(),
+            reviews: vec![reviews[0].date, reviews[1].date],
+        },
+        SplitPart {
+            question: " What is the capital of Spain?\n".to_string(),
+            answer: "Madrid".to_string(),
+            reviews: vec![reviews[1].date],
+        },
+    ]
+}
+
+#[test]
+fn split_test() {
+    let conn = test_db("split");
+    let (card, dependency, dependent, reviews) = overloaded(&conn);
+
+    let ids = split_card(&conn, card, &parts(&reviews), false).unwrap();
+    assert_eq!(ids.len(), 2);
+    assert!(!CardQuery::default().fetch_card_ids(&conn).contains(&card));
+    assert!(get_history(&conn, card).is_empty());
+
+    let france = fetch_card(&conn, ids[0]);
+    let spain = fetch_card(&conn, ids[1]);
+    assert_eq!(spain.question, "What is the capital of Spain?");
+    assert_eq!(france.dependencies, vec![dependency]);
+    assert_eq!(france.dependents, vec![dependent]);
+    assert_eq!(fetch_card(&conn, dependent).dependencies, ids);
+    assert_eq!(fetch_card(&conn, dependency).dependents, ids);
+
+    // Besides the reviews they were given, the new cards got one when they were saved.
+    let dates = |id| -> Vec<u64> {
+        get_history(&conn, id)
+            .iter()
+            .map(|review| review.date.as_secs())
+            .collect()
+    };
+    assert_eq!(dates(france.id)[..2], [100, 200]);
+    assert_eq!(dates(france.id).len(), 3);
+    assert_eq!(dates(spain.id)[..1], [200]);
+    assert_eq!(dates(spain.id).len(), 2);
+}
+
+#[test]
+fn split_summary_test() {
+    let conn = test_db("split_summary");
+    let (card, dependency, dependent, reviews) = overloaded(&conn);
+
+    let ids = split_card(&conn, card, &parts(&reviews), true).unwrap();
+    let summary = fetch_card(&conn, card);
+    assert_eq!(
+        summary.question,
+        "What are the capitals of France and Spain?"
+    );
+    assert_eq!(
+        summary.dependencies,
+        [vec![dependency], ids.clone()].concat()
+    );
+    assert_eq!(summary.dependents, vec![dependent]);
+    assert_eq!(get_history(&conn, card).len(), 3);
+    for id in &ids {
+        assert_eq!(fetch_card(&conn, *id).dependents, vec![card]);
+    }
+    assert_eq!(fetch_card(&conn, dependent).dependencies, vec![card]);
+}
+
+#[test]
+fn split_rollback_test() {
+    let conn = test_db("split_rollback");
+    let before = CardQuery::default().fetch_card_ids(&conn);
+    let result: rusqlite::Result<()> = in_transaction(&conn, || {
+        save(&conn, "Never saved", "a");
+        Err(rusqlite::Error::InvalidQuery)
+    });
+    assert!(result.is_err());
+    assert_eq!(CardQuery::default().fetch_card_ids(&conn), before);
+
+    let panicked = std::panic::catch_unwind(|| {
+        let _: rusqlite::Result<()> = in_transaction(&conn, || {
+            save(&conn, "Never saved either", "a");
+            panic!("database error");
+        });
+    });
+    assert!(panicked.is_err());
+    assert_eq!(CardQuery::default().fetch_card_ids(&conn), before);
+
+    let card = in_transaction(&conn, || Ok(save(&conn, "Saved", "a"))).unwrap();
+    assert!(CardQuery::default().fetch_card_ids(&conn).contains(&card));
+}
+
+#[test]
+fn split_review_test() {
+    let review = Review {
+        grade: RecallGrade::Decent,
+        date: Duration::from_secs(0),
+        answertime: 1.,
+    };
+    let mut item = SplitReview::new(review, 3);
+    assert!(item.to_string().ends_with("decent → 1, 2, 3"));
+
+    let appdata = get_appdata();
+    assert!(item.keyhandler(&appdata, MyKey::Char('2')));
+    assert!(!item.keyhandler(&appdata, MyKey::Char('x')));
+    assert_eq!(item.parts, vec![true, false, true]);
+    item.keyhandler(&appdata, MyKey::Char('a'));
+    assert_eq!(item.parts, vec![true; 3]);
+    item.keyhandler(&appdata, MyKey::Char('a'));
+    assert!(item.to_string().ends_with("→ -"));
+}
@@ -0,0 +1,39 @@
+#[cfg(test)]
+use crate::utils::textimport::{html_to_markdown, load_document, split_sections, strip_markdown};
+
+#[test]
+fn split_markdown_test() {
+    let markdown = "intro\n# First\nsome **bold** text\n```\n# not a heading\n```\n## Second\n[a link](http://x.org)";
+    let sections = split_sections(markdown);
+    assert_eq!(sections.len(), 3);
+    assert_eq!(sections[0], ("".to_string(), "intro".to_string()));
+    assert_eq!(sections[1].0, "First");
+    assert!(sections[1].1.contains("# not a heading"));
+    assert_eq!(
+        strip_markdown(&sections[1].1).lines().next(),
+        Some("some bold text")
+    );
+    assert_eq!(strip_markdown(&sections[2].1), "a link");
+}
+
+#[test]
+fn html_import_test() {
+    let html = "<html><head><title>x</title></head><body><h1>Title</h1><p>Fish &amp; chips</p><script>var a;</script><h2>Next</h2><p>more</p></body></html>";
+    let markdown = html_to_markdown(html);
+    assert_eq!(markdown, "# Title\n\nFish & chips\n\n## Next\n\nmore");
+    let sections = split_sections(&markdown);
+    assert_eq!(
+        sections[0],
+        ("Title".to_string(), "Fish & chips".to_string())
+    );
+    assert_eq!(sections[1], ("Next".to_string(), "more".to_string()));
+}
+
+#[test]
+fn unreadable_document_test() {
+    let path = std::env::temp_dir().join("speki_missing_document.md");
+    let _ = std::fs::remove_file(&path);
+    let book = load_document(&path);
+    assert!(book.desc.starts_with("Couldn't read "));
+    assert!(book.chapters.is_empty());
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+use crate::utils::media::Side;
+#[cfg(test)]
+use crate::utils::tts::{cache_path, synthesize, TtsConfig, TtsSides};
+#[cfg(test)]
+use std::os::unix::fs::PermissionsExt;
+#[cfg(test)]
+use std::path::{Path, PathBuf};
+
+/// An engine that writes the voice and the text it got on stdin or as its third argument.
+#[cfg(test)]
+fn fake_engine(dir: &Path) -> PathBuf {
+    let path = dir.join("fake_tts.sh");
+    let script = "#!/bin/sh\nif [ -n \"$3\" ]; then text=\"$3\"; else text=$(cat); fi\nprintf '%s:%s' \"$1\" \"$text\" > \"$2\"\n";
+    std::fs::write(&path, script).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[test]
+fn synthesize_test() {
+    let dir = std::env::temp_dir().join("speki_tts_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let media = dir.join("media");
+    let engine = fake_engine(&dir);
+
+    let config = TtsConfig {
+        command: format!("{} {{voice}} {{output}}", engine.display()),
+        ..Default::default()
+    };
+    let path = synthesize(&config, &media, "hola mundo", "es").unwrap();
+    assert_eq!(path, cache_path(&media, "hola mundo", "es"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "es:hola mundo");
+    assert_ne!(path, cache_path(&media, "hola mundo", "en"));
+
+    // Cached speech doesn't need the engine.
+    std::fs::remove_file(&engine).unwrap();
+    assert_eq!(
+        synthesize(&config, &media, "hola mundo", "es").unwrap(),
+        path
+    );
+    assert!(synthesize(&config, &media, "adiós", "es").is_err());
+    assert!(!cache_path(&media, "adiós", "es").exists());
+
+    let engine = fake_engine(&dir);
+    let config = TtsConfig {
+        command: format!("{} {{voice}} {{output}} {{text}}", engine.display()),
+        ..Default::default()
+    };
+    let path = synthesize(&config, &media, "guten tag", "de").unwrap();
+    assert_eq!(std::fs::read_to_string(path).unwrap(), "de:guten tag");
+
+    let failing = TtsConfig {
+        command: "false {output}".to_string(),
+        ..Default::default()
+    };
+    assert!(synthesize(&failing, &media, "nothing", "en").is_err());
+}
+
+#[test]
+fn tts_config_test() {
+    let config: TtsConfig = toml::from_str(
+        r#"
+command = "espeak-ng -v {voice} -w {output}"
+sides = "question"
+[voices]
+Spanish = "es"
+"#,
+    )
+    .unwrap();
+    assert!(config.is_enabled());
+    assert!(!TtsConfig::default().is_enabled());
+    assert_eq!(config.sides, TtsSides::Question);
+    assert!(config.sides.includes(Side::Front));
+    assert!(!config.sides.includes(Side::Back));
+    assert!(TtsSides::Both.includes(Side::Back));
+
+    assert_eq!(config.autoplay_voice("Spanish"), Some("es"));
+    assert_eq!(config.autoplay_voice("Math"), None);
+    assert_eq!(config.voice("Math"), "en");
+
+    let autoplay = TtsConfig {
+        autoplay: true,
+        ..config
+    };
+    assert_eq!(autoplay.autoplay_voice("Math"), Some("en"));
+}
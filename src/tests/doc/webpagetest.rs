@@ -0,0 +1,104 @@
+#[cfg(test)]
+use super::mock_server;
+#[cfg(test)]
+use crate::utils::webpage::{fetch_webpage, page_sections};
+#[cfg(test)]
+use crate::utils::wiki::ReqwestClient;
+
+#[cfg(test)]
+const BLOG_POST: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>Spaced repetition | Some blog</title>
+  <meta name="author" content="Ada Writer">
+  <script>var tracking = "<p>not content</p>";</script>
+</head>
+<body>
+  <nav><a href="/">Home</a> <a href="/about">About</a></nav>
+  <div class="sidebar"><p>Subscribe to the newsletter, it is great, really, truly.</p></div>
+  <div class="post-content">
+    <h1>Spaced repetition</h1>
+    <p>Reviewing at growing intervals, as <a href="/papers/ebbinghaus.html">Ebbinghaus</a> found,
+    beats cramming &mdash; by a lot.</p>
+    <h2>Why it works</h2>
+    <p>Memories decay, and every review at the edge of forgetting makes them <em>last longer</em>, which is the point.
+    <p>Second paragraph without a closing tag, long enough to count as text.</p>
+    <ul><li>One<li>Two</ul>
+    <h3>Details</h3>
+    <p>See <a href="https://example.org/more">the paper</a>&#8217;s appendix for the numbers.</p>
+  </div>
+  <div id="comments"><p>First! This is a comment that is long enough, with commas, to score.</p></div>
+  <footer><p>Copyright and such, all rights reserved, forever and ever.</p></footer>
+</body>
+</html>"#;
+
+/// Serves a blog post at /post and a 404 everywhere else.
+#[cfg(test)]
+fn fixture_server() -> String {
+    let (server, _requests) = mock_server("text/html", |request| {
+        if request.starts_with("GET /post ") {
+            ("200 OK", BLOG_POST)
+        } else {
+            ("404 Not Found", "")
+        }
+    });
+    server
+}
+
+#[test]
+fn webpage_test() {
+    let server = fixture_server();
+    let page = fetch_webpage(&ReqwestClient, &format!("{}/post", server)).unwrap();
+    assert_eq!(page.title, "Spaced repetition | Some blog");
+    assert_eq!(page.author, "Ada Writer");
+    assert_eq!(page.url, format!("{}/post", server));
+
+    let expected = format!(
+        "# Spaced repetition\n\n\
+        Reviewing at growing intervals, as [Ebbinghaus]({}/papers/ebbinghaus.html) found, beats cramming — by a lot.\n\n\
+        ## Why it works\n\n\
+        Memories decay, and every review at the edge of forgetting makes them *last longer*, which is the point.\n\n\
+        Second paragraph without a closing tag, long enough to count as text.\n\n\
+        - One\n\
+        - Two\n\n\
+        ### Details\n\n\
+        See [the paper](https://example.org/more)’s appendix for the numbers.",
+        server
+    );
+    assert_eq!(page.text, expected);
+
+    let book = page.into_book();
+    assert_eq!(book.provenance.origin, format!("{}/post", server));
+    assert_eq!(book.provenance.author, "Ada Writer");
+    let outline: Vec<(&str, usize)> = book
+        .chapters
+        .iter()
+        .map(|chapter| (chapter.title.as_str(), chapter.depth))
+        .collect();
+    assert_eq!(
+        outline,
+        vec![
+            ("Spaced repetition", 0),
+            ("Why it works", 1),
+            ("Details", 2)
+        ]
+    );
+
+    assert!(fetch_webpage(&ReqwestClient, &format!("{}/missing", server)).is_err());
+}
+
+#[test]
+fn page_sections_test() {
+    let sections = page_sections("Title", "Intro text.\n\n## First\n\nBody.");
+    let outline: Vec<(&str, usize, &str)> = sections
+        .iter()
+        .map(|chapter| (chapter.title.as_str(), chapter.depth, chapter.text.as_str()))
+        .collect();
+    assert_eq!(
+        outline,
+        vec![
+            ("Title", 0, "# Title\n\nIntro text."),
+            ("First", 0, "# First\n\nBody.")
+        ]
+    );
+}
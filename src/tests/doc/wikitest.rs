@@ -0,0 +1,69 @@
+#[cfg(test)]
+use super::mock_server;
+#[cfg(test)]
+use crate::utils::wiki::{wiki_sections, ReqwestClient, Wiki, WikiPage};
+
+/// Serves canned api responses, picked by what the request asks for.
+#[cfg(test)]
+fn stand_in_server() -> String {
+    let (server, _requests) = mock_server("application/json", |request| {
+        let body = if !request.starts_with("GET /sv/api.php?") {
+            r#"{}"#
+        } else if request.contains("list=search") {
+            r#"{"query":{"search":[{"title":"Mercury (planet)"},{"title":"Mercury (element)"}]}}"#
+        } else if request.contains("prop=links") {
+            r#"{"query":{"pages":[{"title":"Mercury","links":[{"title":"Mercury (planet)"},{"title":"Mercury (element)"}]}]}}"#
+        } else if request.contains("titles=Mercury&") {
+            r#"{"query":{"pages":[{"title":"Mercury","pageprops":{"disambiguation":""},"extract":"Mercury may refer to:"}]}}"#
+        } else if request.contains("titles=Nowhere") {
+            r#"{"query":{"pages":[{"title":"Nowhere","missing":true}]}}"#
+        } else {
+            r#"{"query":{"pages":[{"title":"Mercury (planet)","extract":"Intro text.\n\n== Orbit ==\nIt orbits.\n\n=== Resonance ===\nSpin.\n\n== See also ==\n\n"}]}}"#
+        };
+        ("200 OK", body)
+    });
+    format!("{}/{{language}}/api.php", server)
+}
+
+#[test]
+fn wiki_search_test() {
+    let wiki = Wiki::with_client(Box::new(ReqwestClient), &stand_in_server(), "sv");
+    assert_eq!(
+        wiki.search("mercury").unwrap(),
+        vec!["Mercury (planet)", "Mercury (element)"]
+    );
+    match wiki.page("Mercury").unwrap() {
+        WikiPage::Disambiguation { title, options } => {
+            assert_eq!(title, "Mercury");
+            assert_eq!(options, vec!["Mercury (planet)", "Mercury (element)"]);
+        }
+        _ => panic!("expected a disambiguation page"),
+    }
+    assert!(matches!(wiki.page("Nowhere").unwrap(), WikiPage::Missing));
+    match wiki.page("Mercury (planet)").unwrap() {
+        WikiPage::Article { title, text } => {
+            assert_eq!(title, "Mercury (planet)");
+            assert!(text.starts_with("Intro text."));
+        }
+        _ => panic!("expected an article"),
+    }
+    assert_eq!(
+        wiki.article_url("Mercury (planet)"),
+        "https://sv.wikipedia.org/wiki/Mercury_(planet)"
+    );
+}
+
+#[test]
+fn wiki_sections_test() {
+    let text = "Intro text.\n\n== Orbit ==\nIt orbits.\n\n=== Resonance ===\nSpin.\n\n== Empty ==\n=== Also empty ===\n\n== See also ==\n\n";
+    let sections = wiki_sections("Mercury", text);
+    let outline: Vec<(&str, usize)> = sections
+        .iter()
+        .map(|chapter| (chapter.title.as_str(), chapter.depth))
+        .collect();
+    assert_eq!(
+        outline,
+        vec![("Mercury", 0), ("Orbit", 0), ("Resonance", 1)]
+    );
+    assert_eq!(sections[1].text, "# Orbit\n\nIt orbits.");
+}
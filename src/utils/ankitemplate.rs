@@ -2,7 +2,7 @@ use std::{
     collections::HashMap,
     fs,
     path::PathBuf,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, OnceLock},
     time::Duration,
 };
 
@@ -16,6 +16,8 @@ use crate::{
 pub struct ImportProgress {
     pub curr_index: usize,
     pub max: usize,
+    /// Why the item at `curr_index` failed, which is shown once the progress is done.
+    pub error: Option<String>,
 }
 
 #[derive(Default)]
@@ -235,7 +237,7 @@ impl Template {
 
     pub fn fill_front_view(&self, template: String, idx: usize) -> String {
         let mut text = self.fill_view(template, idx);
-        remove_useless_formatting(&mut text);
+        text = html_to_markup(&text);
         let model = &self.models.get(&self.model_id_from_card_idx(idx)).unwrap();
         if model.is_cloze {
             cloze_format(&mut text, self.cards[idx].template_ord as u32 + 1);
@@ -246,7 +248,7 @@ impl Template {
     }
     pub fn fill_back_view(&self, template: String, idx: usize) -> String {
         let mut text = self.fill_view(template, idx);
-        remove_useless_formatting(&mut text);
+        text = html_to_markup(&text);
         let model = &self.models.get(&self.model_id_from_card_idx(idx)).unwrap();
         if model.is_cloze {
             hide_close(&mut text);
@@ -287,58 +289,90 @@ impl Template {
         template
     }
 
+    /// Imports every card of the deck, what happens to the ones that are already there
+    /// depends on `duplicates`.
     pub fn import_cards(
         &mut self,
         conn: Arc<Mutex<Connection>>,
         transmitter: std::sync::mpsc::SyncSender<ImportProgress>,
         topic: TopicID,
+        duplicates: DuplicatesConfig,
     ) {
         let cardlen = self.cards.len();
+        let mut index = match duplicates.import {
+            ImportDuplicates::Keep => None,
+            _ => Some(DuplicateIndex::new(&conn, duplicates.threshold)),
+        };
 
         for idx in 0..cardlen {
-            let front_template = self.get_front_template(idx);
-            let back_template = self.get_back_template(idx);
-            let frontside = self.fill_front_view(front_template, idx);
-            let backside = self.fill_back_view(back_template, idx);
-            let media = self.get_media(idx);
-            if true {
-                let _ = transmitter.try_send(ImportProgress {
-                    curr_index: idx,
-                    max: cardlen,
-                });
+            let error = self
+                .import_card(&conn, idx, topic, duplicates.import, &mut index)
+                .err()
+                .map(|e| format!("Couldn't import card {}: {}", idx + 1, e));
+            let progress = ImportProgress {
+                curr_index: idx,
+                max: cardlen,
+                error,
             };
-            if self.cards[idx].reps.is_empty() {
-                card::Card::new(CardTypeData::Pending(PendingInfo::default()))
-                    .question(frontside)
-                    .answer(backside)
-                    .topic(topic)
-                    .frontimage(media.frontimage)
-                    .backimage(media.backimage)
-                    .frontaudio(media.frontaudio)
-                    .backaudio(media.backaudio)
-                    .save_card(&conn);
+            // Unlike the progress itself, errors mustn't be dropped when the channel is full.
+            if progress.error.is_some() {
+                let _ = transmitter.send(progress);
             } else {
-                let interval = self.cards[idx].interval;
-                let card_id = card::Card::new(CardTypeData::Finished(FinishedInfo {
-                    strength: 1.0,
-                    stability: interval,
-                }))
-                .question(frontside)
-                .answer(backside)
-                .topic(topic)
-                .frontimage(media.frontimage)
-                .backimage(media.backimage)
-                .frontaudio(media.frontaudio)
-                .backaudio(media.backaudio)
-                .save_card(&conn);
-
-                for review in &self.cards[idx].reps {
-                    revlog_new(&conn, card_id, review).unwrap();
-                }
+                let _ = transmitter.try_send(progress);
             }
         }
     }
 
+    fn import_card(
+        &self,
+        conn: &Arc<Mutex<Connection>>,
+        idx: usize,
+        topic: TopicID,
+        duplicates: ImportDuplicates,
+        index: &mut Option<DuplicateIndex>,
+    ) -> Result<()> {
+        let front_template = self.get_front_template(idx);
+        let back_template = self.get_back_template(idx);
+        let frontside = self.fill_front_view(front_template, idx);
+        let backside = self.fill_back_view(back_template, idx);
+        let media = self.get_media(idx);
+        let duplicate = index
+            .as_ref()
+            .and_then(|index| index.find(&frontside, &backside));
+        if duplicate.is_some() && duplicates == ImportDuplicates::Skip {
+            return Ok(());
+        }
+
+        let cardtype = if self.cards[idx].reps.is_empty() {
+            CardTypeData::Pending(PendingInfo::default())
+        } else {
+            CardTypeData::Finished(FinishedInfo {
+                strength: 1.0,
+                stability: self.cards[idx].interval,
+            })
+        };
+        let card_id = card::Card::new(cardtype)
+            .question(frontside.clone())
+            .answer(backside.clone())
+            .topic(topic)
+            .frontimage(media.frontimage)
+            .backimage(media.backimage)
+            .frontaudio(media.frontaudio)
+            .backaudio(media.backaudio)
+            .save_card(conn);
+
+        for review in &self.cards[idx].reps {
+            revlog_new(conn, card_id, review)?;
+        }
+
+        match (duplicate, index) {
+            (Some(duplicate), _) => merge_cards(conn, duplicate.id, card_id)?,
+            (None, Some(index)) => index.insert(card_id, &frontside, &backside),
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
     fn get_review_history(conn: &Arc<Mutex<Connection>>, id: AnkiCID) -> Vec<Review> {
         let id = id.as_secs();
         let mut reviews = vec![];
@@ -452,6 +486,7 @@ impl Template {
                 let _ = transmitter.try_send(ImportProgress {
                     curr_index: index,
                     max: tot,
+                    error: None,
                 });
 
                 let mut val = val.to_string();
@@ -732,8 +767,10 @@ use rusqlite::Connection;
 
 use super::{
     card::{self, CardTypeData, FinishedInfo, PendingInfo, RecallGrade, Review},
+    duplicates::{merge_cards, DuplicateIndex, DuplicatesConfig, ImportDuplicates},
     misc::SpekiPaths,
     sql::insert::revlog_new,
+    textimport::decode_entities,
 };
 
 fn cloze_format(trd: &mut String, ord: u32) {
@@ -753,22 +790,109 @@ fn strip_cloze(trd: &mut String) {
     let re = Regex::new(pattern).unwrap();
     *trd = re.replace_all(trd, "{{$inside_cloze}}").to_string();
 }
-fn remove_useless_formatting(trd: &mut String) {
-    let pattern = r"(<br />|<br>|<br/>)".to_string();
-    let re = Regex::new(&pattern).unwrap();
-    *trd = re.replace_all(trd, "\n").to_string();
-
-    let pattern = r"(<.*?>)".to_string();
-    let re = Regex::new(&pattern).unwrap();
-    *trd = re.replace_all(trd, "").to_string();
+/// Turns the html of anki fields into markup: `**strong**`, `*emphasis*`, `` `code` ``, lists
+/// and ```` ``` ```` fenced code blocks for `<pre>`. Other tags are dropped, keeping their text.
+pub fn html_to_markup(html: &str) -> String {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    static BLANKLINES: OnceLock<Regex> = OnceLock::new();
+    let tag = TAG
+        .get_or_init(|| Regex::new(r"(?s)<!--.*?-->|<(/?)([a-zA-Z][a-zA-Z0-9]*)[^>]*>").unwrap());
+    let mut text = String::new();
+    let mut inline: Vec<(&str, usize)> = vec![];
+    let mut lists: Vec<Option<usize>> = vec![];
+    let mut in_pre = false;
+    let mut last = 0;
+
+    for caps in tag.captures_iter(html) {
+        let found = caps.get(0).unwrap();
+        text.push_str(&decode_entities(&html[last..found.start()]));
+        last = found.end();
+        let name = match caps.get(2) {
+            Some(name) => name.as_str().to_lowercase(),
+            None => continue,
+        };
+        let closing = !caps[1].is_empty();
+        let marker = match name.as_str() {
+            "b" | "strong" => Some("**"),
+            "i" | "em" => Some("*"),
+            "code" | "kbd" | "tt" if !in_pre => Some("`"),
+            _ => None,
+        };
 
-    let pattern = r"&nbsp;".to_string();
-    let re = Regex::new(&pattern).unwrap();
-    *trd = re.replace_all(trd, "").to_string();
+        match (name.as_str(), marker, closing) {
+            (_, Some(marker), false) => inline.push((marker, text.len())),
+            (_, Some(marker), true) => {
+                let opened = inline.iter().rposition(|(open, _)| *open == marker);
+                // Closing tags without an opening one are dropped, and so are the tags that
+                // were opened inside of it but not closed, as wrapping moves their offsets.
+                if let Some(idx) = opened {
+                    let (_, start) = inline[idx];
+                    inline.truncate(idx);
+                    let inner = text.split_off(start);
+                    wrap_inline(&mut text, &inner, marker);
+                }
+            }
+            ("br", _, _) => text.push('\n'),
+            ("pre", _, _) => {
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                text.push_str("```\n");
+                in_pre = !closing;
+            }
+            ("ul" | "ol", _, false) => {
+                lists.push(if name == "ol" { Some(1) } else { None });
+            }
+            ("ul" | "ol", _, true) => {
+                lists.pop();
+                text.push('\n');
+            }
+            ("li", _, false) => {
+                let indent = "  ".repeat(lists.len().saturating_sub(1));
+                let bullet = match lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}.", *number - 1)
+                    }
+                    _ => "-".to_string(),
+                };
+                text.push_str(&format!("\n{}{} ", indent, bullet));
+            }
+            ("div" | "p" | "tr" | "hr" | "blockquote" | "table", _, _)
+            | ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", _, _)
+                if !text.ends_with('\n') =>
+            {
+                text.push('\n');
+            }
+            _ => {}
+        }
+    }
+    text.push_str(&decode_entities(&html[last..]));
+
+    let blanklines = BLANKLINES.get_or_init(|| Regex::new(r"\n(\s*\n)+").unwrap());
+    let text: String = text
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<&str>>()
+        .join("\n");
+    blanklines.replace_all(&text, "\n\n").trim().to_string()
+}
 
-    let pattern = r"&quot;".to_string();
-    let re = Regex::new(&pattern).unwrap();
-    *trd = re.replace_all(trd, "").to_string();
+/// Puts the markers around the text, outside of the whitespace around it so that it's still
+/// recognized as markup.
+fn wrap_inline(text: &mut String, inner: &str, marker: &str) {
+    let trimmed = inner.trim();
+    if trimmed.is_empty() {
+        text.push_str(inner);
+        return;
+    }
+    if inner.starts_with(char::is_whitespace) {
+        text.push(' ');
+    }
+    text.push_str(&format!("{}{}{}", marker, trimmed, marker));
+    if inner.ends_with(char::is_whitespace) {
+        text.push(' ');
+    }
 }
 
 fn extract_image(trd: &mut String, folderpath: &PathBuf) -> Option<PathBuf> {
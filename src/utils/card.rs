@@ -1,11 +1,22 @@
 use crate::popups::edit_card::Editor;
 use crate::popups::edit_text::TextEditor;
+use crate::popups::filepicker::{FilePicker, FilePickerPurpose};
 use crate::popups::find_card::{CardPurpose, FindCardWidget};
 use crate::popups::newchild::{AddChildWidget, Purpose};
+use crate::utils::area::split_updown_by_percent;
+use crate::utils::media::{
+    import_media, CardMedia, MediaKind, Side, AUDIO_EXTENSIONS, IMAGE_EXTENSIONS,
+};
+use crate::utils::misc::{play_audio, play_audio_queue};
+use crate::utils::occlusion::Occlusion;
+use crate::utils::recording::{Microphone, Recorder};
 use crate::utils::sql::fetch::cards::fetch_card;
+use crate::utils::sql::fetch::get_occlusion;
 use crate::utils::sql::update::update_topic;
+use crate::utils::tts::{self, play_sounds, Sound};
 use crate::widgets::button::Button;
 use crate::widgets::cardrater::CardRater;
+use crate::widgets::image::ImageView;
 use crate::widgets::textinput::Field;
 use crate::widgets::topics::TopicList;
 use crate::{MyKey, MyType};
@@ -13,7 +24,7 @@ use rusqlite::Connection;
 use tui::layout::Rect;
 use tui::Frame;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
@@ -89,6 +100,10 @@ pub struct Card {
     pub history: Vec<Review>,
     pub topic: TopicID,
     pub source: IncID,
+    pub priority: u32,
+    pub page: Option<u32>,
+    /// Character offsets of the cloze in its source text.
+    pub source_range: Option<(usize, usize)>,
 }
 
 #[derive(Clone, Debug)]
@@ -146,6 +161,9 @@ impl Card {
             history: vec![],
             topic: 1,
             source: 0,
+            priority: 0,
+            page: None,
+            source_range: None,
         }
     }
 
@@ -161,6 +179,19 @@ impl Card {
         self.source = source;
         self
     }
+    pub fn priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+    pub fn page(mut self, page: Option<u32>) -> Self {
+        self.page = page;
+        self
+    }
+
+    pub fn source_range(mut self, range: Option<(usize, usize)>) -> Self {
+        self.source_range = range;
+        self
+    }
     pub fn topic(mut self, topic: TopicID) -> Self {
         self.topic = topic;
         self
@@ -181,6 +212,12 @@ impl Card {
         self.backimage = imagepath;
         self
     }
+    pub fn media(self, media: CardMedia) -> Self {
+        self.frontaudio(media.frontaudio)
+            .backaudio(media.backaudio)
+            .frontimage(media.frontimage)
+            .backimage(media.backimage)
+    }
     pub fn dependencies<IDVec: Into<Vec<CardID>>>(mut self, dependencies: IDVec) -> Self {
         for dependency in dependencies.into() {
             self.dependencies.push(dependency);
@@ -287,35 +324,77 @@ impl Card {
     pub fn activate_card(conn: &Arc<Mutex<Connection>>, id: CardID) {
         remove_pending(conn, id).unwrap();
         new_finished(conn, id).unwrap();
+        let topic = get_topic_of_card(conn, id);
+        let count = DailyCount {
+            new_cards: 1,
+            ..Default::default()
+        };
+        bump_daily_count(conn, get_current_day(), topic, count).unwrap();
+    }
+
+    /// The audio of a side, or its speech if it has none.
+    pub fn side_audio(&self, appdata: &AppData, side: Side) -> Option<Sound> {
+        let audio = match side {
+            Side::Front => &self.frontaudio,
+            Side::Back => &self.backaudio,
+        };
+        audio
+            .clone()
+            .map(Sound::File)
+            .or_else(|| tts::autoplay_speech(appdata, self, side))
     }
 
     pub fn play_frontaudio(appdata: &AppData, id: CardID) {
-        let card = fetch_card(&appdata.conn, id);
-        if let Some(path) = card.frontaudio {
-            crate::utils::misc::play_audio(&appdata.audio, path);
+        if appdata.audio.is_none() || !appdata.config.audio.autoplay_front {
+            return;
         }
+        let card = fetch_card(&appdata.conn, id);
+        play_sounds(
+            appdata,
+            card.side_audio(appdata, Side::Front).into_iter().collect(),
+        );
     }
     pub fn play_backaudio(appdata: &AppData, id: CardID) {
+        if appdata.audio.is_none() || !appdata.config.audio.autoplay_back {
+            return;
+        }
+        let card = fetch_card(&appdata.conn, id);
+        play_sounds(
+            appdata,
+            card.side_audio(appdata, Side::Back).into_iter().collect(),
+        );
+    }
+
+    /// Plays the audio of the question again, followed by the answer's once it's revealed.
+    pub fn replay_audio(appdata: &AppData, id: CardID, revealed: bool) {
+        if appdata.audio.is_none() {
+            return;
+        }
         let card = fetch_card(&appdata.conn, id);
-        if let Some(path) = card.backaudio {
-            crate::utils::misc::play_audio(&appdata.audio, path);
+        let mut sounds: Vec<Sound> = card.side_audio(appdata, Side::Front).into_iter().collect();
+        if revealed {
+            sounds.extend(card.side_audio(appdata, Side::Back));
         }
+        play_sounds(appdata, sounds);
     }
 }
 
-use super::misc::{get_current_unix, get_gpt3_response};
+use super::limits::DailyCount;
+use super::llm::{parse_suggestions, similar_cards, Generation, OpenAiChat, Suggestion};
+use super::misc::{get_current_day, get_current_unix};
 use super::sql::delete::{remove_pending, remove_unfinished};
+use super::sql::fetch::cards::get_topic_of_card;
 use super::sql::fetch::cards::{fetch_question, get_stability};
 use super::sql::fetch::fetch_item;
-use super::sql::insert::new_finished;
 use super::sql::insert::revlog_new;
-use super::sql::update::{update_card_answer, update_card_question};
+use super::sql::insert::{bump_daily_count, new_finished};
+use super::sql::update::{set_card_media, set_priority, update_card_answer, update_card_question};
 use super::sql::{
     insert::{save_card, update_both},
     update::set_resolved,
 };
 use super::statelist::{KeyHandler, StatefulList};
-use crate::app::{AppData, TabData, Widget};
+use crate::app::{AppData, PopupValue, Tab, TabData, Widget};
 use crate::utils::aliases::*;
 
 pub struct CardInfo {
@@ -338,6 +417,27 @@ pub struct CardView<'a> {
     pub dependencies: StatefulList<CardItem>,
     pub dependents: StatefulList<CardItem>,
     pub topics: TopicList,
+    pub frontimage: ImageView,
+    pub backimage: ImageView,
+    pub media: CardMedia,
+    /// The side that a picked file gets attached to.
+    media_side: Side,
+    media_error: Option<String>,
+    recorder: Option<Recorder>,
+    /// The last recording, to compare with the audio of the answer.
+    recording: Option<PathBuf>,
+    recording_error: Option<String>,
+    /// The answer being generated with Alt+g.
+    generation: Option<Generation>,
+    generation_error: Option<String>,
+    /// Dependencies being suggested with Alt+D, along with the cards the model picks from.
+    suggesting: Option<(Generation, Vec<CardItem>)>,
+    /// Shown in place of the dependencies until they've all been accepted or rejected.
+    pub suggestions: StatefulList<Suggestion>,
+    /// Suggested concepts accepted for a card that isn't saved yet, they get their cards when
+    /// it's submitted.
+    new_concepts: Vec<String>,
+    occlusion: Option<Occlusion>,
 }
 
 impl<'a> CardView<'a> {
@@ -352,23 +452,70 @@ impl<'a> CardView<'a> {
             dependencies: StatefulList::new("Dependencies".to_string()),
             dependents: StatefulList::new("Dependents".to_string()),
             topics: TopicList::new(conn),
+            frontimage: ImageView::new("Image"),
+            backimage: ImageView::new("Image"),
+            media: CardMedia::default(),
+            media_side: Side::Front,
+            recorder: None,
+            recording: None,
+            media_error: None,
+            recording_error: None,
+            generation: None,
+            generation_error: None,
+            suggesting: None,
+            suggestions: StatefulList::new(
+                "Suggested dependencies (accept: y, reject: n)".to_string(),
+            ),
+            new_concepts: vec![],
+            occlusion: None,
         }
     }
 
     pub fn render(&mut self, f: &mut Frame<MyType>, appdata: &AppData, cursor: &Pos) {
-        self.question.render(f, appdata, cursor);
+        self.poll_generation();
+        self.poll_suggestions();
+        if let Some(occlusion) = &self.occlusion {
+            self.frontimage.overlays = occlusion.overlays(self.revealed);
+        }
+        Self::render_with_image(f, appdata, cursor, &mut self.question, &mut self.frontimage);
         if self.revealed {
-            self.answer.render(f, appdata, cursor);
+            Self::render_with_image(f, appdata, cursor, &mut self.answer, &mut self.backimage);
             self.cardrater.render(f, appdata, cursor);
         } else {
             self.revealbutton.set_area(self.answer.get_area());
             self.revealbutton.render(f, appdata, cursor);
         }
-        self.dependencies.render(f, appdata, cursor);
+        if self.suggestions.items.is_empty() {
+            self.dependencies.render(f, appdata, cursor);
+        } else {
+            self.suggestions.set_area(self.dependencies.get_area());
+            self.suggestions.render(f, appdata, cursor);
+        }
         self.dependents.render(f, appdata, cursor);
         self.topics.render(f, appdata, cursor);
     }
 
+    /// The image, if there is one, takes up the lower part of the field's area.
+    fn render_with_image(
+        f: &mut Frame<MyType>,
+        appdata: &AppData,
+        cursor: &Pos,
+        field: &mut Field,
+        image: &mut ImageView,
+    ) {
+        if !image.is_loaded() {
+            field.render(f, appdata, cursor);
+            return;
+        }
+        let area = field.get_area();
+        let chunks = split_updown_by_percent([30, 70], area);
+        field.set_area(chunks[0]);
+        field.render(f, appdata, cursor);
+        field.set_area(area);
+        image.set_area(chunks[1]);
+        image.render(f, appdata, cursor);
+    }
+
     pub fn keyhandler(
         &mut self,
         appdata: &AppData,
@@ -389,12 +536,17 @@ impl<'a> CardView<'a> {
                     Card::play_backaudio(appdata, self.get_id());
                 }
             }
-            MyKey::Alt('g') if self.question.is_selected(cursor) && self.revealed => {
-                if let Some(key) = &appdata.config.gptkey {
-                    if let Some(answer) = get_gpt3_response(key, &self.question.return_text()) {
-                        self.answer.replace_text(answer);
-                    }
-                }
+            MyKey::Alt('g')
+                if self.question.is_selected(cursor)
+                    && self.revealed
+                    && self.generation.is_none() =>
+            {
+                let config = &appdata.config.llm;
+                let generator = OpenAiChat::new(config, appdata.config.gptkey.as_deref());
+                let prompt = config.answer_prompt(&self.question.return_text());
+                self.generation = Some(Generation::start(Box::new(generator), prompt));
+                self.generation_error = None;
+                self.update_titles();
             }
             MyKey::Alt('t') if self.card.is_some() => {
                 let purpose = CardPurpose::NewDependent(vec![self.get_id()]);
@@ -423,6 +575,16 @@ impl<'a> CardView<'a> {
                     tabdata.popup = Some(Box::new(editor));
                 }
             }
+            MyKey::Alt('D') if self.suggesting.is_none() => self.suggest_dependencies(appdata),
+            key if self.dependencies.is_selected(cursor) && !self.suggestions.items.is_empty() => {
+                match key {
+                    MyKey::Char('y') | MyKey::Enter => self.accept_suggestion(appdata),
+                    MyKey::Char('n') | MyKey::Delete => {
+                        self.suggestions.take_selected_item();
+                    }
+                    key => self.suggestions.keyhandler(appdata, key),
+                }
+            }
             MyKey::Char('e') | MyKey::Enter if self.dependencies.is_selected(cursor) => {
                 if let Some(idx) = self.dependencies.state.selected() {
                     let id = self.dependencies.items[idx].id;
@@ -436,10 +598,93 @@ impl<'a> CardView<'a> {
                     if source == 0 {
                         return;
                     }
-                    let editor = TextEditor::new(appdata, source);
+                    let editor = match card.source_range {
+                        Some((start, _)) => TextEditor::new_at(appdata, source, start),
+                        None => TextEditor::new(appdata, source),
+                    };
                     tabdata.popup = Some(Box::new(editor));
                 }
             }
+            MyKey::Alt('o')
+                if self.revealed
+                    && self.backimage.is_loaded()
+                    && (self.answer.is_selected(cursor) || !self.frontimage.is_loaded()) =>
+            {
+                self.backimage.keyhandler(appdata, key)
+            }
+            MyKey::Alt('o') if self.frontimage.is_loaded() => {
+                self.frontimage.keyhandler(appdata, key)
+            }
+            MyKey::Alt('v')
+                if self.question.is_selected(cursor)
+                    || (self.revealed && self.answer.is_selected(cursor)) =>
+            {
+                let text = if self.question.is_selected(cursor) {
+                    self.question.return_text()
+                } else {
+                    self.answer.return_text()
+                };
+                let topic = self.topics.get_selected_id().unwrap_or_default();
+                let topic = tts::topic_name(appdata, topic);
+                tts::speak(appdata, &text, appdata.config.tts.voice(&topic));
+            }
+            MyKey::Alt('w') => self.toggle_recording(appdata),
+            MyKey::Alt('W') => {
+                let paths = self.recording.iter().chain(self.media.backaudio.iter());
+                play_audio_queue(&appdata.audio, paths.cloned().collect());
+            }
+            MyKey::Alt('S') => {
+                if let Some(path) = self.recording.clone() {
+                    if let Some(path) = self.import_media(appdata, &path) {
+                        self.media.backaudio = Some(path);
+                        self.media_changed(appdata);
+                    }
+                }
+            }
+            MyKey::Alt('e') | MyKey::Alt('E') | MyKey::Alt('l') => {
+                let side = if self.question.is_selected(cursor) {
+                    Side::Front
+                } else if self.revealed && self.answer.is_selected(cursor) {
+                    Side::Back
+                } else {
+                    return;
+                };
+                match key {
+                    MyKey::Alt('e') => {
+                        self.media_side = side;
+                        let extensions: Vec<String> = AUDIO_EXTENSIONS
+                            .iter()
+                            .chain(IMAGE_EXTENSIONS.iter())
+                            .map(|extension| extension.to_string())
+                            .collect();
+                        let picker = FilePicker::new(
+                            FilePickerPurpose::Media,
+                            "Choose an audio file or an image to attach".to_string(),
+                            extensions,
+                        );
+                        tabdata.popup = Some(Box::new(picker));
+                    }
+                    MyKey::Alt('E') => {
+                        self.media.clear(side);
+                        self.media_changed(appdata);
+                    }
+                    _ => {
+                        if let Some(path) = self.media.get(side, MediaKind::Audio) {
+                            play_audio(&appdata.audio, path.clone());
+                        }
+                    }
+                }
+            }
+            MyKey::Alt('+') | MyKey::Alt('-') if self.card.is_some() => {
+                let card = self.card.as_mut().unwrap();
+                card.priority = if key == MyKey::Alt('+') {
+                    card.priority + 1
+                } else {
+                    card.priority.saturating_sub(1)
+                };
+                set_priority(&appdata.conn, card.id, card.priority);
+                self.update_titles();
+            }
             MyKey::Alt('f') => {
                 if let Some(ref card) = self.card {
                     let id = card.id;
@@ -525,8 +770,258 @@ impl<'a> CardView<'a> {
                 .collect();
             StatefulList::with_items("Dependents".to_string(), carditems)
         };
+        self.occlusion = get_occlusion(conn, id);
+        self.media = CardMedia::from_card(&card);
+        // Anything still being generated belongs to the previous card.
+        self.generation = None;
+        self.generation_error = None;
+        self.suggesting = None;
+        self.suggestions.replace_items(vec![]);
+        // So is the recording, it mustn't be saved as the audio of this one.
+        if let Some(recorder) = &mut self.recorder {
+            recorder.cancel();
+        }
+        self.recorder = None;
+        self.recording = None;
+        self.recording_error = None;
+        self.media_error = None;
         self.card = Some(card);
+        self.load_images();
+        self.update_titles();
+    }
+
+    fn load_images(&mut self) {
+        match &self.occlusion {
+            Some(occlusion) => {
+                self.frontimage.load(Some(occlusion.image.clone()));
+                self.backimage.load(None);
+            }
+            None => {
+                self.frontimage.load(self.media.frontimage.clone());
+                self.backimage.load(self.media.backimage.clone());
+            }
+        }
+    }
+
+    fn update_titles(&mut self) {
+        let mut info = vec![];
+        if let Some(card) = &self.card {
+            if card.priority != 0 {
+                info.push(format!("priority {}", card.priority));
+            }
+            if let Some(page) = card.page {
+                info.push(format!("page {}", page));
+            }
+        }
+        if self.media.frontaudio.is_some() {
+            info.push("audio".to_string());
+        }
+        self.question.title = if info.is_empty() {
+            "Question".to_string()
+        } else {
+            format!("Question ({})", info.join(", "))
+        };
+
+        let mut info = vec![];
+        if self.media.backaudio.is_some() {
+            info.push("audio".to_string());
+        }
+        if self
+            .recorder
+            .as_ref()
+            .is_some_and(|recorder| recorder.is_recording())
+        {
+            info.push("recording, stop with Alt+w".to_string());
+        } else if self.recording.is_some() {
+            info.push("recorded, replay with Alt+W".to_string());
+        }
+        if let Some(error) = &self.recording_error {
+            info.push(error.clone());
+        }
+        if let Some(error) = &self.media_error {
+            info.push(error.clone());
+        }
+        if let Some(generation) = &self.generation {
+            info.push(format!("{} generating", generation.spinner()));
+        } else if let Some(error) = &self.generation_error {
+            info.push(error.clone());
+        }
+        self.answer.title = if info.is_empty() {
+            "Answer".to_string()
+        } else {
+            format!("Answer ({})", info.join(", "))
+        };
+    }
+
+    /// Puts the generated answer in place once it's done, keeps the spinner going until then.
+    fn poll_generation(&mut self) {
+        let result = match &self.generation {
+            Some(generation) => generation.poll(),
+            None => return,
+        };
+        match result {
+            Some(Ok(answer)) => {
+                self.generation = None;
+                self.answer.replace_text(answer);
+            }
+            Some(Err(error)) => {
+                self.generation = None;
+                self.generation_error = Some(format!("couldn't generate: {}", error));
+            }
+            None => {}
+        }
+        self.update_titles();
+    }
+
+    /// Asks the language model which of the cards most alike to this one it depends on.
+    fn suggest_dependencies(&mut self, appdata: &AppData) {
+        let question = self.question.return_text();
+        if question.trim().is_empty() {
+            return;
+        }
+        let answer = self.answer.return_text();
+        let config = &appdata.config.llm;
+        let mut exclude: Vec<CardID> = self
+            .dependencies
+            .items
+            .iter()
+            .chain(self.dependents.items.iter())
+            .map(|card| card.id)
+            .collect();
+        exclude.extend(self.card.as_ref().map(|card| card.id));
+        let text = format!("{}\n{}", question, answer);
+        let candidates = similar_cards(&appdata.conn, &text, &exclude, config.candidates);
+        let prompt = config.dependencies_prompt(&question, &answer, &candidates);
+        let generator = OpenAiChat::new(config, appdata.config.gptkey.as_deref());
+        self.suggesting = Some((Generation::start(Box::new(generator), prompt), candidates));
+        self.suggestions.replace_items(vec![]);
     }
+
+    fn poll_suggestions(&mut self) {
+        let (generation, candidates) = match &self.suggesting {
+            Some(suggesting) => suggesting,
+            None => return,
+        };
+        let result = match generation.poll() {
+            Some(result) => result,
+            None => {
+                self.dependencies.title =
+                    format!("Dependencies ({} suggesting)", generation.spinner());
+                return;
+            }
+        };
+        let result = result.and_then(|response| parse_suggestions(&response, candidates));
+        self.suggesting = None;
+        self.dependencies.title = match result {
+            Ok(suggestions) if suggestions.is_empty() => {
+                "Dependencies (nothing to suggest)".to_string()
+            }
+            Ok(suggestions) => {
+                self.suggestions.replace_items(suggestions);
+                self.suggestions.next();
+                "Dependencies".to_string()
+            }
+            Err(error) => format!("Dependencies (couldn't suggest: {})", error),
+        };
+    }
+
+    /// Makes the selected suggestion a dependency, a missing concept gets an unfinished card.
+    /// For a card that isn't saved yet the concept waits in `new_concepts` until it's submitted.
+    fn accept_suggestion(&mut self, appdata: &AppData) {
+        let dependency = match self.suggestions.take_selected_item() {
+            Some(Suggestion::Existing(card)) => card,
+            Some(Suggestion::Missing(concept)) => match &self.card {
+                Some(card) => CardItem {
+                    id: Self::save_concept(appdata, &concept, card.topic),
+                    question: concept,
+                },
+                None => {
+                    self.new_concepts.push(concept);
+                    self.dependencies.title =
+                        format!("Dependencies (new: {})", self.new_concepts.join(", "));
+                    return;
+                }
+            },
+            None => return,
+        };
+        // A new card gets its dependencies once it's submitted.
+        if let Some(card) = &mut self.card {
+            update_both(&appdata.conn, card.id, dependency.id).unwrap();
+            card.dependencies.push(dependency.id);
+            Card::check_resolved(card.id, &appdata.conn);
+        }
+        self.dependencies.push(dependency);
+    }
+
+    fn save_concept(appdata: &AppData, concept: &str, topic: TopicID) -> CardID {
+        Card::new(CardTypeData::Unfinished(UnfinishedInfo::default()))
+            .question(concept.to_string())
+            .topic(topic)
+            .save_card(&appdata.conn)
+    }
+
+    /// Records from the microphone, or stops and keeps the recording in the temp folder.
+    fn toggle_recording(&mut self, appdata: &AppData) {
+        self.recording_error = None;
+        if self.recorder.is_none() {
+            match Microphone::new() {
+                Ok(microphone) => self.recorder = Some(Recorder::new(Box::new(microphone))),
+                Err(e) => self.recording_error = Some(e.to_string()),
+            }
+        }
+        if let Some(recorder) = &mut self.recorder {
+            let result = if recorder.is_recording() {
+                let path = appdata.paths.tempfolder.join("recording.wav");
+                let result = recorder.stop(&path);
+                if result.is_ok() {
+                    self.recording = Some(path);
+                }
+                result
+            } else {
+                recorder.start()
+            };
+            if let Err(e) = result {
+                self.recording_error = Some(e.to_string());
+            }
+        }
+        self.update_titles();
+    }
+
+    fn media_changed(&mut self, appdata: &AppData) {
+        if let Some(card) = &self.card {
+            set_card_media(&appdata.conn, card.id, &self.media);
+        }
+        self.load_images();
+        self.update_titles();
+    }
+
+    /// Attaches the file that was picked, if the popup was the media file picker.
+    pub fn popup_closed(&mut self, appdata: &AppData, popup: &mut Box<dyn Tab>) {
+        let path = match popup.get_popup_value() {
+            PopupValue::Path(path) => path.clone(),
+            _ => return,
+        };
+        let kind = match MediaKind::from_path(&path) {
+            Some(kind) => kind,
+            None => return,
+        };
+        if let Some(path) = self.import_media(appdata, &path) {
+            self.media.set(self.media_side, kind, Some(path));
+            self.media_changed(appdata);
+        }
+    }
+
+    /// Copies the file into the media folder, the error is shown in the title of the answer.
+    fn import_media(&mut self, appdata: &AppData, path: &Path) -> Option<PathBuf> {
+        let imported = import_media(&appdata.paths.media, path);
+        self.media_error = imported
+            .as_ref()
+            .err()
+            .map(|e| format!("couldn't import media: {}", e));
+        self.update_titles();
+        imported.ok()
+    }
+
     pub fn refresh(&mut self, appdata: &AppData) {
         if let Some(card) = &self.card {
             self.change_card(&appdata.conn, card.id);
@@ -571,13 +1066,26 @@ impl<'a> CardView<'a> {
             .question(question)
             .answer(answer)
             .topic(topic)
-            .source(source);
+            .source(source)
+            .media(self.media.clone())
+            .dependencies(
+                self.dependencies
+                    .items
+                    .iter()
+                    .map(|card| card.id)
+                    .chain(
+                        self.new_concepts
+                            .iter()
+                            .map(|concept| Self::save_concept(appdata, concept, topic)),
+                    )
+                    .collect::<Vec<CardID>>(),
+            );
 
         card.save_card(&appdata.conn);
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CardItem {
     pub question: String,
     pub id: CardID,
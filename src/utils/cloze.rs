@@ -0,0 +1,93 @@
+use serde_derive::Deserialize;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How much of the text around a cloze ends up on the card.
+#[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClozeContext {
+    #[default]
+    Sentence,
+    Paragraph,
+    Full,
+}
+
+/// Set under `[cloze]` in the config file.
+#[derive(Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct ClozeConfig {
+    pub context: ClozeContext,
+}
+
+/// Makes the question and answer of a cloze card, blanking out the given character range of
+/// the text. Only the surrounding sentence or paragraph is kept, depending on `context`.
+pub fn make_cloze(text: &str, range: (usize, usize), context: ClozeContext) -> (String, String) {
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let (start, end) = clamp(&graphemes, range);
+    let (from, to) = context_bounds(&graphemes, start, end, context);
+    let question = format!(
+        "{}[...]{}",
+        graphemes[from..start].concat(),
+        graphemes[end..to].concat()
+    );
+    (question.trim().to_string(), graphemes[start..end].concat())
+}
+
+/// The sentence or paragraph around the given character range.
+pub fn surrounding_text(text: &str, range: (usize, usize), context: ClozeContext) -> String {
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let (start, end) = clamp(&graphemes, range);
+    let (from, to) = context_bounds(&graphemes, start, end, context);
+    graphemes[from..to].concat().trim().to_string()
+}
+
+fn clamp(graphemes: &[&str], range: (usize, usize)) -> (usize, usize) {
+    let end = range.1.min(graphemes.len());
+    (range.0.min(end), end)
+}
+
+fn context_bounds(
+    graphemes: &[&str],
+    start: usize,
+    end: usize,
+    context: ClozeContext,
+) -> (usize, usize) {
+    match context {
+        ClozeContext::Full => (0, graphemes.len()),
+        ClozeContext::Paragraph => paragraph_bounds(graphemes, start, end),
+        ClozeContext::Sentence => sentence_bounds(graphemes, start, end),
+    }
+}
+
+/// Paragraphs are separated by blank lines.
+fn paragraph_bounds(graphemes: &[&str], start: usize, end: usize) -> (usize, usize) {
+    let is_break = |i: usize| graphemes[i] == "\n" && graphemes.get(i + 1) == Some(&"\n");
+    let from = (0..start).rev().find(|&i| is_break(i)).map_or(0, |i| i + 2);
+    let to = (end..graphemes.len())
+        .find(|&i| is_break(i))
+        .unwrap_or(graphemes.len());
+    (from.min(start), to)
+}
+
+fn is_sentence_end(graphemes: &[&str], i: usize) -> bool {
+    matches!(graphemes[i], "." | "!" | "?")
+        && graphemes
+            .get(i + 1)
+            .is_none_or(|next| next.trim().is_empty())
+}
+
+fn sentence_bounds(graphemes: &[&str], start: usize, end: usize) -> (usize, usize) {
+    let from = (0..start)
+        .rev()
+        .find(|&i| graphemes[i] == "\n" || is_sentence_end(graphemes, i))
+        .map_or(0, |i| i + 1);
+    let to = (end..graphemes.len())
+        .find(|&i| graphemes[i] == "\n" || is_sentence_end(graphemes, i))
+        .map_or(graphemes.len(), |i| {
+            if graphemes[i] == "\n" {
+                i
+            } else {
+                i + 1
+            }
+        });
+    (from, to)
+}
@@ -0,0 +1,224 @@
+use crate::utils::aliases::*;
+use crate::utils::card::Card;
+use crate::utils::interval::recalc_card;
+use crate::utils::sql::delete::remove_card;
+use crate::utils::sql::fetch::cards::fetch_card;
+use crate::utils::sql::fetch::CardQuery;
+use crate::utils::sql::in_transaction;
+use crate::utils::sql::update::move_card_data;
+use rusqlite::{Connection, Result};
+use serde_derive::Deserialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// What happens to imported cards that already exist.
+#[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportDuplicates {
+    /// Imports them anyway.
+    #[default]
+    Keep,
+    Skip,
+    /// Moves their review history onto the existing card.
+    Merge,
+}
+
+impl ImportDuplicates {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Keep => Self::Skip,
+            Self::Skip => Self::Merge,
+            Self::Merge => Self::Keep,
+        }
+    }
+}
+
+impl fmt::Display for ImportDuplicates {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Self::Keep => "import anyway",
+            Self::Skip => "skip",
+            Self::Merge => "merge into the existing card",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Set under `[duplicates]` in the config file, a threshold above 1 only catches cards that
+/// are the same apart from case, punctuation and whitespace.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct DuplicatesConfig {
+    /// How alike two cards have to be to count as duplicates, from 0 to 1.
+    pub threshold: f32,
+    pub import: ImportDuplicates,
+}
+
+impl Default for DuplicatesConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.85,
+            import: ImportDuplicates::Keep,
+        }
+    }
+}
+
+/// Lowercase words without punctuation, separated by single spaces.
+pub fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn trigrams(normalized: &str) -> HashSet<[char; 3]> {
+    let chars: Vec<char> = format!("  {} ", normalized).chars().collect();
+    chars.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+/// How many trigrams the texts share, from 0 to 1.
+fn overlap(a: &HashSet<[char; 3]>, b: &HashSet<[char; 3]>) -> f32 {
+    if a.is_empty() && b.is_empty() {
+        return 1.;
+    }
+    let shared = a.intersection(b).count();
+    2. * shared as f32 / (a.len() + b.len()) as f32
+}
+
+/// How alike two cards are from 0 to 1, the question counts for more than the answer.
+pub fn similarity(question: &str, answer: &str, other_question: &str, other_answer: &str) -> f32 {
+    let entry = Entry::new(0, question, answer);
+    entry.similarity(&Entry::new(0, other_question, other_answer))
+}
+
+struct Entry {
+    id: CardID,
+    question: String,
+    normalized: (String, String),
+    trigrams: (HashSet<[char; 3]>, HashSet<[char; 3]>),
+}
+
+impl Entry {
+    fn new(id: CardID, question: &str, answer: &str) -> Self {
+        let normalized = (normalize(question), normalize(answer));
+        let trigrams = (trigrams(&normalized.0), trigrams(&normalized.1));
+        Self {
+            id,
+            question: question.to_string(),
+            normalized,
+            trigrams,
+        }
+    }
+
+    fn similarity(&self, other: &Entry) -> f32 {
+        if self.normalized == other.normalized {
+            return 1.;
+        }
+        0.7 * overlap(&self.trigrams.0, &other.trigrams.0)
+            + 0.3 * overlap(&self.trigrams.1, &other.trigrams.1)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Duplicate {
+    pub id: CardID,
+    pub question: String,
+    /// 1 for an exact match.
+    pub similarity: f32,
+}
+
+/// The cards to look for duplicates among, loaded once so that imports don't have to query
+/// the database for every card.
+pub struct DuplicateIndex {
+    entries: Vec<Entry>,
+    threshold: f32,
+    /// The highest id in the index, newer cards are loaded by [`DuplicateIndex::update`].
+    newest: CardID,
+}
+
+impl DuplicateIndex {
+    pub fn new(conn: &Arc<Mutex<Connection>>, threshold: f32) -> Self {
+        let mut index = Self {
+            entries: vec![],
+            threshold,
+            newest: 0,
+        };
+        index.update(conn);
+        index
+    }
+
+    /// Loads the cards that were added since the index was made or last updated.
+    pub fn update(&mut self, conn: &Arc<Mutex<Connection>>) {
+        let entries = CardQuery::default()
+            .newer_than(self.newest)
+            .fetch_generic(conn, |row| {
+                let question: String = row.get("question").unwrap();
+                let answer: String = row.get("answer").unwrap();
+                Entry::new(row.get(0).unwrap(), &question, &answer)
+            });
+        for entry in entries {
+            self.newest = self.newest.max(entry.id);
+            self.entries.push(entry);
+        }
+    }
+
+    /// Cards added after the index was made, like earlier cards of the same import.
+    pub fn insert(&mut self, id: CardID, question: &str, answer: &str) {
+        self.newest = self.newest.max(id);
+        self.entries.push(Entry::new(id, question, answer));
+    }
+
+    /// The card most alike to the given one, if it's alike enough to be a duplicate. An exact
+    /// match of the normalized text always counts.
+    pub fn find(&self, question: &str, answer: &str) -> Option<Duplicate> {
+        let card = Entry::new(0, question, answer);
+        if card.normalized.0.is_empty() {
+            return None;
+        }
+        if let Some(entry) = self
+            .entries
+            .iter()
+            .find(|e| e.normalized == card.normalized)
+        {
+            return Some(Duplicate {
+                id: entry.id,
+                question: entry.question.clone(),
+                similarity: 1.,
+            });
+        }
+        self.entries
+            .iter()
+            .map(|entry| (entry, entry.similarity(&card)))
+            .filter(|(_, similarity)| *similarity >= self.threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entry, similarity)| Duplicate {
+                id: entry.id,
+                question: entry.question.clone(),
+                similarity,
+            })
+    }
+}
+
+/// Merges a card into another, the other card keeps its text but gets the review history,
+/// dependencies, dependents and source of the merged card, which is then deleted. Its stability
+/// and strength are worked out again from the combined history.
+pub fn merge_cards(conn: &Arc<Mutex<Connection>>, keep: CardID, remove: CardID) -> Result<()> {
+    if keep == remove {
+        return Ok(());
+    }
+    in_transaction(conn, || {
+        move_card_data(conn, remove, keep)?;
+        remove_card(conn, remove)
+    })?;
+    let card = fetch_card(conn, keep);
+    if card.is_complete() {
+        recalc_card(conn, keep);
+    }
+    Card::check_resolved(keep, conn);
+    for dependent in card.dependents {
+        Card::check_resolved(dependent, conn);
+    }
+    Ok(())
+}
@@ -1,19 +1,76 @@
-#[derive(Debug)]
-struct Chapter {
-    name: String,
-    path: PathBuf,
-    content: String,
-}
 use ::epub::doc::EpubDoc;
 use epub::doc::NavPoint;
 
 use crate::app::AppData;
+use crate::utils::incread::{inc_title, Provenance};
 use minidom::{Element, Node};
-use std::path::PathBuf;
+use regex::Regex;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// A section of an imported text, `depth` is its nesting level in the table of contents.
+#[derive(Debug, Clone)]
+pub struct Chapter {
+    pub title: String,
+    pub depth: usize,
+    pub text: String,
+}
+
+impl Chapter {
+    /// Makes a top-level chapter out of a section whose first line is the title.
+    pub fn from_text(text: String) -> Self {
+        let title = inc_title(&text).to_string();
+        Self {
+            title,
+            depth: 0,
+            text,
+        }
+    }
+}
+
+impl fmt::Display for Chapter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", "  ".repeat(self.depth), self.title)
+    }
+}
+
+pub struct Book {
+    pub desc: String,
+    pub chapters: Vec<Chapter>,
+    /// Titles of the chapters that couldn't be parsed.
+    pub skipped: Vec<String>,
+    pub provenance: Provenance,
+}
+
+impl Book {
+    /// A book that couldn't be loaded, the description says why.
+    pub fn failed(desc: String, path: &Path) -> Self {
+        Self::from_sections(desc, vec![], path, String::new())
+    }
+
+    /// A book whose sections start with their title, as with pdf and text files.
+    pub fn from_sections(desc: String, sections: Vec<String>, path: &Path, author: String) -> Self {
+        let provenance = Provenance {
+            origin: path.display().to_string(),
+            title: inc_title(&desc).to_string(),
+            author,
+        };
+        Self {
+            desc,
+            chapters: sections.into_iter().map(Chapter::from_text).collect(),
+            skipped: vec![],
+            provenance,
+        }
+    }
+}
 
-pub fn load_book(_appdata: &AppData, path: &PathBuf) -> (String, Vec<String>) {
-    let doc = EpubDoc::new(path);
-    let mut doc = doc.unwrap();
+pub fn load_book(_appdata: &AppData, path: &PathBuf) -> Book {
+    let mut doc = match EpubDoc::new(path) {
+        Ok(doc) => doc,
+        Err(e) => return Book::failed(format!("Couldn't read {}: {}", path.display(), e), path),
+    };
 
     let mut desc = String::new();
 
@@ -32,56 +89,278 @@ pub fn load_book(_appdata: &AppData, path: &PathBuf) -> (String, Vec<String>) {
         }
     }
 
-    let mut chapters: Vec<Chapter> = vec![];
-    let mut chaptercontent = vec![];
+    let metadata = |key: &str| doc.metadata.get(key).cloned().unwrap_or_default();
+    let provenance = Provenance {
+        origin: path.display().to_string(),
+        title: metadata("title").first().cloned().unwrap_or_default(),
+        author: metadata("creator").join(", "),
+    };
 
+    let mut navpoints = vec![];
     for x in &doc.toc {
-        print_labels(&mut chapters, x);
+        flatten_toc(&mut navpoints, x, 0);
     }
 
-    for chapter in &mut chapters {
-        if let Ok(content) = doc.get_resource_str_by_path(&chapter.path) {
-            let source = get_body_contents(content);
-            chaptercontent.push(source);
-        }
+    let mut chapters = vec![];
+    let mut skipped = vec![];
+    let mut loaded = HashSet::new();
+    for (label, content, depth) in navpoints {
+        let title = label.trim().to_string();
+        let resource = strip_fragment(&content);
+        // Several entries may point into the same file, only the first one gets its contents.
+        let body = if loaded.insert(resource.clone()) {
+            let body = doc
+                .get_resource_str_by_path(&resource)
+                .ok()
+                .and_then(|content| get_body_contents(&content));
+            match body {
+                Some(body) => body,
+                None => {
+                    skipped.push(title);
+                    continue;
+                }
+            }
+        } else {
+            String::new()
+        };
+        chapters.push(Chapter {
+            text: with_title(&title, &body),
+            title,
+            depth,
+        });
+    }
+    Book {
+        desc,
+        chapters,
+        skipped,
+        provenance,
     }
-    (desc, chaptercontent)
 }
 
-fn print_labels(chapters: &mut Vec<Chapter>, navpoint: &NavPoint) {
-    chapters.push(Chapter {
-        name: navpoint.label.clone(),
-        path: navpoint.content.clone(),
-        content: String::new(),
-    });
+fn flatten_toc(navpoints: &mut Vec<(String, PathBuf, usize)>, navpoint: &NavPoint, depth: usize) {
+    navpoints.push((navpoint.label.clone(), navpoint.content.clone(), depth));
     for x in &navpoint.children {
-        print_labels(chapters, x);
+        flatten_toc(navpoints, x, depth + 1);
     }
 }
+
+fn strip_fragment(path: &Path) -> PathBuf {
+    let path = path.to_string_lossy();
+    match path.split_once('#') {
+        Some((file, _)) => PathBuf::from(file),
+        None => PathBuf::from(path.as_ref()),
+    }
+}
+
+/// Puts the title on top as a heading, unless the chapter already starts with it.
+fn with_title(title: &str, body: &str) -> String {
+    let first_line = body.lines().next().unwrap_or("");
+    if first_line
+        .trim_start_matches('#')
+        .trim()
+        .eq_ignore_ascii_case(title)
+    {
+        body.to_string()
+    } else if body.is_empty() {
+        format!("# {}", title)
+    } else {
+        format!("# {}\n\n{}", title, body)
+    }
+}
+
 const NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
-fn get_body_contents(xml: String) -> String {
-    let root: Element = xml.trim().parse().unwrap();
+
+/// Converts the body of an xhtml chapter to lightweight markup, returns None if it can't be parsed.
+pub fn get_body_contents(xml: &str) -> Option<String> {
+    let root: Element = xml.trim().parse().ok()?;
+    let body = root.get_child("body", NAMESPACE)?;
 
     let mut thetext = String::new();
+    let mut lists = vec![];
+    for node in body.child_nodes() {
+        write_markup(&mut thetext, node, &mut lists, &|_| None);
+    }
+    Some(tidy_markup(&thetext))
+}
+
+/// An element of the html that [`write_markup`] reads, the xhtml of epub chapters as well as
+/// the web pages that are parsed more forgivingly.
+pub trait MarkupElement: Sized {
+    fn name(&self) -> &str;
+    fn child_nodes(&self) -> Vec<MarkupNode<'_, Self>>;
 
-    let body = root.get_child("body", NAMESPACE).unwrap();
-    for node in body.nodes() {
-        get_text(&mut thetext, node);
-        thetext = thetext.trim().to_string();
-        thetext.push('\n');
+    /// All the text inside the element, leaving out the tags.
+    fn text(&self) -> String {
+        self.child_nodes()
+            .into_iter()
+            .map(|node| match node {
+                MarkupNode::Text(text) => text.to_string(),
+                MarkupNode::Element(element) => element.text(),
+            })
+            .collect()
     }
-    thetext
 }
 
-fn get_text(thetext: &mut String, node: &Node) {
-    match node {
-        Node::Element(element) => {
-            for child in element.nodes() {
-                get_text(thetext, child);
+pub enum MarkupNode<'a, E> {
+    Text(&'a str),
+    Element(&'a E),
+}
+
+/// Gives the url a link element points to, links without one are written as plain text.
+pub type LinkTarget<'a, E> = &'a dyn Fn(&E) -> Option<String>;
+
+impl MarkupElement for Element {
+    fn name(&self) -> &str {
+        Element::name(self)
+    }
+
+    fn child_nodes(&self) -> Vec<MarkupNode<'_, Self>> {
+        self.nodes()
+            .map(|node| match node {
+                Node::Text(text) => MarkupNode::Text(text),
+                Node::Element(element) => MarkupNode::Element(element),
+            })
+            .collect()
+    }
+}
+
+/// Writes the node as markup: `#` headings, `-` or numbered list items, `*emphasis*` and
+/// `**strong**` text and `[text](url)` links, with blank lines between blocks.
+pub fn write_markup<E: MarkupElement>(
+    thetext: &mut String,
+    node: MarkupNode<E>,
+    lists: &mut Vec<Option<usize>>,
+    link: LinkTarget<E>,
+) {
+    let element = match node {
+        MarkupNode::Text(text) => {
+            push_text(thetext, text);
+            return;
+        }
+        MarkupNode::Element(element) => element,
+    };
+
+    match element.name() {
+        "script" | "style" | "head" | "img" | "hr" | "input" | "meta" | "link" => {}
+        "br" => thetext.push('\n'),
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level: usize = element.name()[1..].parse().unwrap();
+            let heading = element.text();
+            let heading = heading.split_whitespace().collect::<Vec<&str>>().join(" ");
+            if !heading.is_empty() {
+                thetext.push_str(&format!("\n\n{} {}\n\n", "#".repeat(level), heading));
             }
         }
-        Node::Text(text) => {
-            thetext.push_str(&text);
+        "ul" | "ol" => {
+            lists.push(if element.name() == "ol" {
+                Some(1)
+            } else {
+                None
+            });
+            thetext.push('\n');
+            write_children(thetext, element, lists, link);
+            lists.pop();
+            thetext.push('\n');
+        }
+        "li" => {
+            let indent = "  ".repeat(lists.len().saturating_sub(1));
+            let bullet = match lists.last_mut() {
+                Some(Some(number)) => {
+                    *number += 1;
+                    format!("{}.", *number - 1)
+                }
+                _ => "-".to_string(),
+            };
+            let mut item = String::new();
+            write_children(&mut item, element, lists, link);
+            let item = tidy_markup(&item).replace("\n\n", "\n");
+            thetext.push_str(&format!("\n{}{} {}", indent, bullet, item));
+        }
+        "a" => match link(element) {
+            Some(target) => wrap_inline(
+                thetext,
+                element,
+                lists,
+                link,
+                "[",
+                &format!("]({})", target),
+            ),
+            None => write_children(thetext, element, lists, link),
+        },
+        "em" | "i" => wrap_inline(thetext, element, lists, link, "*", "*"),
+        "strong" | "b" => wrap_inline(thetext, element, lists, link, "**", "**"),
+        "pre" => {
+            thetext.push_str("\n\n");
+            thetext.push_str(element.text().trim_matches('\n'));
+            thetext.push_str("\n\n");
+        }
+        "p" | "div" | "blockquote" | "section" | "article" | "main" | "header" | "table" | "tr"
+        | "figure" | "figcaption" => {
+            thetext.push_str("\n\n");
+            write_children(thetext, element, lists, link);
+            thetext.push_str("\n\n");
         }
+        _ => write_children(thetext, element, lists, link),
     }
 }
+
+fn write_children<E: MarkupElement>(
+    thetext: &mut String,
+    element: &E,
+    lists: &mut Vec<Option<usize>>,
+    link: LinkTarget<E>,
+) {
+    for child in element.child_nodes() {
+        write_markup(thetext, child, lists, link);
+    }
+}
+
+fn wrap_inline<E: MarkupElement>(
+    thetext: &mut String,
+    element: &E,
+    lists: &mut Vec<Option<usize>>,
+    link: LinkTarget<E>,
+    open: &str,
+    close: &str,
+) {
+    let mut inner = String::new();
+    write_children(&mut inner, element, lists, link);
+    let trimmed = inner.trim();
+    if trimmed.is_empty() {
+        thetext.push_str(&inner);
+        return;
+    }
+    if inner.starts_with(char::is_whitespace) {
+        push_text(thetext, " ");
+    }
+    thetext.push_str(&format!("{}{}{}", open, trimmed, close));
+    if inner.ends_with(char::is_whitespace) {
+        thetext.push(' ');
+    }
+}
+
+/// Collapses whitespace the way a browser would.
+pub fn push_text(thetext: &mut String, text: &str) {
+    let mut collapsed = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if text.starts_with(char::is_whitespace) {
+        collapsed.insert(0, ' ');
+    }
+    if text.ends_with(char::is_whitespace) && !collapsed.ends_with(' ') {
+        collapsed.push(' ');
+    }
+    if thetext.is_empty() || thetext.ends_with(char::is_whitespace) {
+        collapsed = collapsed.trim_start().to_string();
+    }
+    thetext.push_str(&collapsed);
+}
+
+pub fn tidy_markup(text: &str) -> String {
+    static BLANKLINES: OnceLock<Regex> = OnceLock::new();
+    let blanklines = BLANKLINES.get_or_init(|| Regex::new(r"\n(\s*\n)+").unwrap());
+    let text: String = text
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<&str>>()
+        .join("\n");
+    blanklines.replace_all(&text, "\n\n").trim().to_string()
+}
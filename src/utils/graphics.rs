@@ -0,0 +1,299 @@
+use crate::utils::image::Image;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::rc::Rc;
+use tui::layout::Rect;
+use tui::style::{Color, Style};
+use tui::text::{Span, Spans};
+
+/// How big a terminal cell is assumed to be in pixels, used to size images to an area.
+pub const CELL_WIDTH: usize = 10;
+pub const CELL_HEIGHT: usize = 20;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageProtocol {
+    /// Picked from the terminal that speki runs in.
+    #[default]
+    Auto,
+    Kitty,
+    Iterm,
+    Sixel,
+    /// Two pixels per cell with the upper half block character, works in any terminal with
+    /// true colors.
+    Blocks,
+    /// Images are only shown in the external viewer.
+    None,
+}
+
+impl ImageProtocol {
+    /// Resolves `Auto` by looking at the environment variables terminals set.
+    pub fn resolve(self) -> Self {
+        if self != Self::Auto {
+            return self;
+        }
+        let var = |key: &str| std::env::var(key).unwrap_or_default();
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+        if !var("KITTY_WINDOW_ID").is_empty()
+            || term.contains("kitty")
+            || matches!(program.as_str(), "WezTerm" | "ghostty")
+        {
+            Self::Kitty
+        } else if program == "iTerm.app" {
+            Self::Iterm
+        } else if term.contains("sixel") || term.starts_with("foot") || term.starts_with("mlterm") {
+            Self::Sixel
+        } else {
+            Self::Blocks
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageConfig {
+    pub protocol: ImageProtocol,
+    /// Command that opens an image file in an external viewer.
+    pub viewer: String,
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        let viewer = if cfg!(target_os = "macos") {
+            "open"
+        } else {
+            "xdg-open"
+        };
+        Self {
+            protocol: ImageProtocol::Auto,
+            viewer: viewer.to_string(),
+        }
+    }
+}
+
+/// The size in cells that the image is shown at when fitted within the area.
+pub fn cell_size(image: &Image, area: Rect) -> (u16, u16) {
+    let (width, height) = image.fitted_size(
+        area.width as usize * CELL_WIDTH,
+        area.height as usize * CELL_HEIGHT,
+    );
+    (
+        (width.div_ceil(CELL_WIDTH) as u16).clamp(1, area.width.max(1)),
+        (height.div_ceil(CELL_HEIGHT) as u16).clamp(1, area.height.max(1)),
+    )
+}
+
+/// Escape sequence that shows the image with the kitty graphics protocol, scaled to the given
+/// amount of columns and rows.
+pub fn kitty_escape(image: &Image, columns: u16, rows: u16) -> String {
+    let (width, height) =
+        image.fitted_size(columns as usize * CELL_WIDTH, rows as usize * CELL_HEIGHT);
+    let data = base64::encode(image.resize(width, height).to_png());
+    let chunks: Vec<&str> = data
+        .as_bytes()
+        .chunks(4096)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap())
+        .collect();
+    let mut escape = String::new();
+    for (idx, chunk) in chunks.iter().enumerate() {
+        let more = (idx + 1 < chunks.len()) as u8;
+        if idx == 0 {
+            escape.push_str(&format!(
+                "\x1b_Ga=T,f=100,q=2,C=1,c={},r={},m={};{}\x1b\\",
+                columns, rows, more, chunk
+            ));
+        } else {
+            escape.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    escape
+}
+
+/// Escape sequence that shows the image with the inline image protocol of iTerm2.
+pub fn iterm_escape(image: &Image, columns: u16, rows: u16) -> String {
+    let (width, height) =
+        image.fitted_size(columns as usize * CELL_WIDTH, rows as usize * CELL_HEIGHT);
+    let png = image.resize(width, height).to_png();
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        columns,
+        rows,
+        base64::encode(&png)
+    )
+}
+
+/// The image as lines of upper half blocks, the foreground colors the upper pixel and the
+/// background the lower one. Transparent pixels are left to the terminal background.
+pub fn half_blocks(image: &Image, area: Rect) -> Vec<Spans<'static>> {
+    let (width, height) = image.fitted_size(area.width as usize, area.height as usize * 2);
+    let image = image.resize(width, height);
+    let color = |x: usize, y: usize| -> Color {
+        if y >= image.height {
+            return Color::Reset;
+        }
+        match image.pixel(x, y) {
+            [_, _, _, alpha] if alpha < 128 => Color::Reset,
+            [r, g, b, _] => Color::Rgb(r, g, b),
+        }
+    };
+    (0..image.height)
+        .step_by(2)
+        .map(|y| {
+            let spans: Vec<Span> = (0..image.width)
+                .map(|x| {
+                    let style = Style::default().fg(color(x, y)).bg(color(x, y + 1));
+                    Span::styled("▀", style)
+                })
+                .collect();
+            Spans::from(spans)
+        })
+        .collect()
+}
+
+/// Escape sequence that draws the image as sixels, with its colors reduced to a 6x6x6 cube.
+/// Transparent pixels are left out.
+pub fn sixel_escape(image: &Image) -> String {
+    let level = |value: u8| (value as usize * 5 + 127) / 255;
+    let color_index = |pixel: [u8; 4]| -> Option<usize> {
+        if pixel[3] < 128 {
+            return None;
+        }
+        Some(level(pixel[0]) * 36 + level(pixel[1]) * 6 + level(pixel[2]))
+    };
+
+    let mut escape = format!("\x1bPq\"1;1;{};{}", image.width, image.height);
+    for index in 0..216 {
+        let percent = |level: usize| level * 100 / 5;
+        escape.push_str(&format!(
+            "#{};2;{};{};{}",
+            index,
+            percent(index / 36),
+            percent(index / 6 % 6),
+            percent(index % 6)
+        ));
+    }
+
+    for band in (0..image.height).step_by(6) {
+        let rows = band..(band + 6).min(image.height);
+        let mut colors: Vec<usize> = rows
+            .clone()
+            .flat_map(|y| (0..image.width).filter_map(move |x| color_index(image.pixel(x, y))))
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+
+        for color in colors {
+            escape.push_str(&format!("#{}", color));
+            let mut run: Option<(char, usize)> = None;
+            for x in 0..image.width {
+                let mut bits = 0u8;
+                for y in rows.clone() {
+                    if color_index(image.pixel(x, y)) == Some(color) {
+                        bits |= 1 << (y - band);
+                    }
+                }
+                let sixel = (63 + bits) as char;
+                run = match run {
+                    Some((current, count)) if current == sixel => Some((current, count + 1)),
+                    Some((current, count)) => {
+                        push_run(&mut escape, current, count);
+                        Some((sixel, 1))
+                    }
+                    None => Some((sixel, 1)),
+                };
+            }
+            if let Some((current, count)) = run {
+                push_run(&mut escape, current, count);
+            }
+            escape.push('$');
+        }
+        escape.push('-');
+    }
+    escape.push_str("\x1b\\");
+    escape
+}
+
+fn push_run(escape: &mut String, sixel: char, count: usize) {
+    if count > 3 {
+        escape.push_str(&format!("!{}{}", count, sixel));
+    } else {
+        escape.extend(std::iter::repeat_n(sixel, count));
+    }
+}
+
+/// An image to draw over an area of the screen, `id` changes whenever what's drawn changes.
+#[derive(Clone)]
+pub struct Placement {
+    pub area: Rect,
+    pub id: u64,
+    pub escape: Rc<String>,
+}
+
+impl PartialEq for Placement {
+    fn eq(&self, other: &Self) -> bool {
+        self.area == other.area && self.id == other.id
+    }
+}
+
+/// Images can't be drawn through tui, so widgets queue them up while rendering and they're
+/// written to the terminal after the frame is drawn. They're only redrawn when they change.
+pub struct Graphics {
+    pub protocol: ImageProtocol,
+    queued: Vec<Placement>,
+    shown: Vec<Placement>,
+}
+
+impl Graphics {
+    pub fn new(protocol: ImageProtocol) -> Self {
+        Self {
+            protocol: protocol.resolve(),
+            queued: vec![],
+            shown: vec![],
+        }
+    }
+
+    pub fn place(&mut self, placement: Placement) {
+        self.queued.push(placement);
+    }
+
+    pub fn flush(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let queued = std::mem::take(&mut self.queued);
+        if queued == self.shown {
+            return Ok(());
+        }
+        write!(out, "\x1b7")?;
+        match self.protocol {
+            ImageProtocol::Kitty => write!(out, "\x1b_Ga=d,d=a,q=2\x1b\\")?,
+            _ => {
+                for placement in &self.shown {
+                    let area = placement.area;
+                    for y in area.top()..area.bottom() {
+                        write!(
+                            out,
+                            "\x1b[{};{}H{}",
+                            y + 1,
+                            area.x + 1,
+                            " ".repeat(area.width as usize)
+                        )?;
+                    }
+                }
+            }
+        }
+        for placement in &queued {
+            let area = placement.area;
+            write!(
+                out,
+                "\x1b[{};{}H{}",
+                area.y + 1,
+                area.x + 1,
+                placement.escape
+            )?;
+        }
+        write!(out, "\x1b8")?;
+        out.flush()?;
+        self.shown = queued;
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::Path;
+
+pub type ImageResult<T> = Result<T, Box<dyn Error>>;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// An rgba image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+/// A rectangle on an image, in fractions of its width and height so that it doesn't depend on
+/// the size the image is shown at.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Region {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Region {
+    /// Keeps the region within the image.
+    pub fn clamped(self) -> Self {
+        let w = self.w.clamp(0., 1.);
+        let h = self.h.clamp(0., 1.);
+        Self {
+            x: self.x.clamp(0., 1. - w),
+            y: self.y.clamp(0., 1. - h),
+            w,
+            h,
+        }
+    }
+}
+
+/// Something drawn on top of an image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overlay {
+    Fill(Region, [u8; 3]),
+    Outline(Region, [u8; 3]),
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, color: [u8; 4]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; width * height],
+        }
+    }
+
+    /// Decodes png, jpeg and gif files, gifs only by their first frame.
+    pub fn open(path: &Path) -> ImageResult<Self> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    pub fn decode(data: &[u8]) -> ImageResult<Self> {
+        if data.starts_with(&PNG_SIGNATURE) {
+            Self::from_png(data)
+        } else if data.starts_with(&[0xff, 0xd8]) {
+            Self::from_jpeg(data)
+        } else if data.starts_with(b"GIF8") {
+            Self::from_gif(data)
+        } else {
+            Err("unsupported image format".into())
+        }
+    }
+
+    pub fn from_jpeg(data: &[u8]) -> ImageResult<Self> {
+        use jpeg_decoder::PixelFormat;
+        let mut decoder = jpeg_decoder::Decoder::new(data);
+        let bytes = decoder.decode()?;
+        let info = decoder.info().ok_or("missing jpeg header")?;
+        let pixels = match info.pixel_format {
+            PixelFormat::L8 => bytes.iter().map(|&l| [l, l, l, 255]).collect(),
+            PixelFormat::L16 => bytes
+                .chunks_exact(2)
+                .map(|l| [l[0], l[0], l[0], 255])
+                .collect(),
+            PixelFormat::RGB24 => bytes
+                .chunks_exact(3)
+                .map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            PixelFormat::CMYK32 => bytes
+                .chunks_exact(4)
+                .map(|cmyk| {
+                    let k = 255 - cmyk[3] as u16;
+                    let channel = |value: u8| ((255 - value as u16) * k / 255) as u8;
+                    [channel(cmyk[0]), channel(cmyk[1]), channel(cmyk[2]), 255]
+                })
+                .collect(),
+        };
+        Ok(Self {
+            width: info.width as usize,
+            height: info.height as usize,
+            pixels,
+        })
+    }
+
+    pub fn from_gif(data: &[u8]) -> ImageResult<Self> {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(data)?;
+        let mut image = Self::new(decoder.width() as usize, decoder.height() as usize, [0; 4]);
+        let frame = decoder.read_next_frame()?.ok_or("gif without frames")?;
+        for (idx, rgba) in frame.buffer.chunks_exact(4).enumerate() {
+            let x = frame.left as usize + idx % frame.width as usize;
+            let y = frame.top as usize + idx / frame.width as usize;
+            if x < image.width && y < image.height {
+                image.pixels[y * image.width + x] = [rgba[0], rgba[1], rgba[2], rgba[3]];
+            }
+        }
+        Ok(image)
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Nearest-neighbour scaling.
+    pub fn resize(&self, width: usize, height: usize) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let source_y = y * self.height / height;
+            for x in 0..width {
+                pixels.push(self.pixel(x * self.width / width, source_y));
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// The biggest size that fits within the bounds while keeping the aspect ratio, the image is
+    /// never scaled up.
+    pub fn fitted_size(&self, max_width: usize, max_height: usize) -> (usize, usize) {
+        let scale = (max_width as f32 / self.width as f32)
+            .min(max_height as f32 / self.height as f32)
+            .min(1.);
+        (
+            ((self.width as f32 * scale).round() as usize).max(1),
+            ((self.height as f32 * scale).round() as usize).max(1),
+        )
+    }
+
+    /// The pixel bounds of the region, the ends are exclusive.
+    fn bounds(&self, region: &Region) -> (usize, usize, usize, usize) {
+        let region = region.clamped();
+        let x0 = (region.x * self.width as f32).round() as usize;
+        let y0 = (region.y * self.height as f32).round() as usize;
+        let x1 = ((region.x + region.w) * self.width as f32).round() as usize;
+        let y1 = ((region.y + region.h) * self.height as f32).round() as usize;
+        (
+            x0.min(self.width),
+            y0.min(self.height),
+            x1.min(self.width),
+            y1.min(self.height),
+        )
+    }
+
+    fn fill(&mut self, bounds: (usize, usize, usize, usize), color: [u8; 3]) {
+        let (x0, y0, x1, y1) = bounds;
+        for y in y0..y1 {
+            for x in x0..x1 {
+                self.pixels[y * self.width + x] = [color[0], color[1], color[2], 255];
+            }
+        }
+    }
+
+    pub fn draw(&mut self, overlay: &Overlay) {
+        match overlay {
+            Overlay::Fill(region, color) => self.fill(self.bounds(region), *color),
+            Overlay::Outline(region, color) => {
+                let (x0, y0, x1, y1) = self.bounds(region);
+                let thickness = (self.width.max(self.height) / 200).max(1);
+                let inner_x0 = (x0 + thickness).min(x1);
+                let inner_y0 = (y0 + thickness).min(y1);
+                let inner_x1 = x1.saturating_sub(thickness).max(inner_x0);
+                let inner_y1 = y1.saturating_sub(thickness).max(inner_y0);
+                self.fill((x0, y0, x1, inner_y0), *color);
+                self.fill((x0, inner_y1, x1, y1), *color);
+                self.fill((x0, y0, inner_x0, y1), *color);
+                self.fill((inner_x1, y0, x1, y1), *color);
+            }
+        }
+    }
+
+    pub fn from_png(data: &[u8]) -> ImageResult<Self> {
+        let mut decoder = png::Decoder::new(data);
+        // Palettes, transparency and other bit depths all come out as 8-bit samples.
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        let samples = &buffer[..info.buffer_size()];
+        let pixels = match info.color_type {
+            png::ColorType::Grayscale => samples.iter().map(|&v| [v, v, v, 255]).collect(),
+            png::ColorType::GrayscaleAlpha => samples
+                .chunks_exact(2)
+                .map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect(),
+            png::ColorType::Rgb => samples
+                .chunks_exact(3)
+                .map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            png::ColorType::Rgba => samples
+                .chunks_exact(4)
+                .map(|rgba| [rgba[0], rgba[1], rgba[2], rgba[3]])
+                .collect(),
+            png::ColorType::Indexed => return Err("unexpanded png palette".into()),
+        };
+        Ok(Self {
+            width: info.width as usize,
+            height: info.height as usize,
+            pixels,
+        })
+    }
+
+    /// Encodes the image as an 8-bit rgba png.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut png = vec![];
+        let mut encoder = png::Encoder::new(&mut png, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        let data: Vec<u8> = self.pixels.iter().flatten().copied().collect();
+        writer.write_image_data(&data).unwrap();
+        writer.finish().unwrap();
+        png
+    }
+}
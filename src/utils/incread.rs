@@ -1,20 +1,29 @@
 use super::aliases::*;
 use super::card::{Card, CardTypeData, FinishedInfo};
-use super::misc::days_until_unix;
-use super::sql::fetch::{get_incread, load_extracts, CardQuery};
-use super::sql::insert::new_incread;
+use super::misc::get_current_unix;
+use super::sql::delete::{delete_bookmark, remove_read_section};
+use super::sql::fetch::{get_incread, get_provenance, load_bookmarks, load_extracts, CardQuery};
+use super::sql::insert::{add_read_section, new_incread};
 use super::sql::update::update_inc_active;
 use super::statelist::KeyHandler;
 use crate::app::{AppData, TabData, Widget};
+use crate::popups::bookmark::NameBookmark;
 use crate::popups::edit_card::Editor;
 use crate::popups::edit_text::TextEditor;
+use crate::popups::generate_cards::CardGenerator;
 use crate::popups::newchild::{AddChildWidget, Purpose};
 use crate::utils::card::CardItem;
-use crate::utils::sql::update::update_inc_text;
+use crate::utils::cloze::{make_cloze, surrounding_text, ClozeContext};
+use crate::utils::interval::MAX_INC_PRIORITY;
+use crate::utils::pdf::page_at_row;
+use crate::utils::sql::update::{
+    set_inc_offsets, set_inc_page, set_inc_priority, set_inc_progress, update_inc_text,
+};
 use crate::utils::statelist::StatefulList;
 use crate::widgets::button::Button;
 use crate::widgets::infobox::InfoBox;
-use crate::widgets::textinput::Field;
+use crate::widgets::textinput::markup::is_heading;
+use crate::widgets::textinput::{CursorPos, Field};
 use crate::widgets::topics::TopicList;
 use crate::{MyKey, MyType};
 use rusqlite::Connection;
@@ -22,24 +31,153 @@ use std::fmt::{self, Display};
 use std::sync::{Arc, Mutex};
 use tui::style::{Modifier, Style};
 use tui::Frame;
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Clone)]
 pub struct IncListItem {
     pub text: String,
     pub id: IncID,
+    /// How much of the text has been read, shown as a bar when set.
+    pub progress: Option<f32>,
 }
 
 impl KeyHandler for IncListItem {}
 
+/// The first non-empty line of the text, without heading markup.
+pub fn inc_title(source: &str) -> &str {
+    source
+        .lines()
+        .map(|line| line.trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or("")
+}
+
 impl Display for IncListItem {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let text = if self.text.len() > 2 {
-            self.text.clone()
+            inc_title(&self.text).to_string()
         } else {
             String::from("--Empty source--")
         };
 
-        write!(f, "{}", text)
+        match self.progress {
+            Some(progress) => write!(f, "{} {}", progress_bar(progress, 10), text),
+            None => write!(f, "{}", text),
+        }
+    }
+}
+
+/// A bar like `[####------]  40%` showing a progress from 0 to 1.
+pub fn progress_bar(progress: f32, width: usize) -> String {
+    let progress = progress.clamp(0., 1.);
+    let filled = (progress * width as f32).round() as usize;
+    format!(
+        "[{}{}] {:>3}%",
+        "#".repeat(filled),
+        "-".repeat(width - filled),
+        (progress * 100.).round() as u32
+    )
+}
+
+/// A named position in a text, as a character offset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bookmark {
+    pub id: u32,
+    pub name: String,
+    pub offset: usize,
+}
+
+impl KeyHandler for Bookmark {}
+
+impl Display for Bookmark {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// The character range of the section the offset is in, from the heading at or above it up to
+/// the next heading. Text without headings is a single section.
+pub fn section_at(rows: &[String], offset: usize) -> (usize, usize) {
+    let mut starts = vec![];
+    let mut start = 0;
+    for row in rows {
+        starts.push(start);
+        start += row.graphemes(true).count() + 1;
+    }
+    let total = start.saturating_sub(1);
+    let row = starts
+        .iter()
+        .rposition(|start| *start <= offset)
+        .unwrap_or(0);
+    let first = (0..=row)
+        .rev()
+        .find(|row| is_heading(&rows[*row]))
+        .unwrap_or(0);
+    let end = match (row + 1..rows.len()).find(|row| is_heading(&rows[*row])) {
+        Some(next) => starts[next] - 1,
+        None => total,
+    };
+    (starts[first], end)
+}
+
+/// How many characters the ranges cover together, counting overlaps once.
+fn covered(ranges: &[(usize, usize)]) -> usize {
+    let mut ranges = ranges.to_vec();
+    ranges.sort_unstable();
+    let mut count = 0;
+    let mut reached = 0;
+    for (start, end) in ranges {
+        let start = start.max(reached);
+        if end > start {
+            count += end - start;
+            reached = end;
+        }
+    }
+    count
+}
+
+/// Where an imported text came from, extracts inherit it from their source.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Provenance {
+    /// Url or file path.
+    pub origin: String,
+    pub title: String,
+    pub author: String,
+}
+
+impl Provenance {
+    pub fn citation(&self) -> String {
+        let mut citation = self.title.clone();
+        if !self.author.is_empty() {
+            citation.push_str(&format!(" by {}", self.author));
+        }
+        if !self.origin.is_empty() {
+            citation.push_str(&format!("\n{}", self.origin));
+        }
+        citation
+    }
+}
+
+/// An active text in the incremental reading queue.
+#[derive(Clone)]
+pub struct IncQueueItem {
+    pub id: IncID,
+    pub text: String,
+    pub priority: u32,
+    pub days_left: i64,
+}
+
+impl KeyHandler for IncQueueItem {}
+
+impl Display for IncQueueItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let due = if self.days_left == 0 {
+            "due".to_string()
+        } else {
+            format!("{}d", self.days_left)
+        };
+        let title: String = inc_title(&self.text).chars().take(50).collect();
+        write!(f, "{:>4} p{} {}", due, self.priority, title)
     }
 }
 
@@ -66,14 +204,25 @@ pub struct IncActive {
     timestamp: UnixTime,
 }
 
+impl IncActive {
+    pub fn days_left(&self) -> i64 {
+        let due = self.timestamp.as_secs() as i64 + self.interval as i64 * 86400;
+        (due - get_current_unix().as_secs() as i64) / 86400
+    }
+}
+
 pub struct IncRead {
     pub id: IncID,
     pub parent: IncID,
     pub topic: TopicID,
     pub status: IncStatus,
+    pub priority: u32,
+    pub page: Option<u32>,
+    pub last_read: Option<UnixTime>,
     pub source: Field,
     pub extracts: Vec<IncListItem>,
     pub clozes: Vec<CardItem>,
+    pub bookmarks: Vec<Bookmark>,
 }
 
 impl IncRead {
@@ -83,28 +232,96 @@ impl IncRead {
 
     pub fn extract(&mut self, conn: &Arc<Mutex<Connection>>) {
         if let Some(extract) = self.source.return_selection() {
-            new_incread(conn, self.id, self.topic, extract, true);
+            let range = self.source.selection_range();
+            let page = range.and_then(|range| self.page_at(range.0));
+            let id = new_incread(conn, self.id, self.topic, extract, true);
+            set_inc_page(conn, id, page).unwrap();
+            set_inc_offsets(conn, id, range).unwrap();
             self.extracts = load_extracts(conn, self.id);
+            self.source.highlights.extend(range);
         }
     }
-    pub fn cloze(&mut self, conn: &Arc<Mutex<Connection>>) {
-        if let Some(cloze) = self.source.return_selection() {
-            let mut question = self.source.return_text();
-            question = question.replace(&cloze, "[...]");
-            let answer = cloze;
 
+    /// Marks the selection to be made into a cloze along with the next one.
+    pub fn mark_cloze(&mut self) {
+        if let Some(range) = self.source.selection_range() {
+            self.source.marks.push(range);
+        }
+    }
+
+    /// Makes a cloze card out of the selection and each marked range.
+    pub fn cloze(&mut self, conn: &Arc<Mutex<Connection>>, context: ClozeContext) {
+        let mut ranges = std::mem::take(&mut self.source.marks);
+        ranges.extend(self.source.selection_range());
+        ranges.sort_unstable();
+        ranges.dedup();
+        if ranges.is_empty() {
+            return;
+        }
+        let text = self.source.return_text();
+        for range in ranges {
+            let (question, answer) = make_cloze(&text, range, context);
             Card::new(CardTypeData::Finished(FinishedInfo::default()))
                 .question(question)
                 .answer(answer)
                 .topic(self.topic)
                 .source(self.id)
+                .page(self.page_at(range.0))
+                .source_range(Some(range))
                 .save_card(conn);
-            self.clozes = CardQuery::default().source(self.id).fetch_carditems(conn);
+            self.source.highlights.push(range);
+        }
+        self.clozes = CardQuery::default().source(self.id).fetch_carditems(conn);
+    }
+
+    /// The page of a pdf the character offset is on, if the text came from one.
+    pub fn page_at(&self, offset: usize) -> Option<u32> {
+        let row = self.source.pos_at_offset(offset).row;
+        page_at_row(&self.source.text, row).or(self.page)
+    }
+
+    /// How much of the text has been read, from 0 to 1. Everything before the cursor counts as
+    /// read, along with the sections marked as read.
+    pub fn progress(&self) -> f32 {
+        let last_row = self.source.text.len() - 1;
+        let total = self.source.offset_of(&CursorPos {
+            row: last_row,
+            column: self.source.get_rowlen(last_row),
+        });
+        if total == 0 {
+            return 0.;
+        }
+        let mut ranges = self.source.dimmed.clone();
+        ranges.push((0, self.source.offset_of(&self.source.cursor)));
+        (covered(&ranges) as f32 / total as f32).min(1.)
+    }
+
+    /// Marks the section under the cursor as read, or unmarks it if it already was.
+    pub fn toggle_read(&mut self, conn: &Arc<Mutex<Connection>>) {
+        let offset = self.source.offset_of(&self.source.cursor);
+        let section = section_at(&self.source.text, offset);
+        if let Some(idx) = self
+            .source
+            .dimmed
+            .iter()
+            .position(|range| *range == section)
+        {
+            self.source.dimmed.remove(idx);
+            remove_read_section(conn, self.id, section).unwrap();
+        } else {
+            self.source.dimmed.push(section);
+            add_read_section(conn, self.id, section).unwrap();
         }
     }
+
+    pub fn reload_bookmarks(&mut self, conn: &Arc<Mutex<Connection>>) {
+        self.bookmarks = load_bookmarks(conn, self.id);
+    }
+
     pub fn update_text(&self, conn: &Arc<Mutex<Connection>>) {
         let text = self.source.return_text();
         update_inc_text(conn, text, self.id, &self.source.cursor).unwrap();
+        set_inc_progress(conn, self.id, self.progress()).unwrap();
     }
 }
 
@@ -113,8 +330,10 @@ pub struct IncView<'a> {
     pub topics: TopicList,
     pub extracts: StatefulList<IncListItem>,
     pub clozes: StatefulList<CardItem>,
+    pub bookmarks: StatefulList<Bookmark>,
     pub parent: Button<'a>,
     pub info: InfoBox<'a>,
+    pub provenance: Option<Provenance>,
 }
 
 impl<'a> IncView<'a> {
@@ -123,19 +342,23 @@ impl<'a> IncView<'a> {
         let topics = TopicList::new(&appdata.conn);
         let extracts = StatefulList::with_items("Extracts".to_string(), text.extracts.clone());
         let clozes = StatefulList::with_items("Clozes".to_string(), text.clozes.clone());
+        let bookmarks = StatefulList::with_items("Bookmarks".to_string(), text.bookmarks.clone());
         let mut parent = Button::new("Go to parent".to_string());
         let parent_id = text.parent;
         if parent_id == 0 {
             parent.inner.textstyle = Style::default().add_modifier(Modifier::DIM);
         }
         let info = InfoBox::new("hey there".to_string());
+        let provenance = get_provenance(&appdata.conn, id);
         Self {
             text,
             topics,
             extracts,
             clozes,
+            bookmarks,
             parent,
             info,
+            provenance,
         }
     }
 
@@ -143,10 +366,32 @@ impl<'a> IncView<'a> {
         self.save_state(appdata);
         let inc = get_incread(&appdata.conn, id);
         self.text = inc;
+        self.provenance = get_provenance(&appdata.conn, id);
 
         self.extracts =
             StatefulList::with_items("Extracts".to_string(), self.text.extracts.clone());
         self.clozes = StatefulList::with_items("Clozes".to_string(), self.text.clozes.clone());
+        self.bookmarks =
+            StatefulList::with_items("Bookmarks".to_string(), self.text.bookmarks.clone());
+    }
+
+    /// Picks up bookmarks added in a popup.
+    pub fn reload_bookmarks(&mut self, appdata: &AppData) {
+        self.text.reload_bookmarks(&appdata.conn);
+        let area = self.bookmarks.get_area();
+        self.bookmarks =
+            StatefulList::with_items("Bookmarks".to_string(), self.text.bookmarks.clone());
+        self.bookmarks.set_area(area);
+    }
+
+    /// Picks up cards made from the text in a popup.
+    pub fn reload_clozes(&mut self, appdata: &AppData) {
+        self.text.clozes = CardQuery::default()
+            .source(self.text.id)
+            .fetch_carditems(&appdata.conn);
+        let area = self.clozes.get_area();
+        self.clozes = StatefulList::with_items("Clozes".to_string(), self.text.clozes.clone());
+        self.clozes.set_area(area);
     }
 
     pub fn prev_sibling(&mut self, appdata: &AppData) {
@@ -195,6 +440,14 @@ impl<'a> IncView<'a> {
                 let id = self.text.id;
                 update_inc_active(&appdata.conn, id, false).unwrap();
             }
+            MyKey::Alt('+') => {
+                self.text.priority = std::cmp::min(self.text.priority + 1, MAX_INC_PRIORITY);
+                set_inc_priority(&appdata.conn, self.text.id, self.text.priority).unwrap();
+            }
+            MyKey::Alt('-') => {
+                self.text.priority = self.text.priority.saturating_sub(1);
+                set_inc_priority(&appdata.conn, self.text.id, self.text.priority).unwrap();
+            }
             MyKey::Up if self.info.is_selected(cursor) => {
                 let parent = self.text.parent;
                 if parent != 0 {
@@ -218,6 +471,33 @@ impl<'a> IncView<'a> {
                 }
             }
             key if self.clozes.is_selected(cursor) => self.clozes.keyhandler(appdata, key),
+            MyKey::Enter if self.bookmarks.is_selected(cursor) => {
+                if let Some(idx) = self.bookmarks.state.selected() {
+                    let offset = self.bookmarks.items[idx].offset;
+                    self.text.source.jump_to_offset(offset);
+                }
+            }
+            MyKey::Delete if self.bookmarks.is_selected(cursor) => {
+                if let Some(idx) = self.bookmarks.state.selected() {
+                    delete_bookmark(&appdata.conn, self.bookmarks.items[idx].id).unwrap();
+                    self.reload_bookmarks(appdata);
+                }
+            }
+            key if self.bookmarks.is_selected(cursor) => self.bookmarks.keyhandler(appdata, key),
+            MyKey::Alt('b') if self.text.source.is_selected(cursor) => {
+                let cursor = self.text.source.cursor;
+                let name = self.text.source.text[cursor.row]
+                    .trim()
+                    .chars()
+                    .take(40)
+                    .collect();
+                let offset = self.text.source.offset_of(&cursor);
+                let popup = NameBookmark::new(self.text.id, offset, name);
+                tabdata.popup = Some(Box::new(popup));
+            }
+            MyKey::Alt('r') if self.text.source.is_selected(cursor) => {
+                self.text.toggle_read(&appdata.conn);
+            }
 
             MyKey::Alt('x') if self.text.source.is_selected(cursor) => {
                 self.text.extract(&appdata.conn);
@@ -225,10 +505,39 @@ impl<'a> IncView<'a> {
                     StatefulList::with_items("Extracts".to_string(), self.text.extracts.clone());
                 self.text.source.clear_selection();
             }
+            MyKey::Alt('Z') if self.text.source.is_selected(cursor) => {
+                self.text.mark_cloze();
+                self.text.source.clear_selection();
+            }
+            MyKey::Alt('n') if self.text.source.is_selected(cursor) => {
+                if let Some(range) = self.text.source.selection_range() {
+                    let text = self.text.source.return_text();
+                    let context = appdata.config.cloze.context;
+                    let question = surrounding_text(&text, range, context);
+                    let answer = self.text.source.return_selection().unwrap_or_default();
+                    let tab = AddChildWidget::new(appdata, Purpose::Source(self.text.id))
+                        .prefill(question, answer)
+                        .source_range(range, self.text.page_at(range.0));
+                    tabdata.popup = Some(Box::new(tab));
+                    self.text.source.clear_selection();
+                }
+            }
+            MyKey::Alt('g') if self.text.source.is_selected(cursor) => {
+                let text = self
+                    .text
+                    .source
+                    .return_selection()
+                    .unwrap_or_else(|| self.text.source.return_text());
+                if !text.trim().is_empty() {
+                    let popup = CardGenerator::new(appdata, self.text.id, self.text.topic, &text);
+                    tabdata.popup = Some(Box::new(popup));
+                    self.text.source.clear_selection();
+                }
+            }
             MyKey::Alt('z') if self.text.source.is_selected(cursor) => {
-                self.text.cloze(&appdata.conn);
+                self.text.cloze(&appdata.conn, appdata.config.cloze.context);
                 self.clozes =
-                    StatefulList::with_items("Extracts".to_string(), self.text.clozes.clone());
+                    StatefulList::with_items("Clozes".to_string(), self.text.clozes.clone());
 
                 self.text.source.clear_selection();
             }
@@ -247,11 +556,35 @@ impl<'a> IncView<'a> {
             IncStatus::Done => "This text is complete".to_string(),
             IncStatus::Active(val) => {
                 format!(
-                    "Text is active! Interval is {}, next review is in some days",
+                    "Text is active! Interval is {} days, next review is in {} days",
                     val.interval,
+                    std::cmp::max(val.days_left(), 0),
                 )
             }
         };
+        let mut text = format!(
+            "{}\nPriority: {}/{}",
+            text, self.text.priority, MAX_INC_PRIORITY
+        );
+        if let Some(page) = self.text.page {
+            text.push_str(&format!("\nPage: {}", page));
+        }
+        text.push_str(&format!(
+            "\nRead: {}",
+            progress_bar(self.text.progress(), 10)
+        ));
+        let last_read = match self.text.last_read {
+            Some(time) => match get_current_unix().saturating_sub(time).as_secs() / 86400 {
+                0 => "today".to_string(),
+                1 => "yesterday".to_string(),
+                days => format!("{} days ago", days),
+            },
+            None => "never".to_string(),
+        };
+        text.push_str(&format!("\nLast read: {}", last_read));
+        if let Some(provenance) = &self.provenance {
+            text.push_str(&format!("\nFrom: {}", provenance.citation()));
+        }
         self.info.change_text(text);
     }
 
@@ -259,6 +592,7 @@ impl<'a> IncView<'a> {
         self.text.source.render(f, appdata, cursor);
         self.extracts.render(f, appdata, cursor);
         self.clozes.render(f, appdata, cursor);
+        self.bookmarks.render(f, appdata, cursor);
         self.topics.render(f, appdata, cursor);
         self.parent.render(f, appdata, cursor);
         self.info.render(f, appdata, cursor);
@@ -23,6 +23,19 @@ pub fn strength_algo(passed: Duration, stability: Duration) -> f32 {
     (stability.as_secs() as f32 / passed.as_secs() as f32) * e.powf((0.9_f32).log(e))
 }
 
+pub const MAX_INC_PRIORITY: u32 = 10;
+
+/// Next interval in days for an incremental reading text.
+///
+/// Intervals grow by 2x at the lowest priority and not at all at the highest. The further the
+/// reader got into the text since it was last shown (`progress`, 0 to 1), the sooner it comes back.
+pub fn inc_interval(prev: u32, priority: u32, progress: f32, jitter: f32) -> u32 {
+    let priority = priority.min(MAX_INC_PRIORITY) as f32 / MAX_INC_PRIORITY as f32;
+    let growth = 2.0 - priority;
+    let progress = 1.0 - progress.clamp(0., 1.) * 0.5;
+    std::cmp::max((prev as f32 * growth * progress * jitter).round() as u32, 1)
+}
+
 fn time_passed_since_review(review: &Review) -> std::time::Duration {
     let now = get_current_unix();
     let date = review.date;
@@ -104,6 +117,24 @@ pub fn calc_stability(
     }
 }
 
+/// Replays the review history of a finished card to get its stability and strength, for when
+/// the history changed other than by reviewing the card, like when another card is merged into it.
+pub fn recalc_card(conn: &Arc<Mutex<Connection>>, id: CardID) {
+    let history = get_history(conn, id);
+    let last = match history.last() {
+        Some(last) => last,
+        None => return,
+    };
+    let mut stability = Duration::default();
+    for idx in 0..history.len() {
+        stability = calc_stability(&history[..idx].to_vec(), &history[idx], stability);
+    }
+    set_stability(conn, id, stability);
+    // A review from this very second would make it infinitely strong.
+    let passed = time_passed_since_review(last).max(Duration::from_secs(1));
+    update_strength(conn, id, strength_algo(passed, stability));
+}
+
 // call function BEFORE you insert new review to database
 pub fn new_card_stability(conn: Conn, id: CardID, new_review: &Review) -> Duration {
     let history = get_history(conn, id);
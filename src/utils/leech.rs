@@ -0,0 +1,71 @@
+use crate::app::AppData;
+use crate::popups::menu::{Menu, TraitButton};
+use crate::popups::newchild::{AddChildWidget, Purpose};
+use crate::utils::aliases::*;
+use crate::utils::sql::fetch::cards::{get_lapses, is_leech};
+use crate::utils::sql::insert::new_leech;
+use crate::utils::sql::update::set_suspended;
+use serde_derive::Deserialize;
+
+#[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LeechAction {
+    #[default]
+    Tag,
+    Suspend,
+}
+
+/// Set under `[leech]` in the config file, a threshold of 0 turns leech detection off.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LeechConfig {
+    pub threshold: u32,
+    pub action: LeechAction,
+}
+
+impl Default for LeechConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 8,
+            action: LeechAction::Tag,
+        }
+    }
+}
+
+/// Tags the card as a leech if it has lapsed too many times.
+/// Returns true only when the card became a leech just now.
+pub fn check_leech(appdata: &AppData, id: CardID) -> bool {
+    let conn = &appdata.conn;
+    let config = &appdata.config.leech;
+    if config.threshold == 0 || is_leech(conn, id) || get_lapses(conn, id) < config.threshold {
+        return false;
+    }
+    new_leech(conn, id).unwrap();
+    if config.action == LeechAction::Suspend {
+        set_suspended(conn, [id], true);
+    }
+    true
+}
+
+/// Suggests breaking the leech up into simpler cards that it depends on.
+pub fn leech_menu<'a>(appdata: &AppData, id: CardID) -> Menu<'a> {
+    let lapses = get_lapses(&appdata.conn, id);
+    let suspended = match appdata.config.leech.action {
+        LeechAction::Suspend => " and has been suspended",
+        LeechAction::Tag => "",
+    };
+    let prompt = format!(
+        "You've failed this card {} times, it's now tagged as a leech{}.\n\nIt might be easier to remember if you split it up into simpler dependencies.\nPress Esc to continue reviewing.",
+        lapses, suspended
+    );
+
+    let add_dependency = move |appdata: &AppData| -> Box<dyn crate::app::Tab> {
+        Box::new(AddChildWidget::new(appdata, Purpose::Dependency(vec![id])))
+    };
+    let buttons = [TraitButton::new(
+        Box::new(add_dependency),
+        "Add simpler dependency",
+        true,
+    )];
+    Menu::new("Leech".to_string(), prompt, 4, 4, buttons)
+}
@@ -0,0 +1,131 @@
+use crate::utils::aliases::*;
+use crate::utils::misc::get_current_day;
+use crate::utils::sql::fetch::{get_daily_counts, get_topics};
+use serde_derive::Deserialize;
+use std::collections::HashMap;
+
+/// Daily limits as set in the `[limits]` section of the config file.
+///
+/// Topic-specific limits are keyed by topic name and apply on top of the global ones.
+#[derive(Deserialize, Default, Clone, Debug)]
+pub struct LimitsConfig {
+    pub new_cards: Option<u32>,
+    pub reviews: Option<u32>,
+    /// Daily reading budget for incremental reading texts.
+    pub incread: Option<u32>,
+    #[serde(default)]
+    pub interleave: bool,
+    #[serde(default)]
+    pub topics: HashMap<String, TopicLimit>,
+}
+
+#[derive(Deserialize, Default, Clone, Debug)]
+pub struct TopicLimit {
+    pub new_cards: Option<u32>,
+    pub reviews: Option<u32>,
+    pub incread: Option<u32>,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub struct DailyCount {
+    pub new_cards: u32,
+    pub reviews: u32,
+    pub incread: u32,
+}
+
+#[derive(Clone, Copy)]
+pub enum LimitKind {
+    NewCards,
+    Reviews,
+    IncRead,
+}
+
+impl DailyCount {
+    fn get(&self, kind: LimitKind) -> u32 {
+        match kind {
+            LimitKind::NewCards => self.new_cards,
+            LimitKind::Reviews => self.reviews,
+            LimitKind::IncRead => self.incread,
+        }
+    }
+}
+
+impl TopicLimit {
+    fn get(&self, kind: LimitKind) -> Option<u32> {
+        match kind {
+            LimitKind::NewCards => self.new_cards,
+            LimitKind::Reviews => self.reviews,
+            LimitKind::IncRead => self.incread,
+        }
+    }
+}
+
+/// How many more cards of a kind can be shown today, globally and per topic.
+#[derive(Default, Debug)]
+pub struct Remaining {
+    pub global: Option<u32>,
+    pub topics: HashMap<TopicID, u32>,
+}
+
+impl Remaining {
+    pub fn new(
+        limits: &LimitsConfig,
+        topic_ids: &HashMap<String, TopicID>,
+        counts: &HashMap<TopicID, DailyCount>,
+        kind: LimitKind,
+    ) -> Self {
+        let global_limit = match kind {
+            LimitKind::NewCards => limits.new_cards,
+            LimitKind::Reviews => limits.reviews,
+            LimitKind::IncRead => limits.incread,
+        };
+        let total: u32 = counts.values().map(|count| count.get(kind)).sum();
+        let global = global_limit.map(|limit| limit.saturating_sub(total));
+
+        let mut topics = HashMap::new();
+        for (name, topiclimit) in &limits.topics {
+            if let (Some(id), Some(limit)) = (topic_ids.get(name), topiclimit.get(kind)) {
+                let done = counts.get(id).map(|count| count.get(kind)).unwrap_or(0);
+                topics.insert(*id, limit.saturating_sub(done));
+            }
+        }
+        Self { global, topics }
+    }
+
+    /// Keeps the items, in order, that fit within the remaining limits.
+    pub fn apply(mut self, items: Vec<(u32, TopicID)>) -> Vec<u32> {
+        let mut kept = vec![];
+        for (id, topic) in items {
+            if self.global == Some(0) {
+                break;
+            }
+            if let Some(left) = self.topics.get_mut(&topic) {
+                if *left == 0 {
+                    continue;
+                }
+                *left -= 1;
+            }
+            if let Some(left) = &mut self.global {
+                *left -= 1;
+            }
+            kept.push(id);
+        }
+        kept
+    }
+}
+
+/// Cuts down the cards or texts for review so that today's limits aren't exceeded.
+pub fn limit_queue(
+    conn: Conn,
+    limits: &LimitsConfig,
+    items: Vec<(u32, TopicID)>,
+    kind: LimitKind,
+) -> Vec<u32> {
+    let topic_ids = get_topics(conn)
+        .unwrap()
+        .into_iter()
+        .map(|topic| (topic.name, topic.id))
+        .collect();
+    let counts = get_daily_counts(conn, get_current_day());
+    Remaining::new(limits, &topic_ids, &counts, kind).apply(items)
+}
@@ -0,0 +1,346 @@
+use crate::utils::aliases::*;
+use crate::utils::card::{Card, CardItem, CardTypeData, FinishedInfo};
+use crate::utils::sql::fetch::CardQuery;
+use crate::utils::statelist::KeyHandler;
+use rusqlite::Connection;
+use serde_derive::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub type LlmResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Set under `[llm]` in the config file. Any server with an OpenAI compatible chat endpoint
+/// works, like ollama (`http://localhost:11434/v1`) or the llama.cpp server
+/// (`http://localhost:8080/v1`).
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LlmConfig {
+    pub endpoint: String,
+    pub model: String,
+    /// Falls back to `gptkey`, local servers usually don't need one.
+    pub api_key: Option<String>,
+    /// What the model is asked, `{question}` is replaced with the question of the card.
+    pub prompt: String,
+    /// What the model is asked to turn an extract into cards, `{text}` is replaced with the
+    /// extract and `{count}` with `card_count`.
+    pub cards_prompt: String,
+    pub card_count: usize,
+    /// What the model is asked to find the dependencies of a card, `{question}` and `{answer}`
+    /// are replaced with the card and `{cards}` with the existing cards most alike to it.
+    pub dependencies_prompt: String,
+    /// How many of the existing cards the model gets to pick dependencies from.
+    pub candidates: usize,
+    /// Seconds to wait for an answer before giving up.
+    pub timeout: u64,
+    pub max_tokens: u32,
+    /// The limit for proposing cards, which takes longer answers than the rest.
+    pub cards_max_tokens: u32,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "https://api.openai.com/v1".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            api_key: None,
+            prompt: "Answer the following flashcard question as briefly as possible, \
+                     without repeating the question.\n\n{question}"
+                .to_string(),
+            cards_prompt: "Make up to {count} flashcards about the most important facts in the \
+                           text below. Use question and answer cards, or cloze cards where the \
+                           question is a sentence from the text with the hidden part replaced \
+                           by [...] and the answer is the hidden part. Reply with only a json \
+                           array of objects with a \"question\" and an \"answer\".\n\n{text}"
+                .to_string(),
+            card_count: 5,
+            dependencies_prompt: "A flashcard asks \"{question}\" with the answer \"{answer}\". \
+                                  Which of the cards below have to be known to understand it, and \
+                                  which concepts it relies on have no card yet? Reply with only a \
+                                  json object with the ids of the cards as \"prerequisites\" and \
+                                  the concepts as \"missing\".\n\n{cards}"
+                .to_string(),
+            candidates: 20,
+            timeout: 30,
+            max_tokens: 200,
+            cards_max_tokens: 1000,
+        }
+    }
+}
+
+impl LlmConfig {
+    pub fn answer_prompt(&self, question: &str) -> String {
+        self.prompt.replace("{question}", question.trim())
+    }
+
+    pub fn cards_prompt(&self, text: &str) -> String {
+        self.cards_prompt
+            .replace("{count}", &self.card_count.to_string())
+            .replace("{text}", text.trim())
+    }
+
+    pub fn dependencies_prompt(&self, question: &str, answer: &str, cards: &[CardItem]) -> String {
+        let cards: Vec<String> = cards
+            .iter()
+            .map(|card| format!("{}: {}", card.id, card.question.replace('\n', " ")))
+            .collect();
+        self.dependencies_prompt
+            .replace("{question}", question.trim())
+            .replace("{answer}", answer.trim())
+            .replace("{cards}", &cards.join("\n"))
+    }
+}
+
+/// A card the model came up with, it's only saved once it's been accepted.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct CardProposal {
+    pub question: String,
+    pub answer: String,
+}
+
+impl fmt::Display for CardProposal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.question)
+    }
+}
+
+impl CardProposal {
+    pub fn save(&self, conn: &Arc<Mutex<Connection>>, topic: TopicID, source: IncID) -> CardID {
+        Card::new(CardTypeData::Finished(FinishedInfo::default()))
+            .question(self.question.trim().to_string())
+            .answer(self.answer.trim().to_string())
+            .topic(topic)
+            .source(source)
+            .save_card(conn)
+    }
+}
+
+/// Reads the cards out of the json array in the response. Models like to wrap it in a code
+/// block or add some words around it, so everything outside of the array is ignored.
+pub fn parse_cards(response: &str) -> LlmResult<Vec<CardProposal>> {
+    let start = response.find('[').ok_or("no cards in the response")?;
+    let end = response.rfind(']').ok_or("no cards in the response")?;
+    if end < start {
+        return Err("no cards in the response".into());
+    }
+    let items: Vec<Value> = serde_json::from_str(&response[start..=end])?;
+    Ok(items
+        .into_iter()
+        .filter_map(|item| serde_json::from_value::<CardProposal>(item).ok())
+        .filter(|card| !card.question.trim().is_empty())
+        .collect())
+}
+
+/// A dependency the model thinks a card has.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Suggestion {
+    Existing(CardItem),
+    /// A concept without a card, accepting it makes an unfinished card for it, for a new card
+    /// once that's submitted.
+    Missing(String),
+}
+
+impl fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Existing(card) => write!(f, "{}", card.question),
+            Self::Missing(concept) => write!(f, "new: {}", concept),
+        }
+    }
+}
+
+impl KeyHandler for Suggestion {}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() > 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// The existing cards sharing the most words with the text, best first. Words that many
+/// cards have count for less, so that "what" and "the" don't make cards alike. Only these
+/// cards are shown to the model, as there are usually too many to send them all.
+pub fn similar_cards(
+    conn: &Arc<Mutex<Connection>>,
+    text: &str,
+    exclude: &[CardID],
+    limit: usize,
+) -> Vec<CardItem> {
+    let wanted = words(text);
+    let cards: Vec<(HashSet<String>, CardItem)> = CardQuery::default()
+        .fetch_carditems(conn)
+        .into_iter()
+        .map(|card| (words(&card.question), card))
+        .collect();
+    let total = cards.len() as f32;
+    let weight = |word: &String| {
+        let count = cards
+            .iter()
+            .filter(|(words, _)| words.contains(word))
+            .count();
+        (total / count as f32).ln()
+    };
+    let weights: Vec<(String, f32)> = wanted
+        .iter()
+        .filter(|word| cards.iter().any(|(words, _)| words.contains(*word)))
+        .map(|word| (word.clone(), weight(word)))
+        .collect();
+
+    let mut scored: Vec<(f32, CardItem)> = cards
+        .into_iter()
+        .filter(|(_, card)| !exclude.contains(&card.id))
+        .map(|(words, card)| {
+            let score = weights
+                .iter()
+                .filter(|(word, _)| words.contains(word))
+                .map(|(_, weight)| weight)
+                .sum();
+            (score, card)
+        })
+        .filter(|(score, _)| *score > 0.)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.id.cmp(&b.1.id)));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, card)| card)
+        .collect()
+}
+
+/// Reads the suggestions out of the json object in the response. Ids that weren't among the
+/// candidates are left out, the model sometimes makes them up.
+pub fn parse_suggestions(response: &str, candidates: &[CardItem]) -> LlmResult<Vec<Suggestion>> {
+    let start = response.find('{').ok_or("no suggestions in the response")?;
+    let end = response
+        .rfind('}')
+        .ok_or("no suggestions in the response")?;
+    if end < start {
+        return Err("no suggestions in the response".into());
+    }
+    let json: Value = serde_json::from_str(&response[start..=end])?;
+    let mut suggestions = vec![];
+    for id in json["prerequisites"].as_array().into_iter().flatten() {
+        let id = match id {
+            Value::String(id) => id.trim().parse().ok(),
+            id => id.as_u64(),
+        };
+        if let Some(card) = candidates.iter().find(|card| Some(card.id as u64) == id) {
+            let suggestion = Suggestion::Existing(card.clone());
+            if !suggestions.contains(&suggestion) {
+                suggestions.push(suggestion);
+            }
+        }
+    }
+    for concept in json["missing"].as_array().into_iter().flatten() {
+        if let Some(concept) = concept.as_str().map(str::trim) {
+            if !concept.is_empty() {
+                suggestions.push(Suggestion::Missing(concept.to_string()));
+            }
+        }
+    }
+    Ok(suggestions)
+}
+
+/// Something that can answer a prompt, so that the model behind it can be swapped out.
+pub trait AnswerGenerator: Send {
+    fn generate(&self, prompt: &str) -> LlmResult<String>;
+}
+
+/// Talks to the `/chat/completions` endpoint that OpenAI and most local servers provide.
+pub struct OpenAiChat {
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+    timeout: Duration,
+    max_tokens: u32,
+}
+
+impl OpenAiChat {
+    pub fn new(config: &LlmConfig, fallback_key: Option<&str>) -> Self {
+        let api_key = config
+            .api_key
+            .as_deref()
+            .or(fallback_key)
+            .filter(|key| !key.trim().is_empty())
+            .map(str::to_string);
+        Self {
+            endpoint: config.endpoint.trim_end_matches('/').to_string(),
+            model: config.model.clone(),
+            api_key,
+            timeout: Duration::from_secs(config.timeout.max(1)),
+            max_tokens: config.max_tokens,
+        }
+    }
+
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+}
+
+impl AnswerGenerator for OpenAiChat {
+    fn generate(&self, prompt: &str) -> LlmResult<String> {
+        let body = json!({
+            "model": self.model,
+            "messages": [{"role": "user", "content": prompt}],
+            "max_tokens": self.max_tokens,
+        });
+        let mut request = reqwest::blocking::Client::builder()
+            .timeout(self.timeout)
+            .build()?
+            .post(format!("{}/chat/completions", self.endpoint))
+            .header(reqwest::header::USER_AGENT, "speki")
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request.send()?.error_for_status()?;
+        let json: Value = serde_json::from_str(&response.text()?)?;
+        let content = json["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or("no answer in the response")?;
+        Ok(content.trim().to_string())
+    }
+}
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// A prompt being answered on another thread, so that the ui keeps going in the meantime.
+pub struct Generation {
+    receiver: Receiver<LlmResult<String>>,
+    started: Instant,
+}
+
+impl Generation {
+    pub fn start(generator: Box<dyn AnswerGenerator>, prompt: String) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            // Nobody is listening anymore if the view was closed in the meantime.
+            let _ = sender.send(generator.generate(&prompt));
+        });
+        Self {
+            receiver,
+            started: Instant::now(),
+        }
+    }
+
+    /// The result once it's there, without waiting for it.
+    pub fn poll(&self) -> Option<LlmResult<String>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("the generator stopped".into())),
+        }
+    }
+
+    pub fn spinner(&self) -> char {
+        let frame = self.started.elapsed().as_millis() / 100;
+        SPINNER[frame as usize % SPINNER.len()]
+    }
+}
@@ -0,0 +1,95 @@
+use crate::utils::card::Card;
+use sha2::{Digest, Sha256};
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const AUDIO_EXTENSIONS: [&str; 4] = ["mp3", "wav", "ogg", "flac"];
+pub const IMAGE_EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "gif"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Front,
+    Back,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MediaKind {
+    Audio,
+    Image,
+}
+
+impl MediaKind {
+    /// Tells audio from images by the file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        if AUDIO_EXTENSIONS.contains(&extension.as_str()) {
+            Some(Self::Audio)
+        } else if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+            Some(Self::Image)
+        } else {
+            None
+        }
+    }
+}
+
+/// The audio and images attached to the sides of a card.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CardMedia {
+    pub frontaudio: Option<PathBuf>,
+    pub backaudio: Option<PathBuf>,
+    pub frontimage: Option<PathBuf>,
+    pub backimage: Option<PathBuf>,
+}
+
+impl CardMedia {
+    pub fn from_card(card: &Card) -> Self {
+        Self {
+            frontaudio: card.frontaudio.clone(),
+            backaudio: card.backaudio.clone(),
+            frontimage: card.frontimage.clone(),
+            backimage: card.backimage.clone(),
+        }
+    }
+
+    pub fn get(&self, side: Side, kind: MediaKind) -> Option<&PathBuf> {
+        match (side, kind) {
+            (Side::Front, MediaKind::Audio) => self.frontaudio.as_ref(),
+            (Side::Back, MediaKind::Audio) => self.backaudio.as_ref(),
+            (Side::Front, MediaKind::Image) => self.frontimage.as_ref(),
+            (Side::Back, MediaKind::Image) => self.backimage.as_ref(),
+        }
+    }
+
+    pub fn set(&mut self, side: Side, kind: MediaKind, path: Option<PathBuf>) {
+        let slot = match (side, kind) {
+            (Side::Front, MediaKind::Audio) => &mut self.frontaudio,
+            (Side::Back, MediaKind::Audio) => &mut self.backaudio,
+            (Side::Front, MediaKind::Image) => &mut self.frontimage,
+            (Side::Back, MediaKind::Image) => &mut self.backimage,
+        };
+        *slot = path;
+    }
+
+    /// Removes both the audio and the image of a side.
+    pub fn clear(&mut self, side: Side) {
+        self.set(side, MediaKind::Audio, None);
+        self.set(side, MediaKind::Image, None);
+    }
+}
+
+/// Copies a file into the media folder, named after the hash of its contents so that the same
+/// file attached twice is only stored once.
+pub fn import_media(media: &Path, file: &Path) -> io::Result<PathBuf> {
+    let data = std::fs::read(file)?;
+    let hash = format!("{:x}", Sha256::digest(&data));
+    let mut name = hash[..32].to_string();
+    if let Some(extension) = file.extension().and_then(|extension| extension.to_str()) {
+        name = format!("{}.{}", name, extension.to_lowercase());
+    }
+    std::fs::create_dir_all(media)?;
+    let path = media.join(name);
+    if !path.exists() {
+        std::fs::write(&path, data)?;
+    }
+    Ok(path)
+}
@@ -54,83 +54,37 @@ pub fn play_audio(audio: &Option<Audio>, path: PathBuf) {
     }
 }
 
-#[derive(Deserialize, Debug)]
-struct OpenAIChoices {
-    text: String,
-}
-
-#[derive(Deserialize, Debug)]
-struct OpenAIResponse {
-    choices: Vec<OpenAIChoices>,
+/// Plays the files one after another.
+pub fn play_audio_queue(audio: &Option<Audio>, paths: Vec<PathBuf>) {
+    if let Some(audio) = audio {
+        queue_audio(&audio.handle, audio.volume, paths);
+    }
 }
 
-#[derive(Serialize, Debug)]
-struct OpenAIRequest {
-    model: String,
-    prompt: String,
-    max_tokens: u32,
-    stop: String,
+/// Like [`play_audio_queue`], but with only the handle of the output so that it can be called
+/// from another thread.
+pub fn queue_audio(handle: &rodio::OutputStreamHandle, volume: f32, paths: Vec<PathBuf>) {
+    let sink = match rodio::Sink::try_new(handle) {
+        Ok(sink) => sink,
+        Err(_) => return,
+    };
+    for path in paths {
+        if let Ok(file) = std::fs::File::open(&path) {
+            if let Ok(source) = rodio::Decoder::new(BufReader::new(file)) {
+                sink.append(source);
+            }
+        }
+    }
+    sink.set_volume(volume);
+    sink.detach();
 }
 
-use hyper::{body::Buf, header, Body, Client, Request};
-use hyper_tls::HttpsConnector;
-use serde_derive::{Deserialize, Serialize};
-
 use super::{
     aliases::{CardID, Pos, UnixTime},
     sql::fetch::cards::fetch_card,
     statelist::StatefulList,
 };
 
-// stole code from here: https://github.com/zahidkhawaja/rusty
-#[tokio::main]
-pub async fn get_gpt3_response(api_key: &str, user_input: &str) -> Option<String> {
-    let https = HttpsConnector::new();
-    let client = Client::builder().build(https);
-    let uri = "https://api.openai.com/v1/completions";
-
-    let model = String::from("text-davinci-002");
-    let stop = String::from("Text");
-
-    let auth_header_val = format!("Bearer {}", api_key);
-
-    let openai_request = OpenAIRequest {
-        model,
-        prompt: format!("{}\n\n", user_input),
-        max_tokens: 200,
-        stop,
-    };
-
-    let body = Body::from(serde_json::to_vec(&openai_request).unwrap());
-
-    let req = Request::post(uri)
-        .header(header::CONTENT_TYPE, "application/json")
-        .header("Authorization", &auth_header_val)
-        .body(body)
-        .unwrap();
-
-    let res = client.request(req).await.unwrap();
-
-    let body = hyper::body::aggregate(res).await.unwrap();
-
-    let json: OpenAIResponse = match serde_json::from_reader(body.reader()) {
-        Ok(response) => response,
-        Err(_) => {
-            return None;
-        }
-    };
-
-    Some(
-        json.choices[0]
-            .text
-            .split('\n')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<_>>()
-            .join("\n"),
-    )
-}
-
 pub fn get_dependencies(conn: &Arc<Mutex<Connection>>, id: CardID) -> StatefulList<CardItem> {
     let thecard = fetch_card(conn, id);
     let dep_ids = &thecard.dependencies;
@@ -174,8 +128,91 @@ pub struct SpekiPaths {
 }
 
 impl SpekiPaths {
-    const DEFAULTCONFIG: &'static str = r#"
+    pub const DEFAULTCONFIG: &'static str = r#"
+# OpenAI key, used by the language model below when it has no api_key of its own
 #gptkey = ""
+
+# language model that fills in the answer with Alt+g, any OpenAI compatible chat endpoint works
+# like ollama (http://localhost:11434/v1) or the llama.cpp server (http://localhost:8080/v1)
+#[llm]
+#endpoint = "https://api.openai.com/v1"
+#model = "gpt-4o-mini"
+#api_key = ""
+# {question} is replaced with the question of the card
+#prompt = "Answer the following flashcard question as briefly as possible, without repeating the question.\n\n{question}"
+# asked with Alt+g in incremental reading to turn the extract into cards, the answer has to
+# contain a json array of objects with a "question" and an "answer"
+#cards_prompt = "Make up to {count} flashcards about the most important facts in the text below. Use question and answer cards, or cloze cards where the question is a sentence from the text with the hidden part replaced by [...] and the answer is the hidden part. Reply with only a json array of objects with a \"question\" and an \"answer\".\n\n{text}"
+#card_count = 5
+#cards_max_tokens = 1000
+# asked with Alt+D to suggest dependencies among the existing cards most alike to the card,
+# the answer has to contain a json object like {"prerequisites": [12, 40], "missing": ["concept"]}
+#dependencies_prompt = "A flashcard asks \"{question}\" with the answer \"{answer}\". Which of the cards below have to be known to understand it, and which concepts it relies on have no card yet? Reply with only a json object with the ids of the cards as \"prerequisites\" and the concepts as \"missing\".\n\n{cards}"
+#candidates = 20
+# seconds to wait for an answer
+#timeout = 30
+#max_tokens = 200
+
+# maximum amount of new cards and reviews per day, both globally and per topic
+#[limits]
+#new_cards = 20
+#reviews = 200
+# show new cards mixed in with the other reviews instead of after them
+#interleave = false
+# how many incremental reading texts to read per day
+#incread = 20
+#[limits.topics.root]
+#new_cards = 10
+
+# order of due cards: "random", "overdue", "strength", "topic" or "interleaved"
+#[review]
+#order = "random"
+#seed = 42
+
+# cards failed this many times are leeches, action is either "tag" or "suspend"
+#[leech]
+#threshold = 8
+#action = "tag"
+
+# cards at least this alike (0 to 1) count as duplicates, adding one asks to confirm first.
+# what importing does with duplicates: "keep", "skip" or "merge" (into the existing card)
+#[duplicates]
+#threshold = 0.85
+#import = "keep"
+
+# how much text around a cloze is kept on the card: "sentence", "paragraph" or "full"
+#[cloze]
+#context = "sentence"
+
+# how images are drawn: "auto", "kitty", "iterm", "sixel", "blocks" (half blocks, works in
+# most terminals) or "none" to only open them in the viewer
+#[images]
+#protocol = "auto"
+#viewer = "xdg-open"
+
+# sound is turned on and off with Alt+m, device is the name of the output device to use
+#[audio]
+#enabled = true
+#volume = 0.2
+#device = ""
+# play the audio of the question when a card comes up and of the answer when it's revealed
+#autoplay_front = true
+#autoplay_back = true
+# replays the audio of the card in review together with Alt
+#replay_key = "a"
+
+# reads cards out loud with a local engine, {output} is the wav file it writes, {voice} the voice
+# and {text} the text, which is written to its stdin when left out. Alt+v reads out a side.
+#[tts]
+#command = "espeak-ng -v {voice} -w {output}"
+#voice = "en"
+# "question", "answer" or "both"
+#sides = "both"
+# read out cards of all topics when reviewing, not only the ones with their own voice
+#autoplay = false
+#[tts.voices]
+#Spanish = "es"
+#German = "de"
         "#;
     pub fn new(home: &PathBuf) -> Self {
         let mut home = home.clone();
@@ -237,6 +274,16 @@ pub fn get_current_unix() -> UnixTime {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
 }
 
+/// Days since unix epoch in local time, so that days change at midnight.
+pub fn unix_to_day(unix: u64) -> u32 {
+    let offset = chrono::Local::now().offset().local_minus_utc() as i64;
+    ((unix as i64 + offset) / 86400) as u32
+}
+
+pub fn get_current_day() -> u32 {
+    unix_to_day(get_current_unix().as_secs())
+}
+
 pub fn new_mod(num: i64) -> u32 {
     let mut val = (num % 510) - 254;
     if val < 0 {
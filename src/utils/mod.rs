@@ -2,13 +2,31 @@ pub mod aliases;
 pub mod ankitemplate;
 pub mod area;
 pub mod card;
+pub mod cloze;
+pub mod duplicates;
 pub mod epub;
+pub mod graphics;
+pub mod image;
 pub mod incread;
 pub mod interval;
+pub mod leech;
 pub mod libextensions;
+pub mod limits;
+pub mod llm;
+pub mod media;
 pub mod misc;
+pub mod occlusion;
+pub mod ordering;
+pub mod pdf;
+pub mod recording;
+pub mod session;
+pub mod split;
 pub mod sql;
 pub mod statelist;
+pub mod textimport;
+pub mod tts;
+pub mod webpage;
+pub mod wiki;
 
 #[derive(Clone, PartialEq)]
 pub struct CardInList {
@@ -0,0 +1,89 @@
+use crate::utils::aliases::*;
+use crate::utils::card::{Card, CardTypeData, FinishedInfo};
+use crate::utils::image::{Overlay, Region};
+use crate::utils::media::import_media;
+use crate::utils::sql::insert::set_occlusion;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::statelist::KeyHandler;
+
+/// The mask that the card asks about.
+pub const TARGET_COLOR: [u8; 3] = [231, 76, 60];
+/// The other masks, hidden as well so they don't give the answer away.
+pub const MASK_COLOR: [u8; 3] = [255, 214, 102];
+/// The mask being drawn in the editor.
+pub const CURSOR_COLOR: [u8; 3] = [52, 152, 219];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mask {
+    pub region: Region,
+    /// What's hidden under the mask, it becomes the answer of the card.
+    pub label: String,
+}
+
+impl KeyHandler for Mask {}
+
+impl fmt::Display for Mask {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// An image with masks over it, where one of the masks is asked about. Every mask of an image
+/// becomes its own card.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Occlusion {
+    pub image: PathBuf,
+    pub masks: Vec<Mask>,
+    pub target: usize,
+}
+
+impl Occlusion {
+    /// All masks hide what's under them, when revealed the target mask is only outlined.
+    pub fn overlays(&self, revealed: bool) -> Vec<Overlay> {
+        self.masks
+            .iter()
+            .enumerate()
+            .map(|(idx, mask)| match (idx == self.target, revealed) {
+                (true, true) => Overlay::Outline(mask.region, TARGET_COLOR),
+                (true, false) => Overlay::Fill(mask.region, TARGET_COLOR),
+                (false, _) => Overlay::Fill(mask.region, MASK_COLOR),
+            })
+            .collect()
+    }
+}
+
+/// Copies the image into the media folder and makes one finished card per mask, returns their
+/// ids.
+pub fn make_occlusion_cards(
+    conn: &Arc<Mutex<Connection>>,
+    media: &Path,
+    image: &Path,
+    masks: Vec<Mask>,
+    question: String,
+    topic: TopicID,
+) -> io::Result<Vec<CardID>> {
+    let image = import_media(media, image)?;
+    let mut ids = vec![];
+    for (target, mask) in masks.iter().enumerate() {
+        let id = Card::new(CardTypeData::Finished(FinishedInfo::default()))
+            .question(question.clone())
+            .answer(mask.label.clone())
+            .topic(topic)
+            .frontimage(Some(image.clone()))
+            .save_card(conn);
+        let occlusion = Occlusion {
+            image: image.clone(),
+            masks: masks.clone(),
+            target,
+        };
+        set_occlusion(conn, id, &occlusion).unwrap();
+        ids.push(id);
+    }
+    Ok(ids)
+}
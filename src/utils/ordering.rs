@@ -0,0 +1,85 @@
+use crate::utils::aliases::*;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use serde_derive::Deserialize;
+use std::collections::BTreeMap;
+
+/// The order in which due cards are shown, set with `order` under `[review]` in the config file.
+#[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewOrder {
+    #[default]
+    Random,
+    Overdue,
+    Strength,
+    Topic,
+    Interleaved,
+}
+
+#[derive(Deserialize, Default, Clone, Debug)]
+pub struct ReviewConfig {
+    #[serde(default)]
+    pub order: ReviewOrder,
+    /// Fixed seed for the random choices in review, mostly useful for testing.
+    pub seed: Option<u64>,
+}
+
+impl ReviewConfig {
+    pub fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReviewItem {
+    pub id: CardID,
+    pub topic: TopicID,
+    pub priority: u32,
+    pub strength: f32,
+    /// Days since the card became due.
+    pub overdue: f32,
+}
+
+impl ReviewItem {
+    pub fn topic_pair(&self) -> (CardID, TopicID) {
+        (self.id, self.topic)
+    }
+}
+
+/// Sorts the items according to the given order, higher priority items always come first.
+pub fn order_items(
+    mut items: Vec<ReviewItem>,
+    order: ReviewOrder,
+    rng: &mut StdRng,
+) -> Vec<ReviewItem> {
+    items.shuffle(rng);
+    match order {
+        ReviewOrder::Random => {}
+        ReviewOrder::Overdue => items.sort_by(|a, b| b.overdue.total_cmp(&a.overdue)),
+        ReviewOrder::Strength => items.sort_by(|a, b| a.strength.total_cmp(&b.strength)),
+        ReviewOrder::Topic => items.sort_by_key(|item| item.topic),
+        ReviewOrder::Interleaved => items = interleave_topics(items),
+    }
+    items.sort_by_key(|item| std::cmp::Reverse(item.priority));
+    items
+}
+
+/// Takes one item from each topic in turn.
+fn interleave_topics(items: Vec<ReviewItem>) -> Vec<ReviewItem> {
+    let mut topics = BTreeMap::<TopicID, Vec<ReviewItem>>::new();
+    for item in items.into_iter().rev() {
+        topics.entry(item.topic).or_default().push(item);
+    }
+
+    let mut interleaved = vec![];
+    while !topics.is_empty() {
+        for queue in topics.values_mut() {
+            interleaved.push(queue.pop().unwrap());
+        }
+        topics.retain(|_, queue| !queue.is_empty());
+    }
+    interleaved
+}
@@ -0,0 +1,113 @@
+use lopdf::{Document, Object};
+use regex::Regex;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::utils::epub::Book;
+
+/// Loads a pdf with one section per outline entry if the pdf has an outline and one per page
+/// otherwise. Every page starts with a `[page N]` marker line.
+pub fn load_pdf(path: &Path) -> Book {
+    let doc = match Document::load(path) {
+        Ok(doc) => doc,
+        Err(e) => return Book::failed(format!("Couldn't read {}: {}", path.display(), e), path),
+    };
+    let author = pdf_info(&doc, b"Author").unwrap_or_default();
+    let title = pdf_info(&doc, b"Title").unwrap_or_else(|| {
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("")
+            .to_string()
+    });
+    let desc = format!("{}\n\n{}", title, path.display());
+    Book::from_sections(desc, pdf_sections(&doc), path, author)
+}
+
+pub fn pdf_sections(doc: &Document) -> Vec<String> {
+    let pagecount = doc.get_pages().len() as u32;
+    let page_text = |page: u32| -> String {
+        let text = doc.extract_text(&[page]).unwrap_or_default();
+        format!("{}\n{}", page_marker(page), text.trim())
+    };
+
+    let mut outline: Vec<(String, u32)> = match doc.get_toc() {
+        Ok(toc) => {
+            let toplevel = toc.toc.iter().map(|entry| entry.level).min().unwrap_or(0);
+            toc.toc
+                .into_iter()
+                .filter(|entry| entry.level == toplevel)
+                .map(|entry| (entry.title, entry.page as u32))
+                .collect()
+        }
+        Err(_) => vec![],
+    };
+    outline.sort_by_key(|(_, page)| *page);
+    outline.dedup_by_key(|(_, page)| *page);
+
+    if outline.is_empty() {
+        return (1..=pagecount)
+            .map(page_text)
+            .filter(|text| text.lines().count() > 1)
+            .collect();
+    }
+
+    if outline[0].1 > 1 {
+        outline.insert(0, ("Front matter".to_string(), 1));
+    }
+    let mut sections = vec![];
+    for (idx, (title, start)) in outline.iter().enumerate() {
+        let end = match outline.get(idx + 1) {
+            Some((_, next)) => next - 1,
+            None => pagecount,
+        };
+        let pages: Vec<String> = (*start..=end).map(page_text).collect();
+        sections.push(format!("{}\n\n{}", title.trim(), pages.join("\n")));
+    }
+    sections
+}
+
+fn pdf_info(doc: &Document, key: &[u8]) -> Option<String> {
+    let info = doc.trailer.get(b"Info").ok()?.as_reference().ok()?;
+    let info = doc.get_object(info).ok()?.as_dict().ok()?;
+    match info.get(key).ok()? {
+        Object::String(bytes, _) if !bytes.is_empty() => Some(decode_pdf_string(bytes)),
+        _ => None,
+    }
+}
+
+fn decode_pdf_string(bytes: &[u8]) -> String {
+    if bytes.starts_with(&[0xfe, 0xff]) {
+        let utf16: Vec<u16> = bytes[2..]
+            .chunks(2)
+            .filter(|pair| pair.len() == 2)
+            .map(|pair| (pair[0] as u16) << 8 | pair[1] as u16)
+            .collect();
+        String::from_utf16_lossy(&utf16)
+    } else {
+        String::from_utf8_lossy(bytes).to_string()
+    }
+}
+
+pub fn page_marker(page: u32) -> String {
+    format!("[page {}]", page)
+}
+
+/// The first page referenced in the text.
+pub fn first_page(text: &str) -> Option<u32> {
+    let rows: Vec<String> = text.lines().map(String::from).collect();
+    let row = rows
+        .iter()
+        .position(|line| line.trim().starts_with("[page "))?;
+    page_at_row(&rows, row)
+}
+
+/// The page that the given row belongs to, found by the last page marker at or above it.
+pub fn page_at_row(rows: &[String], row: usize) -> Option<u32> {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    let marker = MARKER.get_or_init(|| Regex::new(r"^\[page (\d+)\]$").unwrap());
+    rows.iter()
+        .take(row + 1)
+        .rev()
+        .find_map(|line| marker.captures(line.trim()))
+        .and_then(|caps| caps[1].parse().ok())
+}
@@ -0,0 +1,175 @@
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub type RecordResult<T> = Result<T, Box<dyn Error>>;
+
+/// Interleaved 16 bit samples.
+pub type SampleBuffer = Arc<Mutex<Vec<i16>>>;
+
+/// Something that audio can be recorded from.
+pub trait InputDevice {
+    /// Starts appending samples to the buffer until stopped.
+    fn start(&mut self, buffer: SampleBuffer) -> RecordResult<()>;
+    fn stop(&mut self);
+    fn channels(&self) -> u16;
+    fn sample_rate(&self) -> u32;
+}
+
+/// The default input device of the system, usually the microphone.
+pub struct Microphone {
+    device: cpal::Device,
+    config: cpal::SupportedStreamConfig,
+    stream: Option<cpal::Stream>,
+}
+
+impl Microphone {
+    pub fn new() -> RecordResult<Self> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or("no microphone found")?;
+        let config = device.default_input_config()?;
+        Ok(Self {
+            device,
+            config,
+            stream: None,
+        })
+    }
+}
+
+impl InputDevice for Microphone {
+    fn start(&mut self, buffer: SampleBuffer) -> RecordResult<()> {
+        let config: cpal::StreamConfig = self.config.clone().into();
+        let on_error = |_| {};
+        let stream = match self.config.sample_format() {
+            cpal::SampleFormat::I16 => self.device.build_input_stream(
+                &config,
+                move |data: &[i16], _: &_| buffer.lock().unwrap().extend_from_slice(data),
+                on_error,
+            )?,
+            cpal::SampleFormat::U16 => self.device.build_input_stream(
+                &config,
+                move |data: &[u16], _: &_| {
+                    let samples = data.iter().map(|&sample| (sample as i32 - 32768) as i16);
+                    buffer.lock().unwrap().extend(samples);
+                },
+                on_error,
+            )?,
+            cpal::SampleFormat::F32 => self.device.build_input_stream(
+                &config,
+                move |data: &[f32], _: &_| {
+                    let samples = data
+                        .iter()
+                        .map(|&sample| (sample.clamp(-1., 1.) * i16::MAX as f32) as i16);
+                    buffer.lock().unwrap().extend(samples);
+                },
+                on_error,
+            )?,
+        };
+        stream.play()?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        self.stream = None;
+    }
+
+    fn channels(&self) -> u16 {
+        self.config.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.config.sample_rate().0
+    }
+}
+
+/// Plays back a wav file as if it was spoken into a microphone, all of it at once.
+pub struct FileInput {
+    path: PathBuf,
+    spec: hound::WavSpec,
+}
+
+impl FileInput {
+    pub fn new(path: PathBuf) -> RecordResult<Self> {
+        let spec = hound::WavReader::open(&path)?.spec();
+        Ok(Self { path, spec })
+    }
+}
+
+impl InputDevice for FileInput {
+    fn start(&mut self, buffer: SampleBuffer) -> RecordResult<()> {
+        let mut reader = hound::WavReader::open(&self.path)?;
+        let samples = reader.samples::<i16>().collect::<Result<Vec<i16>, _>>()?;
+        buffer.lock().unwrap().extend(samples);
+        Ok(())
+    }
+
+    fn stop(&mut self) {}
+
+    fn channels(&self) -> u16 {
+        self.spec.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.spec.sample_rate
+    }
+}
+
+pub struct Recorder {
+    device: Box<dyn InputDevice>,
+    buffer: SampleBuffer,
+    recording: bool,
+}
+
+impl Recorder {
+    pub fn new(device: Box<dyn InputDevice>) -> Self {
+        Self {
+            device,
+            buffer: Arc::default(),
+            recording: false,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn start(&mut self) -> RecordResult<()> {
+        self.buffer.lock().unwrap().clear();
+        self.device.start(self.buffer.clone())?;
+        self.recording = true;
+        Ok(())
+    }
+
+    /// Stops recording and throws away what was recorded.
+    pub fn cancel(&mut self) {
+        if self.recording {
+            self.device.stop();
+            self.recording = false;
+        }
+        self.buffer.lock().unwrap().clear();
+    }
+
+    /// Stops recording and saves what was recorded as a wav file.
+    pub fn stop(&mut self, path: &Path) -> RecordResult<()> {
+        self.device.stop();
+        self.recording = false;
+        let spec = hound::WavSpec {
+            channels: self.device.channels(),
+            sample_rate: self.device.sample_rate(),
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut writer = hound::WavWriter::create(path, spec)?;
+        for sample in std::mem::take(&mut *self.buffer.lock().unwrap()) {
+            writer.write_sample(sample)?;
+        }
+        writer.finalize()?;
+        Ok(())
+    }
+}
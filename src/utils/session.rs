@@ -0,0 +1,14 @@
+/// The counts of a finished review session that are saved to the sessions table.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct SessionStats {
+    pub start: u64,
+    pub end: u64,
+    pub reviews: u32,
+    pub pending: u32,
+    pub unfinished: u32,
+    pub incread: u32,
+    pub grades: [u32; 4],
+    pub resolved: u32,
+    pub extracts: u32,
+    pub clozes: u32,
+}
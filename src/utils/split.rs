@@ -0,0 +1,148 @@
+use crate::utils::aliases::*;
+use crate::utils::card::{Card, RecallGrade, Review};
+use crate::utils::sql::delete::{remove_card, remove_card_data};
+use crate::utils::sql::fetch::cards::fetch_card;
+use crate::utils::sql::in_transaction;
+use crate::utils::sql::insert::{copy_reviews, update_both};
+use crate::utils::statelist::KeyHandler;
+use crate::{app::AppData, MyKey};
+use chrono::TimeZone;
+use rusqlite::{Connection, Result};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// One of the cards an overloaded card is split into.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SplitPart {
+    pub question: String,
+    pub answer: String,
+    /// The dates of the reviews of the original card that this card gets a copy of.
+    pub reviews: Vec<UnixTime>,
+}
+
+impl fmt::Display for SplitPart {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.question.trim().is_empty() {
+            write!(f, "(empty)")
+        } else {
+            write!(f, "{}", self.question.replace('\n', " "))
+        }
+    }
+}
+
+impl KeyHandler for SplitPart {}
+
+/// A review of the card being split, and which of the new cards it goes to.
+pub struct SplitReview {
+    pub review: Review,
+    pub parts: Vec<bool>,
+}
+
+impl SplitReview {
+    pub fn new(review: Review, parts: usize) -> Self {
+        Self {
+            review,
+            parts: vec![true; parts],
+        }
+    }
+
+    fn toggle(&mut self, part: usize) {
+        if let Some(included) = self.parts.get_mut(part) {
+            *included ^= true;
+        }
+    }
+}
+
+impl fmt::Display for SplitReview {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let date = match chrono::Local
+            .timestamp_opt(self.review.date.as_secs() as i64, 0)
+            .single()
+        {
+            Some(date) => date.format("%Y-%m-%d %H:%M").to_string(),
+            None => "?".to_string(),
+        };
+        let grade = match self.review.grade {
+            RecallGrade::None => "none",
+            RecallGrade::Failed => "failed",
+            RecallGrade::Decent => "decent",
+            RecallGrade::Easy => "easy",
+        };
+        let parts: Vec<String> = self
+            .parts
+            .iter()
+            .enumerate()
+            .filter(|(_, included)| **included)
+            .map(|(idx, _)| (idx + 1).to_string())
+            .collect();
+        let parts = if parts.is_empty() {
+            "-".to_string()
+        } else {
+            parts.join(", ")
+        };
+        write!(f, "{} {:<6} → {}", date, grade, parts)
+    }
+}
+
+impl KeyHandler for SplitReview {
+    fn keyhandler(&mut self, _appdata: &AppData, key: MyKey) -> bool {
+        match key {
+            MyKey::Char(c @ '1'..='9') => {
+                self.toggle(c as usize - '1' as usize);
+                true
+            }
+            MyKey::Char('a') => {
+                let all = self.parts.iter().all(|included| *included);
+                self.parts.iter_mut().for_each(|included| *included = !all);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Makes a new card for every part, with the topic, source and dependencies of the original
+/// and copies of the reviews that were given to it. With `summary` the original stays as a
+/// card depending on the new ones, otherwise it's deleted and its dependents depend on the new
+/// cards instead. Nothing changes if any of it fails, including the card functions that panic on
+/// a database error.
+pub fn split_card(
+    conn: &Arc<Mutex<Connection>>,
+    id: CardID,
+    parts: &[SplitPart],
+    summary: bool,
+) -> Result<Vec<CardID>> {
+    in_transaction(conn, || {
+        let card = fetch_card(conn, id);
+        let mut ids = vec![];
+        for part in parts {
+            let mut new = Card::new(card.cardtype.clone())
+                .question(part.question.trim().to_string())
+                .answer(part.answer.trim().to_string())
+                .topic(card.topic)
+                .source(card.source)
+                .page(card.page)
+                .dependencies(card.dependencies.clone());
+            if !summary {
+                new = new.dependents(card.dependents.clone());
+            }
+            let new = new.save_card(conn);
+            copy_reviews(conn, id, new, &part.reviews)?;
+            ids.push(new);
+        }
+
+        if summary {
+            for new in &ids {
+                update_both(conn, id, *new)?;
+            }
+            Card::check_resolved(id, conn);
+        } else {
+            remove_card_data(conn, id)?;
+            remove_card(conn, id)?;
+            for dependent in card.dependents {
+                Card::check_resolved(dependent, conn);
+            }
+        }
+        Ok(ids)
+    })
+}
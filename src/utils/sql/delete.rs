@@ -10,6 +10,28 @@ pub fn delete_topic(conn: &Arc<Mutex<Connection>>, id: TopicID) -> Result<()> {
     Ok(())
 }
 
+pub fn delete_bookmark(conn: &Arc<Mutex<Connection>>, id: u32) -> Result<()> {
+    conn.lock()
+        .unwrap()
+        .prepare("delete from bookmarks where id = ?")?
+        .execute(params![id])?;
+    Ok(())
+}
+
+pub fn remove_read_section(
+    conn: &Arc<Mutex<Connection>>,
+    id: IncID,
+    range: (usize, usize),
+) -> Result<()> {
+    conn.lock()
+        .unwrap()
+        .prepare(
+            "delete from read_sections where incread = ? and offset_start = ? and offset_end = ?",
+        )?
+        .execute(params![id, range.0, range.1])?;
+    Ok(())
+}
+
 pub fn remove_unfinished(conn: &Arc<Mutex<Connection>>, id: CardID) -> Result<()> {
     conn.lock()
         .unwrap()
@@ -26,6 +48,17 @@ pub fn remove_pending(conn: &Arc<Mutex<Connection>>, id: CardID) -> Result<()> {
     Ok(())
 }
 
+/// Deletes the review history of a card and its links to other cards.
+pub fn remove_card_data(conn: &Arc<Mutex<Connection>>, id: CardID) -> Result<()> {
+    let guard = conn.lock().unwrap();
+    guard.execute("delete from revlog where cid = ?1", params![id])?;
+    guard.execute(
+        "delete from dependencies where dependent = ?1 or dependency = ?1",
+        params![id],
+    )?;
+    Ok(())
+}
+
 pub fn remove_card(conn: &Arc<Mutex<Connection>>, id: CardID) -> Result<()> {
     conn.lock()
         .unwrap()
@@ -43,5 +76,13 @@ pub fn remove_card(conn: &Arc<Mutex<Connection>>, id: CardID) -> Result<()> {
         .unwrap()
         .prepare("delete from finished_cards where id = ?")?
         .execute(params![id])?;
+    conn.lock()
+        .unwrap()
+        .prepare("delete from leeches where id = ?")?
+        .execute(params![id])?;
+    conn.lock()
+        .unwrap()
+        .prepare("delete from occlusions where card = ?")?
+        .execute(params![id])?;
     Ok(())
 }
@@ -107,6 +107,12 @@ pub fn row2card(row: &Row) -> Card {
         history: Vec::new(),
         topic: row.get(10).unwrap(),
         source: row.get(11).unwrap(),
+        priority: row.get(12).unwrap(),
+        page: row.get(13).unwrap(),
+        source_range: match (row.get(14).unwrap(), row.get(15).unwrap()) {
+            (Some(start), Some(end)) => Some((start, end)),
+            _ => None,
+        },
     }
 }
 
@@ -210,6 +216,25 @@ pub fn get_pending(conn: Conn) -> Vec<PendingItem> {
     .unwrap()
 }
 
+/// Amount of times the card has been rated as not recalled.
+pub fn get_lapses(conn: Conn, id: CardID) -> u32 {
+    fetch_item(
+        conn,
+        format!("SELECT COUNT(*) FROM revlog WHERE cid={} AND grade < 2", id),
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+pub fn is_leech(conn: Conn, id: CardID) -> bool {
+    fetch_item(
+        conn,
+        format!("SELECT EXISTS (SELECT 1 FROM leeches WHERE id={})", id),
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
 pub fn get_skipduration(conn: Conn, id: CardID) -> u32 {
     fetch_item(
         conn,
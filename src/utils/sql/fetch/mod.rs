@@ -1,6 +1,7 @@
 use crate::utils::aliases::*;
 use crate::utils::card::{Card, CardType};
-use crate::utils::misc::get_current_unix;
+use crate::utils::misc::{get_current_unix, unix_to_day};
+use crate::utils::ordering::ReviewItem;
 //, Topic, Review}
 use crate::widgets::topics::Topic;
 use rusqlite::{Connection, Result, Row};
@@ -27,6 +28,8 @@ enum CardFilter {
     MinSkipDaysPassed(f32),
     MaxSkipDaysPassed(f32),
     Source(u32),
+    Leech(bool),
+    NewerThan(CardID),
     DueUnfinished,
 }
 
@@ -80,6 +83,9 @@ impl fmt::Display for CardFilter {
                 format!("skiptime > {}", since)
             }
             Source(val) => format!("source = {}", val),
+            Leech(true) => "cards.id IN (SELECT id FROM leeches)".to_string(),
+            Leech(false) => "cards.id NOT IN (SELECT id FROM leeches)".to_string(),
+            NewerThan(val) => format!("cards.id > {}", val),
             MaxPosition(val) => format!("position < {}", val),
             MinPosition(val) => format!("position > {}", val),
             Minstability(val) => format!("stability > {}", val),
@@ -201,6 +207,15 @@ impl CardQuery {
         self.filters.push(CardFilter::Source(val));
         self
     }
+    pub fn leech(mut self, val: bool) -> Self {
+        self.filters.push(CardFilter::Leech(val));
+        self
+    }
+    /// The cards that were added after the given one.
+    pub fn newer_than(mut self, val: CardID) -> Self {
+        self.filters.push(CardFilter::NewerThan(val));
+        self
+    }
     pub fn unfinished_due(mut self) -> Self {
         self.filters.push(CardFilter::DueUnfinished);
         self
@@ -230,6 +245,41 @@ impl CardQuery {
         let f = |row: &Row| row.get(0).unwrap();
         self.fetch_generic(conn, f)
     }
+    pub fn fetch_card_topics(self, conn: &Arc<Mutex<Connection>>) -> Vec<(CardID, TopicID)> {
+        let f = |row: &Row| (row.get(0).unwrap(), row.get(10).unwrap());
+        self.fetch_generic(conn, f)
+    }
+    pub fn fetch_review_items(self, conn: &Arc<Mutex<Connection>>) -> Vec<ReviewItem> {
+        let now = get_current_unix().as_secs_f32();
+        let f = |row: &Row| {
+            let strength: Option<f32> = row.get("strength").unwrap();
+            let stability: Option<f32> = row.get("stability").unwrap();
+            let skiptime: Option<u32> = row.get("skiptime").unwrap();
+            let skipduration: Option<u32> = row.get("skipduration").unwrap();
+            let (strength, overdue) = match (strength, stability, skiptime, skipduration) {
+                (Some(strength), Some(stability), _, _) => {
+                    let passed = 0.9 * stability / strength;
+                    (strength, passed - stability)
+                }
+                (_, _, Some(skiptime), Some(skipduration)) => {
+                    let passed = (now - skiptime as f32) / 86400.;
+                    (
+                        0.9 * skipduration as f32 / passed,
+                        passed - skipduration as f32,
+                    )
+                }
+                _ => (0., 0.),
+            };
+            ReviewItem {
+                id: row.get(0).unwrap(),
+                topic: row.get(10).unwrap(),
+                priority: row.get(12).unwrap(),
+                strength,
+                overdue,
+            }
+        };
+        self.fetch_generic(conn, f)
+    }
     pub fn fetch_card(self, conn: &Arc<Mutex<Connection>>) -> Vec<Card> {
         //let f = |row: &Row| { row2card(row)};
         let mut cards = self.fetch_generic(conn, row2card);
@@ -289,6 +339,62 @@ pub fn get_topics(conn: &Arc<Mutex<Connection>>) -> PrettyResult<Vec<Topic>> {
     Ok(vecoftops)
 }
 
+use crate::utils::limits::DailyCount;
+use std::collections::HashMap;
+
+pub fn get_daily_counts(conn: Conn, day: u32) -> HashMap<TopicID, DailyCount> {
+    fetch_items(
+        conn,
+        format!(
+            "SELECT topic, new_cards, reviews, incread FROM daily_counts WHERE day = {}",
+            day
+        ),
+        |row| {
+            (
+                row.get(0).unwrap(),
+                DailyCount {
+                    new_cards: row.get(1).unwrap(),
+                    reviews: row.get(2).unwrap(),
+                    incread: row.get(3).unwrap(),
+                },
+            )
+        },
+    )
+    .unwrap()
+    .into_iter()
+    .collect()
+}
+
+pub fn get_highest_card_id(conn: Conn) -> CardID {
+    fetch_item(
+        conn,
+        "SELECT IFNULL(MAX(id), 0) FROM cards".to_string(),
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+pub fn get_highest_inc_id(conn: Conn) -> IncID {
+    fetch_item(
+        conn,
+        "SELECT IFNULL(MAX(id), 0) FROM incread".to_string(),
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+/// All the days with at least one review, in ascending order.
+pub fn get_review_days(conn: Conn) -> Vec<u32> {
+    let mut days: Vec<u32> = fetch_items(
+        conn,
+        "SELECT unix FROM revlog ORDER BY unix ASC".to_string(),
+        |row| unix_to_day(row.get(0).unwrap()),
+    )
+    .unwrap();
+    days.dedup();
+    days
+}
+
 use crate::utils::incread::{IncRead, IncStatus};
 use crate::widgets::textinput::Field;
 
@@ -307,7 +413,9 @@ struct IncTemp {
 pub fn get_incread(conn: &Arc<Mutex<Connection>>, id: u32) -> IncRead {
     let extracts = load_extracts(conn, id);
     let clozes = CardQuery::default().source(id).fetch_carditems(conn);
-    conn.lock()
+    let bookmarks = load_bookmarks(conn, id);
+    let mut incread = conn
+        .lock()
         .unwrap()
         .query_row("SELECT * FROM incread WHERE id = ?", [id], |row| {
             Ok(IncRead {
@@ -320,14 +428,25 @@ pub fn get_incread(conn: &Arc<Mutex<Connection>>, id: u32) -> IncRead {
                     row.get(8).unwrap(),
                 ),
                 status: IncStatus::new_from_row(row),
+                priority: row.get(9).unwrap(),
+                page: row.get(10).unwrap(),
+                last_read: row
+                    .get::<usize, Option<u64>>(14)
+                    .unwrap()
+                    .map(std::time::Duration::from_secs),
                 extracts,
                 clozes,
+                bookmarks,
             })
         })
-        .unwrap()
+        .unwrap();
+    incread.source.highlights = load_extracted_ranges(conn, id);
+    incread.source.dimmed = load_read_sections(conn, id);
+    incread
 }
 
-use crate::utils::incread::IncListItem;
+use crate::utils::incread::{inc_title, Bookmark, IncListItem, IncQueueItem, Provenance};
+use crate::utils::occlusion::Occlusion;
 
 pub fn load_inc_items(
     conn: &Arc<Mutex<Connection>>,
@@ -342,6 +461,7 @@ pub fn load_inc_items(
             incvec.push(IncListItem {
                 text: row.get(3)?,
                 id: row.get(0)?,
+                progress: Some(row.get(13)?),
             });
             Ok(())
         })?
@@ -356,21 +476,141 @@ pub fn load_extracts(conn: &Arc<Mutex<Connection>>, parent: IncID) -> Vec<IncLis
         |row| IncListItem {
             text: row.get(3).unwrap(),
             id: row.get(0).unwrap(),
+            progress: None,
         },
     )
     .unwrap()
 }
 
-pub fn load_active_inc(conn: &Arc<Mutex<Connection>>) -> Vec<IncID> {
+/// Character ranges of the parent text that extracts and clozes were taken from.
+pub fn load_extracted_ranges(conn: &Arc<Mutex<Connection>>, parent: IncID) -> Vec<(usize, usize)> {
+    fetch_items(
+        conn,
+        format!(
+            "SELECT offset_start, offset_end FROM incread WHERE parent = {0} AND offset_start IS NOT NULL
+            UNION ALL SELECT offset_start, offset_end FROM cards WHERE source = {0} AND offset_start IS NOT NULL",
+            parent
+        ),
+        |row| (row.get(0).unwrap(), row.get(1).unwrap()),
+    )
+    .unwrap()
+}
+
+/// Character ranges of the text that have been marked as read.
+pub fn load_read_sections(conn: &Arc<Mutex<Connection>>, id: IncID) -> Vec<(usize, usize)> {
+    fetch_items(
+        conn,
+        format!(
+            "SELECT offset_start, offset_end FROM read_sections WHERE incread = {}",
+            id
+        ),
+        |row| (row.get(0).unwrap(), row.get(1).unwrap()),
+    )
+    .unwrap()
+}
+
+/// The bookmarks of the text in the order they appear in it.
+pub fn load_bookmarks(conn: &Arc<Mutex<Connection>>, id: IncID) -> Vec<Bookmark> {
+    fetch_items(
+        conn,
+        format!(
+            "SELECT id, name, offset FROM bookmarks WHERE incread = {} ORDER BY offset",
+            id
+        ),
+        |row| Bookmark {
+            id: row.get(0).unwrap(),
+            name: row.get(1).unwrap(),
+            offset: row.get(2).unwrap(),
+        },
+    )
+    .unwrap()
+}
+
+/// The masked image of an image occlusion card.
+pub fn get_occlusion(conn: &Arc<Mutex<Connection>>, card: CardID) -> Option<Occlusion> {
+    fetch_item(
+        conn,
+        format!(
+            "SELECT image, masks, target FROM occlusions WHERE card = {}",
+            card
+        ),
+        |row| {
+            let masks: String = row.get(1)?;
+            Ok(Occlusion {
+                image: std::path::PathBuf::from(row.get::<usize, String>(0)?),
+                masks: serde_json::from_str(&masks).unwrap_or_default(),
+                target: row.get(2)?,
+            })
+        },
+    )
+    .ok()
+}
+
+/// The provenance of the text, inherited from the source it was extracted from.
+pub fn get_provenance(conn: &Arc<Mutex<Connection>>, id: IncID) -> Option<Provenance> {
+    let mut id = id;
+    while id != 0 {
+        let provenance = fetch_item(
+            conn,
+            format!(
+                "SELECT origin, title, author FROM provenance WHERE incread = {}",
+                id
+            ),
+            |row| {
+                Ok(Provenance {
+                    origin: row.get(0)?,
+                    title: row.get(1)?,
+                    author: row.get(2)?,
+                })
+            },
+        );
+        if provenance.is_ok() {
+            return provenance.ok();
+        }
+        id = fetch_item(
+            conn,
+            format!("SELECT parent FROM incread WHERE id = {}", id),
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    }
+    None
+}
+
+/// The due texts along with their topics, highest priority and most overdue first.
+pub fn load_active_inc(conn: &Arc<Mutex<Connection>>) -> Vec<(IncID, TopicID)> {
     let current_time = get_current_unix();
 
     fetch_items(
         conn, 
-        format!("SELECT id FROM incread where active = 1 and (({} - skiptime) > (skipduration * 86400))", current_time.as_secs()), 
-        |row| row.get::<usize, IncID>(0).unwrap()
+        format!("SELECT id, topic FROM incread where active = 1 and (({} - skiptime) > (skipduration * 86400)) ORDER BY priority DESC, (skiptime + skipduration * 86400) ASC", current_time.as_secs()), 
+        |row| (row.get(0).unwrap(), row.get(1).unwrap())
         ).unwrap()
 }
 
+/// All active texts in the order they'll be reviewed, due texts first.
+pub fn load_inc_queue(conn: &Arc<Mutex<Connection>>) -> Vec<IncQueueItem> {
+    let now = get_current_unix().as_secs();
+    fetch_items(
+        conn,
+        format!(
+            "SELECT id, source, priority, (skiptime + skipduration * 86400) AS due FROM incread WHERE active = 1 
+            ORDER BY due <= {now} DESC, CASE WHEN due <= {now} THEN priority ELSE 0 END DESC, due ASC",
+            now = now
+        ),
+        |row| {
+            let due: i64 = row.get(3).unwrap();
+            IncQueueItem {
+                id: row.get(0).unwrap(),
+                text: row.get(1).unwrap(),
+                priority: row.get(2).unwrap(),
+                days_left: (due - now as i64).max(0) / 86400,
+            }
+        },
+    )
+    .unwrap()
+}
+
 use crate::utils::card::CardItem;
 
 use self::cards::{fill_card_vec, row2card};
@@ -387,8 +627,8 @@ pub fn load_inc_title(
     incid: IncID,
     titlelen: u16,
 ) -> Result<String> {
-    let mut source = load_inc_text(conn, incid).unwrap();
-    source.truncate(titlelen.into());
+    let source = load_inc_text(conn, incid).unwrap();
+    let mut source: String = inc_title(&source).chars().take(titlelen.into()).collect();
     if source.len() < 5 {
         source = "Empty Source".to_string();
     }
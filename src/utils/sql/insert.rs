@@ -1,6 +1,10 @@
 use crate::utils::aliases::*;
 use crate::utils::card::{Card, CardType, CardTypeData, Review};
+use crate::utils::incread::Provenance;
+use crate::utils::limits::DailyCount;
 use crate::utils::misc::get_current_unix;
+use crate::utils::occlusion::Occlusion;
+use crate::utils::session::SessionStats;
 //, Status, Topic, Review}
 use crate::utils::sql::update::set_cardtype;
 use rusqlite::{params, Connection, Result};
@@ -42,9 +46,13 @@ pub fn save_card(conn: &Arc<Mutex<Connection>>, card: Card) -> CardID {
             suspended, 
             resolved, 
             topic, 
-            source
+            source,
+            priority,
+            page,
+            offset_start,
+            offset_end
             ) 
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
             params![
                 card.question,
                 card.answer,
@@ -57,6 +65,10 @@ pub fn save_card(conn: &Arc<Mutex<Connection>>, card: Card) -> CardID {
                 card.resolved,
                 card.topic,
                 card.source,
+                card.priority,
+                card.page,
+                card.source_range.map(|range| range.0),
+                card.source_range.map(|range| range.1),
             ],
         )
         .unwrap();
@@ -118,6 +130,75 @@ pub fn revlog_new(conn: &Arc<Mutex<Connection>>, card_id: u32, review: &Review)
     Ok(())
 }
 
+/// Gives another card a copy of the reviews of a card that were made at the given dates.
+pub fn copy_reviews(
+    conn: &Arc<Mutex<Connection>>,
+    from: CardID,
+    to: CardID,
+    dates: &[UnixTime],
+) -> Result<()> {
+    let guard = conn.lock().unwrap();
+    for date in dates {
+        guard.execute(
+            "INSERT INTO revlog (unix, cid, grade, qtime, atime)
+                SELECT unix, ?1, grade, qtime, atime FROM revlog WHERE cid = ?2 AND unix = ?3",
+            params![to, from, date.as_secs()],
+        )?;
+    }
+    Ok(())
+}
+
+pub fn save_session(conn: &Arc<Mutex<Connection>>, session: &SessionStats) -> Result<()> {
+    conn.lock().unwrap().execute(
+        "INSERT INTO sessions (start, end, reviews, pending, unfinished, incread, grade_none, grade_failed, grade_decent, grade_easy, resolved, extracts, clozes) 
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            session.start,
+            session.end,
+            session.reviews,
+            session.pending,
+            session.unfinished,
+            session.incread,
+            session.grades[0],
+            session.grades[1],
+            session.grades[2],
+            session.grades[3],
+            session.resolved,
+            session.extracts,
+            session.clozes,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn new_leech(conn: &Arc<Mutex<Connection>>, id: CardID) -> Result<()> {
+    conn.lock()
+        .unwrap()
+        .execute("INSERT INTO leeches (id) VALUES (?1)", params![id])?;
+    Ok(())
+}
+
+/// Adds to the amount of new cards, reviews and texts read on a given day in a given topic.
+pub fn bump_daily_count(
+    conn: &Arc<Mutex<Connection>>,
+    day: u32,
+    topic: TopicID,
+    count: DailyCount,
+) -> Result<()> {
+    let conn = conn.lock().unwrap();
+    let updated = conn.execute(
+        "UPDATE daily_counts SET new_cards = new_cards + ?3, reviews = reviews + ?4, incread = incread + ?5 WHERE day = ?1 AND topic = ?2",
+        params![day, topic, count.new_cards, count.reviews, count.incread],
+    )?;
+    if updated == 0 {
+        conn.execute(
+            "INSERT INTO daily_counts (day, topic, new_cards, reviews, incread) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![day, topic, count.new_cards, count.reviews, count.incread],
+        )?;
+    }
+    Ok(())
+}
+
 pub fn new_topic(conn: &Arc<Mutex<Connection>>, name: String, parent: u32, pos: u32) -> Result<()> {
     conn.lock().unwrap().execute(
         "INSERT INTO topics (name, parent, relpos) VALUES (?1, ?2, ?3)",
@@ -141,6 +222,62 @@ pub fn new_incread(
     conn.lock().unwrap().last_insert_rowid() as TopicID
 }
 
+pub fn set_provenance(
+    conn: &Arc<Mutex<Connection>>,
+    id: IncID,
+    provenance: &Provenance,
+) -> Result<()> {
+    conn.lock().unwrap().execute(
+        "INSERT OR REPLACE INTO provenance (incread, origin, title, author) VALUES (?1, ?2, ?3, ?4)",
+        params![id, provenance.origin, provenance.title, provenance.author],
+    )?;
+    Ok(())
+}
+
+/// The masks are stored as json.
+pub fn set_occlusion(
+    conn: &Arc<Mutex<Connection>>,
+    card: CardID,
+    occlusion: &Occlusion,
+) -> Result<()> {
+    let masks = serde_json::to_string(&occlusion.masks).unwrap();
+    conn.lock().unwrap().execute(
+        "INSERT OR REPLACE INTO occlusions (card, image, masks, target) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            card,
+            occlusion.image.to_string_lossy(),
+            masks,
+            occlusion.target
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn new_bookmark(
+    conn: &Arc<Mutex<Connection>>,
+    id: IncID,
+    name: &str,
+    offset: usize,
+) -> Result<()> {
+    conn.lock().unwrap().execute(
+        "INSERT INTO bookmarks (incread, name, offset) VALUES (?1, ?2, ?3)",
+        params![id, name, offset],
+    )?;
+    Ok(())
+}
+
+pub fn add_read_section(
+    conn: &Arc<Mutex<Connection>>,
+    id: IncID,
+    range: (usize, usize),
+) -> Result<()> {
+    conn.lock().unwrap().execute(
+        "INSERT INTO read_sections (incread, offset_start, offset_end) VALUES (?1, ?2, ?3)",
+        params![id, range.0, range.1],
+    )?;
+    Ok(())
+}
+
 pub fn new_finished(conn: &Arc<Mutex<Connection>>, id: CardID) -> Result<()> {
     conn.lock().unwrap().execute(
         "INSERT INTO finished_cards (id, strength, stability) VALUES (?1, ?2, ?3)",
@@ -8,8 +8,9 @@ use rusqlite::{Connection, Result};
 
 use std::{
     fmt::Display,
+    panic::{self, AssertUnwindSafe},
     path::PathBuf,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, PoisonError},
 };
 
 use self::insert::new_incread;
@@ -37,11 +38,19 @@ pub fn init_db(dbpath: &PathBuf) -> Result<bool> {
             suspended    bool not null,
             resolved     bool not null,
             topic        integer not null,
-            source       integer not null
+            source       integer not null,
+            priority     integer not null default 0,
+            page         integer,
+            offset_start integer,
+            offset_end   integer
             
     )",
         [],
     )?;
+    add_column(&conn, "cards", "priority", "integer not null default 0")?;
+    add_column(&conn, "cards", "page", "integer")?;
+    add_column(&conn, "cards", "offset_start", "integer")?;
+    add_column(&conn, "cards", "offset_end", "integer")?;
 
     conn.execute(
         "create table if not exists finished_cards (
@@ -109,11 +118,105 @@ pub fn init_db(dbpath: &PathBuf) -> Result<bool> {
             skiptime integer,
             skipduration integer,
             row integer,
-            column integer
+            column integer,
+            priority integer not null default 0,
+            page integer,
+            offset_start integer,
+            offset_end integer,
+            progress real not null default 0,
+            last_read integer
 
     )",
         [],
     )?;
+    add_column(&conn, "incread", "priority", "integer not null default 0")?;
+    add_column(&conn, "incread", "page", "integer")?;
+    add_column(&conn, "incread", "offset_start", "integer")?;
+    add_column(&conn, "incread", "offset_end", "integer")?;
+    add_column(&conn, "incread", "progress", "real not null default 0")?;
+    add_column(&conn, "incread", "last_read", "integer")?;
+
+    conn.execute(
+        "create table if not exists bookmarks ( 
+            id      integer primary key,
+            incread integer not null,
+            name    text not null,
+            offset  integer not null
+    )",
+        [],
+    )?;
+
+    conn.execute(
+        "create table if not exists read_sections ( 
+            incread      integer not null,
+            offset_start integer not null,
+            offset_end   integer not null
+    )",
+        [],
+    )?;
+
+    conn.execute(
+        "create table if not exists provenance ( 
+            incread integer primary key,
+            origin  text not null,
+            title   text not null,
+            author  text not null
+    )",
+        [],
+    )?;
+
+    conn.execute(
+        "create table if not exists occlusions ( 
+            card   integer primary key,
+            image  text not null,
+            masks  text not null,
+            target integer not null
+    )",
+        [],
+    )?;
+
+    conn.execute(
+        "create table if not exists sessions ( 
+            start        integer not null,
+            end          integer not null,
+            reviews      integer not null,
+            pending      integer not null,
+            unfinished   integer not null,
+            incread      integer not null,
+            grade_none   integer not null,
+            grade_failed integer not null,
+            grade_decent integer not null,
+            grade_easy   integer not null,
+            resolved     integer not null,
+            extracts     integer not null,
+            clozes       integer not null
+    )",
+        [],
+    )?;
+
+    conn.execute(
+        "create table if not exists leeches ( 
+            id     integer not null
+    )",
+        [],
+    )?;
+
+    conn.execute(
+        "create table if not exists daily_counts ( 
+            day       integer not null,
+            topic     integer not null,
+            new_cards integer not null,
+            reviews   integer not null,
+            incread   integer not null
+    )",
+        [],
+    )?;
+    add_column(
+        &conn,
+        "daily_counts",
+        "incread",
+        "integer not null default 0",
+    )?;
 
     let conn = Arc::new(Mutex::new(conn));
 
@@ -255,6 +358,46 @@ In the future there will be many more ways of getting text in here.
     Ok(new_db)
 }
 
+/// Adds a column to a table that was created by an older version of speki.
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let exists = conn
+        .prepare(&format!(
+            "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1",
+            table
+        ))?
+        .exists([column])?;
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// Runs `f` in a transaction that's rolled back if it fails. The connection isn't held in the
+/// meantime, so `f` can use the functions that lock it themselves.
+/// Many of those unwrap their errors, so a panic in `f` rolls back as well before it carries on.
+pub fn in_transaction<T>(
+    conn: &Arc<Mutex<Connection>>,
+    f: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    conn.lock().unwrap().execute_batch("BEGIN")?;
+    let result = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result,
+        Err(payload) => {
+            // The panic may have happened while the connection was locked.
+            let conn = conn.lock().unwrap_or_else(PoisonError::into_inner);
+            let _ = conn.execute_batch("ROLLBACK");
+            drop(conn);
+            panic::resume_unwind(payload);
+        }
+    };
+    let end = if result.is_ok() { "COMMIT" } else { "ROLLBACK" };
+    conn.lock().unwrap().execute_batch(end)?;
+    result
+}
+
 #[allow(non_camel_case_types)]
 #[derive(Debug)]
 pub enum cardColumns {
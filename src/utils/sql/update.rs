@@ -2,10 +2,13 @@ use super::fetch::cards::{get_inc_skipduration, get_skipduration};
 use super::fetch::*;
 use crate::utils::aliases::*;
 use crate::utils::card::CardType;
+use crate::utils::interval::inc_interval;
+use crate::utils::media::CardMedia;
 use crate::widgets::textinput::CursorPos;
 use rand::prelude::*;
 use rusqlite::{params, Connection, Result, ToSql};
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -50,6 +53,15 @@ pub fn update_card_answer(conn: &Arc<Mutex<Connection>>, id: CardID, answer: Str
     update_card(conn, "cards", "answer", answer, [id]).unwrap()
 }
 
+pub fn set_card_media(conn: &Arc<Mutex<Connection>>, id: CardID, media: &CardMedia) {
+    let path =
+        |path: &Option<PathBuf>| path.as_ref().map(|path| path.to_string_lossy().to_string());
+    update_card(conn, "cards", "frontaudio", path(&media.frontaudio), [id]).unwrap();
+    update_card(conn, "cards", "backaudio", path(&media.backaudio), [id]).unwrap();
+    update_card(conn, "cards", "frontimg", path(&media.frontimage), [id]).unwrap();
+    update_card(conn, "cards", "backimg", path(&media.backimage), [id]).unwrap();
+}
+
 pub fn update_strength(conn: &Arc<Mutex<Connection>>, id: CardID, strength: f32) {
     update_card(conn, "finished_cards", "strength", strength, [id]).unwrap()
 }
@@ -69,6 +81,10 @@ pub fn update_position(conn: &Arc<Mutex<Connection>>, id: CardID, position: u32)
     update_card(conn, "pending_cards", "position", position, [id]).unwrap()
 }
 
+pub fn set_priority(conn: &Arc<Mutex<Connection>>, id: CardID, priority: u32) {
+    update_card(conn, "cards", "priority", priority, [id]).unwrap()
+}
+
 pub fn update_topic(conn: &Arc<Mutex<Connection>>, id: CardID, topic_id: TopicID) {
     update_card(conn, "cards", "topic", topic_id, [id]).unwrap()
 }
@@ -161,13 +177,17 @@ pub fn update_skiptime(conn: &Arc<Mutex<Connection>>, id: CardID) {
     update_card(conn, "unfinished_cards", "skiptime", unix, [id]).unwrap();
 }
 
-pub fn double_inc_skip_duration(conn: &Arc<Mutex<Connection>>, id: IncID) -> Result<()> {
+pub fn reschedule_inc(
+    conn: &Arc<Mutex<Connection>>,
+    id: IncID,
+    priority: u32,
+    progress: f32,
+) -> Result<()> {
     update_inc_skiptime(conn, id).unwrap();
     let mut rng = rand::thread_rng();
-    let mut y: f64 = rng.gen();
-    y += 0.5; // y is now between 0.5 and 1.5
+    let jitter: f32 = rng.gen_range(0.75..1.25);
     let skipduration = get_inc_skipduration(conn, id);
-    let new_skipduration = std::cmp::max((skipduration as f64 * y * 2.0) as u32, 2);
+    let new_skipduration = inc_interval(skipduration, priority, progress, jitter);
     conn.lock()
         .unwrap()
         .prepare("UPDATE incread SET skipduration = ? WHERE id = ?")
@@ -176,6 +196,52 @@ pub fn double_inc_skip_duration(conn: &Arc<Mutex<Connection>>, id: IncID) -> Res
     Ok(())
 }
 
+pub fn set_inc_priority(conn: &Arc<Mutex<Connection>>, id: IncID, priority: u32) -> Result<()> {
+    conn.lock()
+        .unwrap()
+        .prepare("UPDATE incread SET priority = ? WHERE id = ?")
+        .unwrap()
+        .execute(params![priority, id])?;
+    Ok(())
+}
+
+pub fn set_inc_page(conn: &Arc<Mutex<Connection>>, id: IncID, page: Option<u32>) -> Result<()> {
+    conn.lock()
+        .unwrap()
+        .prepare("UPDATE incread SET page = ? WHERE id = ?")
+        .unwrap()
+        .execute(params![page, id])?;
+    Ok(())
+}
+
+/// Where the extract was taken from in its parent text, as character offsets.
+pub fn set_inc_offsets(
+    conn: &Arc<Mutex<Connection>>,
+    id: IncID,
+    range: Option<(usize, usize)>,
+) -> Result<()> {
+    conn.lock()
+        .unwrap()
+        .prepare("UPDATE incread SET offset_start = ?, offset_end = ? WHERE id = ?")
+        .unwrap()
+        .execute(params![range.map(|r| r.0), range.map(|r| r.1), id])?;
+    Ok(())
+}
+
+/// How far into the text the reader got, also marks it as read just now.
+pub fn set_inc_progress(conn: &Arc<Mutex<Connection>>, id: IncID, progress: f32) -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    conn.lock()
+        .unwrap()
+        .prepare("UPDATE incread SET progress = ?, last_read = ? WHERE id = ?")
+        .unwrap()
+        .execute(params![progress, now, id])?;
+    Ok(())
+}
+
 pub fn update_inc_skiptime(conn: &Arc<Mutex<Connection>>, id: IncID) -> Result<()> {
     let unix = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -188,3 +254,35 @@ pub fn update_inc_skiptime(conn: &Arc<Mutex<Connection>>, id: IncID) -> Result<(
         .execute(params![unix, id])?;
     Ok(())
 }
+
+/// Moves the review history, dependencies and source of a card onto another one. The source
+/// only moves if the other card doesn't have one already. Run it in a transaction along with
+/// deleting the old card, see [`super::in_transaction`].
+pub fn move_card_data(conn: &Arc<Mutex<Connection>>, from: CardID, to: CardID) -> Result<()> {
+    let guard = conn.lock().unwrap();
+    guard.execute(
+        "UPDATE revlog SET cid = ?1 WHERE cid = ?2",
+        params![to, from],
+    )?;
+    guard.execute(
+        "UPDATE dependencies SET dependent = ?1 WHERE dependent = ?2",
+        params![to, from],
+    )?;
+    guard.execute(
+        "UPDATE dependencies SET dependency = ?1 WHERE dependency = ?2",
+        params![to, from],
+    )?;
+    guard.execute("DELETE FROM dependencies WHERE dependent = dependency", [])?;
+    guard.execute(
+        "DELETE FROM dependencies WHERE rowid NOT IN
+            (SELECT min(rowid) FROM dependencies GROUP BY dependent, dependency)",
+        [],
+    )?;
+    guard.execute(
+        "UPDATE cards SET (source, page, offset_start, offset_end) =
+            (SELECT source, page, offset_start, offset_end FROM cards WHERE id = ?2)
+            WHERE id = ?1 AND source = 0",
+        params![to, from],
+    )?;
+    Ok(())
+}
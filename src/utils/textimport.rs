@@ -0,0 +1,175 @@
+use regex::Regex;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::utils::epub::Book;
+
+/// Loads a .txt, .md or .html file with its sections split by heading.
+pub fn load_document(path: &Path) -> Book {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => return Book::failed(format!("Couldn't read {}: {}", path.display(), e), path),
+    };
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let markdown = match extension.as_str() {
+        "html" | "htm" => html_to_markdown(&contents),
+        "md" | "markdown" => contents,
+        _ => {
+            let sections = vec![contents.trim().to_string()];
+            return Book::from_sections(
+                describe(&file_title(path), path),
+                sections,
+                path,
+                String::new(),
+            );
+        }
+    };
+
+    let sections = split_sections(&markdown);
+    let title = match sections.first() {
+        Some((title, _)) if !title.is_empty() => title.clone(),
+        _ => file_title(path),
+    };
+    let sections = sections
+        .into_iter()
+        .map(|(title, body)| {
+            let body = strip_markdown(&body);
+            if title.is_empty() {
+                body
+            } else {
+                format!("{}\n\n{}", title, body)
+            }
+        })
+        .collect();
+    Book::from_sections(describe(&title, path), sections, path, String::new())
+}
+
+fn describe(title: &str, path: &Path) -> String {
+    format!("{}\n\n{}", title, path.display())
+}
+
+fn file_title(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// Splits markdown into (heading, body) pairs. Text before the first heading gets an empty heading.
+pub fn split_sections(markdown: &str) -> Vec<(String, String)> {
+    let mut sections = vec![];
+    let mut title = String::new();
+    let mut body = String::new();
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+        }
+        if !in_code && trimmed.starts_with('#') {
+            let heading = trimmed.trim_start_matches('#');
+            if heading.is_empty() || heading.starts_with(' ') {
+                if !title.is_empty() || !body.trim().is_empty() {
+                    sections.push((title, body.trim().to_string()));
+                }
+                title = strip_markdown(heading.trim());
+                body = String::new();
+                continue;
+            }
+        }
+        body.push_str(line);
+        body.push('\n');
+    }
+    if !title.is_empty() || !body.trim().is_empty() || sections.is_empty() {
+        sections.push((title, body.trim().to_string()));
+    }
+    sections
+}
+
+/// Removes the most common inline markdown syntax, keeping the text.
+pub fn strip_markdown(text: &str) -> String {
+    static IMAGE: OnceLock<Regex> = OnceLock::new();
+    let image = IMAGE.get_or_init(|| Regex::new(r"!\[([^\]]*)\]\([^)]*\)").unwrap());
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    let link = LINK.get_or_init(|| Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap());
+    static STRONG: OnceLock<Regex> = OnceLock::new();
+    let strong = STRONG.get_or_init(|| Regex::new(r"(\*\*|__)(\S.*?)(\*\*|__)").unwrap());
+    static EMPHASIS: OnceLock<Regex> = OnceLock::new();
+    let emphasis = EMPHASIS.get_or_init(|| Regex::new(r"\*(\S[^*]*?)\*").unwrap());
+    static CODE: OnceLock<Regex> = OnceLock::new();
+    let code = CODE.get_or_init(|| Regex::new(r"`([^`\n]+)`").unwrap());
+
+    let text = image.replace_all(text, "$1");
+    let text = link.replace_all(&text, "$1");
+    let text = strong.replace_all(&text, "$2");
+    let text = emphasis.replace_all(&text, "$1");
+    code.replace_all(&text, "$1").to_string()
+}
+
+/// Strips html tags, keeping headings as markdown headings so the document can be split up.
+pub fn html_to_markdown(html: &str) -> String {
+    static SKIPPED: OnceLock<Regex> = OnceLock::new();
+    let skipped = SKIPPED.get_or_init(|| {
+        Regex::new(r"(?is)<(script|style|head)[^>]*>.*?</(script|style|head)>").unwrap()
+    });
+    static COMMENTS: OnceLock<Regex> = OnceLock::new();
+    let comments = COMMENTS.get_or_init(|| Regex::new(r"(?s)<!--.*?-->").unwrap());
+    static HEADING: OnceLock<Regex> = OnceLock::new();
+    let heading = HEADING.get_or_init(|| Regex::new(r"(?i)<h([1-6])[^>]*>").unwrap());
+    static BLOCK: OnceLock<Regex> = OnceLock::new();
+    let block = BLOCK.get_or_init(|| {
+        Regex::new(r"(?i)</?(p|div|br|li|tr|h[1-6]|ul|ol|table|section|article|blockquote)[^>]*>")
+            .unwrap()
+    });
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    let tag = TAG.get_or_init(|| Regex::new(r"<[^>]*>").unwrap());
+    static BLANKLINES: OnceLock<Regex> = OnceLock::new();
+    let blanklines = BLANKLINES.get_or_init(|| Regex::new(r"\n[ \t]*\n(\s*\n)+").unwrap());
+
+    let text = skipped.replace_all(html, "");
+    let text = comments.replace_all(&text, "");
+    let text = heading.replace_all(&text, |caps: &regex::Captures| {
+        let level: usize = caps[1].parse().unwrap();
+        format!("\n\n{} ", "#".repeat(level))
+    });
+    let text = block.replace_all(&text, "\n");
+    let text = tag.replace_all(&text, "");
+    let text = decode_entities(&text);
+    let text: String = text
+        .lines()
+        .map(|line| line.trim())
+        .collect::<Vec<&str>>()
+        .join("\n");
+    blanklines.replace_all(&text, "\n\n").trim().to_string()
+}
+
+/// Decodes the common named entities along with numeric ones like `&#8217;` and `&#x2019;`.
+pub fn decode_entities(text: &str) -> String {
+    static NUMERIC: OnceLock<Regex> = OnceLock::new();
+    let numeric = NUMERIC.get_or_init(|| Regex::new(r"&#(x[0-9a-fA-F]+|[0-9]+);").unwrap());
+    let text = numeric.replace_all(text, |caps: &regex::Captures| {
+        let code = match caps[1].strip_prefix('x') {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => caps[1].parse().ok(),
+        };
+        code.and_then(char::from_u32)
+            .map(String::from)
+            .unwrap_or_default()
+    });
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&mdash;", "—")
+        .replace("&ndash;", "–")
+        .replace("&hellip;", "…")
+        .replace("&amp;", "&")
+}
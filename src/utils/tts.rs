@@ -0,0 +1,207 @@
+use crate::app::AppData;
+use crate::utils::aliases::*;
+use crate::utils::card::Card;
+use crate::utils::media::Side;
+use crate::utils::misc::queue_audio;
+use crate::utils::sql::fetch::get_topics;
+use serde_derive::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Which sides of a card are read out loud.
+#[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TtsSides {
+    Question,
+    Answer,
+    #[default]
+    Both,
+}
+
+impl TtsSides {
+    pub fn includes(self, side: Side) -> bool {
+        matches!(
+            (self, side),
+            (Self::Both, _) | (Self::Question, Side::Front) | (Self::Answer, Side::Back)
+        )
+    }
+}
+
+/// Set under `[tts]` in the config file. An empty command turns text-to-speech off.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TtsConfig {
+    /// The engine to run, `{output}` is replaced with the wav file to write and `{voice}` with
+    /// the voice. The text is passed in place of `{text}`, or on stdin if it's left out.
+    pub command: String,
+    pub voice: String,
+    pub sides: TtsSides,
+    /// Reads out cards of every topic with the default voice when they're reviewed.
+    pub autoplay: bool,
+    /// Topic names mapped to the voice their cards are read out with, these are read out
+    /// even without autoplay.
+    pub voices: HashMap<String, String>,
+}
+
+impl Default for TtsConfig {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            voice: "en".to_string(),
+            sides: TtsSides::Both,
+            autoplay: false,
+            voices: HashMap::new(),
+        }
+    }
+}
+
+impl TtsConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.command.trim().is_empty()
+    }
+
+    /// The voice for a topic, if its cards are to be read out when reviewed.
+    pub fn autoplay_voice(&self, topic: &str) -> Option<&str> {
+        match self.voices.get(topic) {
+            Some(voice) => Some(voice),
+            None if self.autoplay => Some(&self.voice),
+            None => None,
+        }
+    }
+
+    pub fn voice(&self, topic: &str) -> &str {
+        self.voices.get(topic).unwrap_or(&self.voice)
+    }
+}
+
+/// Where the speech for a text is cached, so it's only synthesized once.
+pub fn cache_path(media: &Path, text: &str, voice: &str) -> PathBuf {
+    let hash = format!("{:x}", Sha256::digest(format!("{}\0{}", voice, text)));
+    media.join(format!("tts-{}.wav", &hash[..32]))
+}
+
+/// Runs the engine unless the speech is already cached, returns the wav file.
+pub fn synthesize(
+    config: &TtsConfig,
+    media: &Path,
+    text: &str,
+    voice: &str,
+) -> io::Result<PathBuf> {
+    let output = cache_path(media, text, voice);
+    if output.exists() {
+        return Ok(output);
+    }
+    std::fs::create_dir_all(media)?;
+
+    let mut words = config.command.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no tts command"))?;
+    let output_str = output.to_string_lossy();
+    let mut text_in_args = false;
+    let args: Vec<String> = words
+        .map(|word| {
+            text_in_args |= word.contains("{text}");
+            word.replace("{output}", &output_str)
+                .replace("{voice}", voice)
+                .replace("{text}", text)
+        })
+        .collect();
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    if !text_in_args {
+        // An engine that quits early fails below anyway.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    drop(stdin);
+
+    let status = child.wait()?;
+    if !status.success() || !output.exists() {
+        let _ = std::fs::remove_file(&output);
+        return Err(io::Error::other(format!("tts command failed: {}", status)));
+    }
+    Ok(output)
+}
+
+/// Something to play, speech is only synthesized when it's about to be played.
+pub enum Sound {
+    File(PathBuf),
+    Speech { text: String, voice: String },
+}
+
+/// Plays the sounds one after another. The engine can take a while to synthesize the speech, so
+/// that happens on another thread and the sounds start once they're all there.
+pub fn play_sounds(appdata: &AppData, sounds: Vec<Sound>) {
+    let audio = match &appdata.audio {
+        Some(audio) => audio,
+        None => return,
+    };
+    if sounds.is_empty() {
+        return;
+    }
+    let handle = audio.handle.clone();
+    let volume = audio.volume;
+    let config = appdata.config.tts.clone();
+    let media = appdata.paths.media.clone();
+    std::thread::spawn(move || {
+        let paths = sounds
+            .into_iter()
+            .filter_map(|sound| match sound {
+                Sound::File(path) => Some(path),
+                Sound::Speech { text, voice } => synthesize(&config, &media, &text, &voice).ok(),
+            })
+            .collect();
+        queue_audio(&handle, volume, paths);
+    });
+}
+
+pub fn speak(appdata: &AppData, text: &str, voice: &str) {
+    let config = &appdata.config.tts;
+    if !config.is_enabled() || text.trim().is_empty() {
+        return;
+    }
+    let speech = Sound::Speech {
+        text: text.to_string(),
+        voice: voice.to_string(),
+    };
+    play_sounds(appdata, vec![speech]);
+}
+
+pub fn topic_name(appdata: &AppData, topic: TopicID) -> String {
+    get_topics(&appdata.conn)
+        .unwrap()
+        .into_iter()
+        .find(|candidate| candidate.id == topic)
+        .map(|topic| topic.name)
+        .unwrap_or_default()
+}
+
+/// The speech of a side of a card being reviewed, if the config asks for it to be read out.
+pub fn autoplay_speech(appdata: &AppData, card: &Card, side: Side) -> Option<Sound> {
+    let config = &appdata.config.tts;
+    if !config.is_enabled() || !config.sides.includes(side) {
+        return None;
+    }
+    let topic = topic_name(appdata, card.topic);
+    let voice = config.autoplay_voice(&topic)?;
+    let text = match side {
+        Side::Front => &card.question,
+        Side::Back => &card.answer,
+    };
+    if text.trim().is_empty() {
+        return None;
+    }
+    Some(Sound::Speech {
+        text: text.clone(),
+        voice: voice.to_string(),
+    })
+}
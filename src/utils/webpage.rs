@@ -0,0 +1,414 @@
+use crate::utils::epub::{tidy_markup, write_markup, Book, Chapter, MarkupElement, MarkupNode};
+use crate::utils::incread::Provenance;
+use crate::utils::textimport::decode_entities;
+use crate::utils::wiki::{HttpGet, WikiResult};
+use crate::widgets::textinput::markup::is_heading;
+use regex::Regex;
+use reqwest::Url;
+use std::sync::OnceLock;
+
+/// The main content of a web page as markup, with `#` headings and `[text](url)` links.
+pub struct WebPage {
+    pub url: String,
+    pub title: String,
+    pub author: String,
+    pub text: String,
+}
+
+impl WebPage {
+    pub fn into_book(self) -> Book {
+        Book {
+            desc: format!("{}\n\n{}", self.title, self.url),
+            chapters: page_sections(&self.title, &self.text),
+            skipped: vec![],
+            provenance: Provenance {
+                origin: self.url,
+                title: self.title,
+                author: self.author,
+            },
+        }
+    }
+}
+
+/// Downloads the page and extracts its main content. Urls without a scheme are taken to be https.
+pub fn fetch_webpage(client: &dyn HttpGet, url: &str) -> WikiResult<WebPage> {
+    let url = url.trim();
+    let url = if url.contains("://") {
+        Url::parse(url)?
+    } else {
+        Url::parse(&format!("https://{}", url))?
+    };
+    let html = client.get(url.as_str(), &[])?;
+    Ok(readable_page(&html, &url))
+}
+
+/// Picks out the main content of the page the way reader modes do, by scoring the elements by
+/// how much paragraph text they hold and leaving out navigation, sidebars and the like.
+pub fn readable_page(html: &str, url: &Url) -> WebPage {
+    let mut root = parse_html(html);
+    let meta = |key: &str| find_meta(&root, key).filter(|value| !value.is_empty());
+    let title = meta("og:title")
+        .or_else(|| find(&root, "title").map(|title| collapse(&title.text())))
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| url.to_string());
+    let author = meta("author").unwrap_or_default();
+
+    prune(&mut root);
+    root.measure();
+    let body = find(&root, "body").unwrap_or(&root);
+    let content = best_candidate(body).unwrap_or(body);
+
+    // Links are resolved against the url of the page, leaving out the ones within it.
+    let link = |element: &Element| {
+        element
+            .attr("href")
+            .filter(|href| !href.starts_with('#') && !href.starts_with("javascript:"))
+            .and_then(|href| url.join(href.trim()).ok())
+            .map(String::from)
+    };
+    let mut text = String::new();
+    let mut lists = vec![];
+    for node in content.child_nodes() {
+        write_markup(&mut text, node, &mut lists, &link);
+    }
+    WebPage {
+        url: url.to_string(),
+        title,
+        author,
+        text: tidy_markup(&text),
+    }
+}
+
+/// Splits the markup into sections by heading, nested by heading level. The text before the first
+/// heading goes under the page title.
+pub fn page_sections(title: &str, text: &str) -> Vec<Chapter> {
+    let mut sections = vec![(title.to_string(), None, String::new())];
+    for line in text.lines() {
+        if is_heading(line) {
+            let trimmed = line.trim_start();
+            let level = trimmed.len() - trimmed.trim_start_matches('#').len();
+            let heading = trimmed.trim_start_matches('#').trim().to_string();
+            sections.push((heading, Some(level), String::new()));
+        } else {
+            let body = &mut sections.last_mut().unwrap().2;
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    if sections[0].2.trim().is_empty() && sections.len() > 1 {
+        sections.remove(0);
+    }
+    let toplevel = sections
+        .iter()
+        .filter_map(|(_, level, _)| *level)
+        .min()
+        .unwrap_or(1);
+
+    sections
+        .into_iter()
+        .map(|(title, level, body)| {
+            let body = body.trim();
+            let text = if body.is_empty() {
+                format!("# {}", title)
+            } else {
+                format!("# {}\n\n{}", title, body)
+            };
+            Chapter {
+                title,
+                depth: level.map_or(0, |level| level.saturating_sub(toplevel)),
+                text,
+            }
+        })
+        .collect()
+}
+
+enum Node {
+    Text(String),
+    Element(Element),
+}
+
+struct Element {
+    name: String,
+    attributes: String,
+    children: Vec<Node>,
+    /// The length of the text with runs of whitespace counted once, set by [`Element::measure`].
+    text_len: usize,
+    /// How much of that text is inside links.
+    link_len: usize,
+    commas: usize,
+}
+
+impl Element {
+    fn new(name: &str, attributes: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            attributes: attributes.to_string(),
+            children: vec![],
+            text_len: 0,
+            link_len: 0,
+            commas: 0,
+        }
+    }
+
+    fn attr(&self, key: &str) -> Option<String> {
+        static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+        let attribute = ATTRIBUTE.get_or_init(|| {
+            Regex::new(r#"(?:^|\s)([^\s=>]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+        });
+        let caps = attribute
+            .captures_iter(&self.attributes)
+            .find(|caps| caps[1].eq_ignore_ascii_case(key))?;
+        let value = caps.get(2).or(caps.get(3)).or(caps.get(4))?;
+        Some(decode_entities(value.as_str()))
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|child| match child {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Sums up the text of the element and its descendants in one pass from the bottom up, so
+    /// that scoring the candidates doesn't walk the subtree of every element again.
+    fn measure(&mut self) {
+        let (mut text_len, mut link_len, mut commas) = (0, 0, 0);
+        for child in self.children.iter_mut() {
+            match child {
+                Node::Text(content) => {
+                    text_len += collapsed_len(content);
+                    commas += content.matches(',').count();
+                }
+                Node::Element(element) => {
+                    element.measure();
+                    text_len += element.text_len;
+                    commas += element.commas;
+                    link_len += if element.name == "a" {
+                        element.text_len
+                    } else {
+                        element.link_len
+                    };
+                }
+            }
+        }
+        self.text_len = text_len;
+        self.link_len = link_len;
+        self.commas = commas;
+    }
+}
+
+impl MarkupElement for Element {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn child_nodes(&self) -> Vec<MarkupNode<'_, Self>> {
+        self.children
+            .iter()
+            .map(|child| match child {
+                Node::Text(content) => MarkupNode::Text(content),
+                Node::Element(element) => MarkupNode::Element(element),
+            })
+            .collect()
+    }
+}
+
+/// The length of the text with every run of whitespace counted as a single space.
+fn collapsed_len(text: &str) -> usize {
+    let mut len = 0;
+    let mut in_whitespace = false;
+    for c in text.chars() {
+        if !c.is_whitespace() || !in_whitespace {
+            len += 1;
+        }
+        in_whitespace = c.is_whitespace();
+    }
+    len
+}
+
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Builds an element tree out of the html, forgiving about unclosed and stray tags.
+fn parse_html(html: &str) -> Element {
+    static SKIPPED: OnceLock<Regex> = OnceLock::new();
+    let skipped = SKIPPED.get_or_init(|| {
+        Regex::new(
+            r"(?is)<(script|style|noscript|svg|template)\b[^>]*>.*?</(script|style|noscript|svg|template)\s*>|<!--.*?-->|<![^>]*>|<\?[^>]*>",
+        )
+        .unwrap()
+    });
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    let tag = TAG.get_or_init(|| Regex::new(r"<(/?)([a-zA-Z][a-zA-Z0-9]*)([^>]*)>").unwrap());
+    let html = skipped.replace_all(html, "");
+
+    let mut stack = vec![Element::new("#root", "")];
+    let mut last = 0;
+    for caps in tag.captures_iter(&html) {
+        let whole = caps.get(0).unwrap();
+        add_text(&mut stack, &html[last..whole.start()]);
+        last = whole.end();
+        let name = caps[2].to_lowercase();
+
+        if &caps[1] == "/" {
+            if let Some(idx) = stack.iter().rposition(|element| element.name == name) {
+                while stack.len() > idx.max(1) {
+                    close(&mut stack);
+                }
+            }
+            continue;
+        }
+        // A new paragraph or list item ends the previous one.
+        if (name == "p" || name == "li") && stack.last().unwrap().name == name {
+            close(&mut stack);
+        }
+        let element = Element::new(&name, &caps[3]);
+        if VOID_ELEMENTS.contains(&name.as_str()) || caps[3].trim_end().ends_with('/') {
+            stack
+                .last_mut()
+                .unwrap()
+                .children
+                .push(Node::Element(element));
+        } else {
+            stack.push(element);
+        }
+    }
+    add_text(&mut stack, &html[last..]);
+    while stack.len() > 1 {
+        close(&mut stack);
+    }
+    stack.pop().unwrap()
+}
+
+fn add_text(stack: &mut [Element], text: &str) {
+    if !text.is_empty() {
+        let text = decode_entities(text);
+        stack.last_mut().unwrap().children.push(Node::Text(text));
+    }
+}
+
+fn close(stack: &mut Vec<Element>) {
+    let element = stack.pop().unwrap();
+    stack
+        .last_mut()
+        .unwrap()
+        .children
+        .push(Node::Element(element));
+}
+
+fn find<'a>(element: &'a Element, name: &str) -> Option<&'a Element> {
+    if element.name == name {
+        return Some(element);
+    }
+    element.elements().find_map(|child| find(child, name))
+}
+
+fn find_meta(element: &Element, key: &str) -> Option<String> {
+    if element.name == "meta" {
+        let name = element.attr("name").or_else(|| element.attr("property"));
+        if name.is_some_and(|name| name.eq_ignore_ascii_case(key)) {
+            return element.attr("content").map(|content| collapse(&content));
+        }
+    }
+    element.elements().find_map(|child| find_meta(child, key))
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+const UNLIKELY_TAGS: [&str; 10] = [
+    "head", "nav", "footer", "aside", "form", "button", "iframe", "select", "dialog", "menu",
+];
+
+fn unlikely_pattern() -> &'static Regex {
+    static UNLIKELY: OnceLock<Regex> = OnceLock::new();
+    UNLIKELY.get_or_init(|| Regex::new(r"(?i)banner|breadcrumb|comment|cookie|footer|menu|modal|nav|popup|promo|related|share|sidebar|social|sponsor|subscribe|newsletter|advert|\bads?\b").unwrap())
+}
+
+fn likely_pattern() -> &'static Regex {
+    static LIKELY: OnceLock<Regex> = OnceLock::new();
+    LIKELY
+        .get_or_init(|| Regex::new(r"(?i)article|body|content|entry|main|post|story|text").unwrap())
+}
+
+fn class_and_id(element: &Element) -> String {
+    format!(
+        "{} {}",
+        element.attr("class").unwrap_or_default(),
+        element.attr("id").unwrap_or_default()
+    )
+}
+
+/// Removes the parts of the page that are hardly ever part of the content.
+fn prune(element: &mut Element) {
+    let unlikely = unlikely_pattern();
+    let likely = likely_pattern();
+    element.children.retain(|child| match child {
+        Node::Element(child) => {
+            let names = class_and_id(child);
+            let role = child.attr("role").unwrap_or_default();
+            !(UNLIKELY_TAGS.contains(&child.name.as_str())
+                || matches!(role.as_str(), "navigation" | "complementary" | "banner")
+                || (unlikely.is_match(&names) && !likely.is_match(&names)))
+        }
+        Node::Text(_) => true,
+    });
+    for child in element.children.iter_mut() {
+        if let Node::Element(child) = child {
+            prune(child);
+        }
+    }
+}
+
+/// How much a paragraph counts towards the score of the elements around it.
+fn paragraph_score(element: &Element) -> f32 {
+    if !matches!(element.name.as_str(), "p" | "pre" | "blockquote" | "td") {
+        return 0.;
+    }
+    let length = element.text_len;
+    if length < 25 {
+        return 0.;
+    }
+    1. + element.commas as f32 + (length as f32 / 100.).min(3.)
+}
+
+fn candidate_score(element: &Element) -> f32 {
+    let mut score: f32 = 0.;
+    for child in element.elements() {
+        score += paragraph_score(child);
+        score += child.elements().map(paragraph_score).sum::<f32>() / 2.;
+    }
+    if score == 0. {
+        return 0.;
+    }
+    let names = class_and_id(element);
+    if likely_pattern().is_match(&names) {
+        score += 25.;
+    }
+    if unlikely_pattern().is_match(&names) {
+        score -= 25.;
+    }
+    score += match element.name.as_str() {
+        "article" | "main" => 10.,
+        "div" | "section" => 5.,
+        _ => 0.,
+    };
+    let link_density = element.link_len as f32 / element.text_len.max(1) as f32;
+    score * (1. - link_density)
+}
+
+fn best_candidate(element: &Element) -> Option<&Element> {
+    let mut best: Option<(&Element, f32)> = None;
+    let mut stack = vec![element];
+    while let Some(element) = stack.pop() {
+        let score = candidate_score(element);
+        if score > 0. && best.is_none_or(|(_, best)| score > best) {
+            best = Some((element, score));
+        }
+        stack.extend(element.elements());
+    }
+    best.map(|(element, _)| element)
+}
@@ -0,0 +1,171 @@
+use crate::utils::epub::Chapter;
+use regex::Regex;
+use serde_json::Value;
+use std::error::Error;
+
+pub type WikiResult<T> = Result<T, Box<dyn Error>>;
+
+/// Makes the http requests for [`Wiki`], so that it can be pointed at something other than
+/// wikipedia, like a local server in the tests.
+pub trait HttpGet {
+    fn get(&self, url: &str, params: &[(&str, &str)]) -> WikiResult<String>;
+}
+
+pub struct ReqwestClient;
+
+impl HttpGet for ReqwestClient {
+    fn get(&self, url: &str, params: &[(&str, &str)]) -> WikiResult<String> {
+        let response = reqwest::blocking::Client::new()
+            .get(url)
+            .query(params)
+            .header(reqwest::header::USER_AGENT, "speki")
+            .send()?
+            .error_for_status()?;
+        Ok(response.text()?)
+    }
+}
+
+pub enum WikiPage {
+    Article {
+        title: String,
+        text: String,
+    },
+    /// A disambiguation page, with the titles of the articles it links to.
+    Disambiguation {
+        title: String,
+        options: Vec<String>,
+    },
+    Missing,
+}
+
+pub struct Wiki {
+    client: Box<dyn HttpGet>,
+    /// Url of the api, where `{language}` is replaced with the language code.
+    api_url: String,
+    pub language: String,
+}
+
+impl Wiki {
+    pub fn new(language: &str) -> Self {
+        Self::with_client(
+            Box::new(ReqwestClient),
+            "https://{language}.wikipedia.org/w/api.php",
+            language,
+        )
+    }
+
+    pub fn with_client(client: Box<dyn HttpGet>, api_url: &str, language: &str) -> Self {
+        Self {
+            client,
+            api_url: api_url.to_string(),
+            language: language.trim().to_string(),
+        }
+    }
+
+    fn query(&self, params: &[(&str, &str)]) -> WikiResult<Value> {
+        let url = self.api_url.replace("{language}", &self.language);
+        let mut params = params.to_vec();
+        params.extend([("format", "json"), ("formatversion", "2")]);
+        let response = self.client.get(&url, &params)?;
+        Ok(serde_json::from_str(&response)?)
+    }
+
+    /// Titles of the articles matching the query, best matches first.
+    pub fn search(&self, query: &str) -> WikiResult<Vec<String>> {
+        let json = self.query(&[
+            ("action", "query"),
+            ("list", "search"),
+            ("srsearch", query),
+            ("srlimit", "20"),
+        ])?;
+        let results = json["query"]["search"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        Ok(results
+            .iter()
+            .filter_map(|result| result["title"].as_str().map(String::from))
+            .collect())
+    }
+
+    pub fn page(&self, title: &str) -> WikiResult<WikiPage> {
+        let json = self.query(&[
+            ("action", "query"),
+            ("prop", "extracts|pageprops"),
+            ("explaintext", "1"),
+            ("exsectionformat", "wiki"),
+            ("ppprop", "disambiguation"),
+            ("redirects", "1"),
+            ("titles", title),
+        ])?;
+        let page = &json["query"]["pages"][0];
+        if page.is_null() || page["missing"].as_bool().unwrap_or(false) {
+            return Ok(WikiPage::Missing);
+        }
+        let title = page["title"].as_str().unwrap_or(title).to_string();
+
+        if !page["pageprops"]["disambiguation"].is_null() {
+            let json = self.query(&[
+                ("action", "query"),
+                ("prop", "links"),
+                ("plnamespace", "0"),
+                ("pllimit", "max"),
+                ("titles", &title),
+            ])?;
+            let links = json["query"]["pages"][0]["links"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
+            let options = links
+                .iter()
+                .filter_map(|link| link["title"].as_str().map(String::from))
+                .collect();
+            return Ok(WikiPage::Disambiguation { title, options });
+        }
+
+        let text = page["extract"].as_str().unwrap_or_default().to_string();
+        Ok(WikiPage::Article { title, text })
+    }
+
+    pub fn article_url(&self, title: &str) -> String {
+        format!(
+            "https://{}.wikipedia.org/wiki/{}",
+            self.language,
+            title.replace(' ', "_")
+        )
+    }
+}
+
+/// Splits the plain text of an article into its sections, nested by heading level. Sections
+/// without any text, neither their own nor in their subsections, are left out.
+pub fn wiki_sections(title: &str, text: &str) -> Vec<Chapter> {
+    let heading = Regex::new(r"^(={2,6})\s*(.*?)\s*={2,6}$").unwrap();
+    let mut sections = vec![(title.to_string(), 0, String::new())];
+    for line in text.lines() {
+        match heading.captures(line.trim()) {
+            Some(caps) => sections.push((caps[2].to_string(), caps[1].len() - 2, String::new())),
+            None => {
+                let body = &mut sections.last_mut().unwrap().2;
+                body.push_str(line);
+                body.push('\n');
+            }
+        }
+    }
+
+    let mut chapters: Vec<Chapter> = vec![];
+    for (title, depth, body) in sections.into_iter().rev() {
+        let body = body.trim();
+        let has_children = chapters.last().is_some_and(|next| next.depth > depth);
+        if body.is_empty() && !has_children {
+            continue;
+        }
+        let text = if body.is_empty() {
+            format!("# {}", title)
+        } else {
+            format!("# {}\n\n{}", title, body)
+        };
+        chapters.push(Chapter { title, depth, text });
+    }
+    chapters.reverse();
+    chapters
+}
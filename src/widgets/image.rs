@@ -0,0 +1,206 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use tui::{
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::app::{AppData, Widget};
+use crate::utils::aliases::Pos;
+use crate::utils::graphics::{
+    cell_size, half_blocks, iterm_escape, kitty_escape, sixel_escape, ImageProtocol, Placement,
+    CELL_HEIGHT, CELL_WIDTH,
+};
+use crate::utils::image::{Image, Overlay};
+use crate::{MyKey, MyType};
+
+/// Shows an image file with overlays drawn on top of it, through the graphics protocol of the
+/// terminal or with half blocks. With images turned off it can only be opened in the external
+/// viewer.
+pub struct ImageView {
+    pub title: String,
+    pub overlays: Vec<Overlay>,
+    path: Option<PathBuf>,
+    image: Option<Result<Image, String>>,
+    area: Rect,
+    /// The last drawn image, along with what it was drawn from.
+    cache: Option<(u64, Drawn)>,
+}
+
+enum Drawn {
+    /// Escape sequence of a graphics protocol and the area it covers.
+    Escape(Rc<String>, Rect),
+    Blocks(Vec<Spans<'static>>),
+}
+
+impl ImageView {
+    pub fn new<T: Into<String>>(title: T) -> Self {
+        Self {
+            title: title.into(),
+            overlays: vec![],
+            path: None,
+            image: None,
+            area: Rect::default(),
+            cache: None,
+        }
+    }
+
+    pub fn load(&mut self, path: Option<PathBuf>) {
+        if path == self.path {
+            return;
+        }
+        self.image = path
+            .as_ref()
+            .map(|path| Image::open(path).map_err(|e| e.to_string()));
+        self.path = path;
+        self.cache = None;
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.path.is_some()
+    }
+
+    pub fn image(&self) -> Option<&Image> {
+        self.image.as_ref()?.as_ref().ok()
+    }
+
+    /// The image with the overlays drawn on it.
+    pub fn composed(&self) -> Option<Image> {
+        let mut image = self.image()?.clone();
+        for overlay in &self.overlays {
+            image.draw(overlay);
+        }
+        Some(image)
+    }
+
+    /// Opens the image, as it's shown here, with the viewer command from the config.
+    pub fn open_external(&self, appdata: &AppData) {
+        let path = match (self.composed(), &self.path) {
+            (Some(image), _) if !self.overlays.is_empty() => {
+                let path = std::env::temp_dir().join("speki_image.png");
+                if std::fs::write(&path, image.to_png()).is_err() {
+                    return;
+                }
+                path
+            }
+            // Showing an image that couldn't be masked would give the answer away.
+            (None, _) if !self.overlays.is_empty() => return,
+            (_, Some(path)) => path.clone(),
+            (_, None) => return,
+        };
+        let _ = std::process::Command::new(&appdata.config.images.viewer)
+            .arg(path)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn();
+    }
+
+    fn signature(&self, area: Rect, protocol: ImageProtocol) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.path.hash(&mut hasher);
+        format!("{:?}{:?}{:?}", self.overlays, area, protocol).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn message(&self) -> String {
+        let name = self
+            .path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match &self.image {
+            Some(Err(e)) => format!("{}\n\nCouldn't show the image: {}", name, e),
+            _ => format!("{}\n\nOpen it in the image viewer with Alt+o", name),
+        }
+    }
+}
+
+impl Widget for ImageView {
+    fn get_area(&self) -> Rect {
+        self.area
+    }
+
+    fn set_area(&mut self, area: Rect) {
+        self.area = area;
+    }
+
+    fn keyhandler(&mut self, appdata: &AppData, key: MyKey) {
+        if key == MyKey::Alt('o') {
+            self.open_external(appdata);
+        }
+    }
+
+    fn render(&mut self, f: &mut tui::Frame<MyType>, appdata: &AppData, cursor: &Pos) {
+        let bordercolor = if self.is_selected(cursor) {
+            Color::Red
+        } else {
+            Color::White
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(bordercolor))
+            .title(Span::styled(
+                self.title.clone(),
+                Style::default().fg(Color::Magenta),
+            ));
+        let inner = block.inner(self.area);
+        f.render_widget(block, self.area);
+        if !self.is_loaded() || inner.width == 0 || inner.height == 0 {
+            return;
+        }
+
+        let protocol = appdata.graphics.borrow().protocol;
+        let signature = self.signature(inner, protocol);
+        if protocol == ImageProtocol::None {
+            self.cache = None;
+        } else if self.cache.as_ref().map(|(id, _)| *id) != Some(signature) {
+            self.cache = self.composed().map(|image| {
+                let (columns, rows) = cell_size(&image, inner);
+                let area = Rect::new(inner.x, inner.y, columns, rows);
+                let drawn = match protocol {
+                    ImageProtocol::Kitty => {
+                        Drawn::Escape(Rc::new(kitty_escape(&image, columns, rows)), area)
+                    }
+                    ImageProtocol::Iterm => {
+                        Drawn::Escape(Rc::new(iterm_escape(&image, columns, rows)), area)
+                    }
+                    ImageProtocol::Sixel => {
+                        let (width, height) = image.fitted_size(
+                            columns as usize * CELL_WIDTH,
+                            rows as usize * CELL_HEIGHT,
+                        );
+                        let escape = sixel_escape(&image.resize(width, height));
+                        Drawn::Escape(Rc::new(escape), area)
+                    }
+                    _ => Drawn::Blocks(half_blocks(&image, inner)),
+                };
+                (signature, drawn)
+            });
+        }
+
+        match &self.cache {
+            Some((id, Drawn::Escape(escape, area))) => {
+                appdata.graphics.borrow_mut().place(Placement {
+                    area: *area,
+                    id: *id,
+                    escape: escape.clone(),
+                })
+            }
+            Some((_, Drawn::Blocks(lines))) => {
+                f.render_widget(Paragraph::new(lines.clone()), inner);
+            }
+            None => {
+                let paragraph = Paragraph::new(self.message())
+                    .alignment(Alignment::Center)
+                    .wrap(Wrap { trim: true });
+                f.render_widget(paragraph, inner);
+            }
+        }
+    }
+}
@@ -9,6 +9,7 @@ pub mod progress_bar;
 //pub mod optionlist;
 pub mod cardstatus;
 pub mod checkboxlist;
+pub mod image;
 pub mod infobox;
 pub mod textinput;
 pub mod topics;
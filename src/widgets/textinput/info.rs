@@ -26,6 +26,47 @@ impl Field {
     pub fn selection_exists(&self) -> bool {
         self.startselect.is_some()
     }
+
+    /// Whichever end of the selection comes first in the text.
+    pub fn selection_start(&self) -> Option<CursorPos> {
+        self.startselect
+            .map(|start| std::cmp::min_by_key(start, self.cursor, |pos| (pos.row, pos.column)))
+    }
+
+    /// Character offsets of the selection in the whole text, the end is exclusive.
+    pub fn selection_range(&self) -> Option<(usize, usize)> {
+        let start = self.selection_start()?;
+        let end = std::cmp::max_by_key(self.startselect?, self.cursor, |pos| (pos.row, pos.column));
+        Some((self.offset_of(&start), self.offset_of(&end) + 1))
+    }
+
+    /// Character offset of the position in the whole text, rows are joined by a newline.
+    pub fn offset_of(&self, pos: &CursorPos) -> usize {
+        let rows: usize = (0..pos.row.min(self.text.len()))
+            .map(|row| self.get_rowlen(row) + 1)
+            .sum();
+        rows + pos.column
+    }
+
+    pub fn pos_at_offset(&self, offset: usize) -> CursorPos {
+        let mut offset = offset;
+        for row in 0..self.text.len() {
+            let rowlen = self.get_rowlen(row);
+            if offset <= rowlen {
+                return CursorPos {
+                    row,
+                    column: offset,
+                };
+            }
+            offset -= rowlen + 1;
+        }
+        let row = self.text.len() - 1;
+        CursorPos {
+            row,
+            column: self.get_rowlen(row),
+        }
+    }
+
     pub fn find_grapheme_bytepos(mystr: &String, column: usize) -> usize {
         let mut boundary = 0;
         let mut cursor = unicode_segmentation::GraphemeCursor::new(0, mystr.len(), false);
@@ -0,0 +1,139 @@
+use regex::Regex;
+use std::sync::OnceLock;
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Styles rows of lightweight markup, like [`markup_spans`], with the rows between two
+/// ```` ``` ```` fences shown as a code block.
+pub fn markup_rows<S: AsRef<str>>(rows: &[S]) -> Vec<Spans<'static>> {
+    let mut in_code = false;
+    rows.iter()
+        .map(|row| {
+            let row = row.as_ref();
+            if is_fence(row) {
+                in_code = !in_code;
+                Spans::from(Span::styled(
+                    row.to_string(),
+                    code().add_modifier(Modifier::DIM),
+                ))
+            } else if in_code {
+                Spans::from(Span::styled(row.to_string(), code()))
+            } else {
+                markup_spans(row)
+            }
+        })
+        .collect()
+}
+
+/// Styles a row of lightweight markup. The markup characters are kept so that the cursor
+/// positions still line up with the text.
+///
+/// Rows starting with `#` are headings, `- ` and `1. ` start list items, and `*emphasis*`,
+/// `**strong**` and `` `code` `` are styled inline.
+pub fn markup_spans(row: &str) -> Spans<'static> {
+    static BULLET: OnceLock<Regex> = OnceLock::new();
+    static INLINE: OnceLock<Regex> = OnceLock::new();
+    if is_heading(row) {
+        let style = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD);
+        return Spans::from(Span::styled(row.to_string(), style));
+    }
+
+    let mut spans = vec![];
+    let mut rest = row;
+    let bullet = BULLET.get_or_init(|| Regex::new(r"^\s*(-|\*|\d+\.) ").unwrap());
+    if let Some(found) = bullet.find(row) {
+        spans.push(Span::styled(
+            found.as_str().to_string(),
+            Style::default().fg(Color::Yellow),
+        ));
+        rest = &row[found.end()..];
+    }
+
+    let inline = INLINE.get_or_init(|| {
+        Regex::new(r"`[^`]+`|\*\*\S(?:.*?\S)?\*\*|\*[^*\s](?:[^*]*[^*\s])?\*").unwrap()
+    });
+    let mut last = 0;
+    for found in inline.find_iter(rest) {
+        if found.start() > last {
+            spans.push(Span::raw(rest[last..found.start()].to_string()));
+        }
+        let style = if found.as_str().starts_with('`') {
+            code()
+        } else if found.as_str().starts_with("**") {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().add_modifier(Modifier::ITALIC)
+        };
+        spans.push(Span::styled(found.as_str().to_string(), style));
+        last = found.end();
+    }
+    if last < rest.len() || spans.is_empty() {
+        spans.push(Span::raw(rest[last..].to_string()));
+    }
+    Spans::from(spans)
+}
+
+pub fn is_heading(row: &str) -> bool {
+    let trimmed = row.trim_start();
+    let hashes = trimmed.len() - trimmed.trim_start_matches('#').len();
+    hashes > 0 && trimmed[hashes..].starts_with(' ')
+}
+
+/// Opens or closes a code block.
+pub fn is_fence(row: &str) -> bool {
+    row.trim_start().starts_with("```")
+}
+
+pub fn code() -> Style {
+    Style::default().fg(Color::LightGreen)
+}
+
+pub fn extracted() -> Style {
+    Style::default()
+        .fg(Color::Green)
+        .add_modifier(Modifier::UNDERLINED)
+}
+
+pub fn marked() -> Style {
+    Style::default().bg(Color::Blue)
+}
+
+pub fn read() -> Style {
+    Style::default().add_modifier(Modifier::DIM)
+}
+
+/// Highlights the given column ranges of a row, the ends are exclusive.
+pub fn highlight(
+    spans: Spans<'static>,
+    ranges: &[(usize, usize)],
+    highlighted: Style,
+) -> Spans<'static> {
+    if ranges.is_empty() {
+        return spans;
+    }
+    let mut result: Vec<Span> = vec![];
+    let mut column = 0;
+    for span in spans.0 {
+        for grapheme in span.content.graphemes(true) {
+            let style = if ranges
+                .iter()
+                .any(|(start, end)| *start <= column && column < *end)
+            {
+                span.style.patch(highlighted)
+            } else {
+                span.style
+            };
+            match result.last_mut() {
+                Some(last) if last.style == style => last.content.to_mut().push_str(grapheme),
+                _ => result.push(Span::styled(grapheme.to_string(), style)),
+            }
+            column += 1;
+        }
+    }
+    Spans::from(result)
+}
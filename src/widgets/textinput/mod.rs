@@ -19,6 +19,7 @@ use tui::{
 
 pub mod info;
 pub mod keyhandler;
+pub mod markup;
 pub mod navigation;
 
 /*
@@ -61,6 +62,12 @@ pub struct Field {
     pub title: String,
     preferredcol: Option<usize>,
     area: Rect,
+    /// Character ranges of the text to highlight, like the parts that were already extracted.
+    pub highlights: Vec<(usize, usize)>,
+    /// Character ranges marked for an action that hasn't happened yet, like clozes to be made.
+    pub marks: Vec<(usize, usize)>,
+    /// Character ranges that are dimmed, like the sections that have been read.
+    pub dimmed: Vec<(usize, usize)>,
 }
 
 #[derive(Debug, Clone)]
@@ -83,6 +90,9 @@ impl Default for Field {
             title: "".to_string(),
             preferredcol: None,
             area: Rect::default(),
+            highlights: vec![],
+            marks: vec![],
+            dimmed: vec![],
         };
         myfield.set_insert_mode();
         myfield
@@ -358,8 +368,26 @@ impl Field {
             f.set_cursor(x, y);
         }
 
-        for text in self.text.iter() {
-            spanvec.push(Spans::from(text.clone()));
+        let mut offset = 0;
+        let rows = markup::markup_rows(&self.text);
+        for (text, spans) in self.text.iter().zip(rows) {
+            let rowlen = text.graphemes(true).count();
+            let row_ranges = |ranges: &Vec<(usize, usize)>| -> Vec<(usize, usize)> {
+                ranges
+                    .iter()
+                    .filter(|(start, end)| *start < offset + rowlen && *end > offset)
+                    .map(|(start, end)| (start.saturating_sub(offset), end - offset))
+                    .collect()
+            };
+            let spans = markup::highlight(spans, &row_ranges(&self.dimmed), markup::read());
+            let spans =
+                markup::highlight(spans, &row_ranges(&self.highlights), markup::extracted());
+            spanvec.push(markup::highlight(
+                spans,
+                &row_ranges(&self.marks),
+                markup::marked(),
+            ));
+            offset += rowlen + 1;
         }
         spanvec
     }
@@ -618,13 +646,13 @@ impl<'a> TuiWidget for Paraclone<'a> {
         let mut line_composer: Box<dyn LineComposer> =
             { Box::new(WordWrapper::new(&mut styled, text_area.width)) };
         let mut y = 0;
-        let mut thestyle = Style::default();
         while let Some((current_line, _)) = line_composer.next_line() {
             let mut x: u16 = 0;
             if y >= self.scroll.y {
-                for StyledGrapheme { symbol, .. } in current_line {
+                for StyledGrapheme { symbol, style } in current_line {
+                    let mut thestyle = *style;
                     if let Some(selvec) = &self.selection {
-                        thestyle = stylegetter(y as usize, x as usize, selvec)
+                        thestyle = thestyle.patch(stylegetter(y as usize, x as usize, selvec))
                     }
 
                     buf.get_mut(text_area.left() + x, text_area.top() + y - self.scroll.y)
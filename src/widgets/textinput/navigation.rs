@@ -3,6 +3,10 @@ use super::Field;
 use unicode_segmentation::UnicodeSegmentation;
 
 impl Field {
+    pub fn jump_to_offset(&mut self, offset: usize) {
+        self.cursor = self.pos_at_offset(offset);
+        self.preferredcol = None;
+    }
     pub fn jump_forward(&mut self, jmp: usize) {
         self.cursor.column = std::cmp::min(
             self.text[self.cursor.row].graphemes(true).count(),